@@ -1,12 +1,63 @@
 use winnow::{
-    ascii::{space1, till_line_ending},
-    combinator::preceded,
+    ascii::{space0, space1, till_line_ending},
+    combinator::{alt, eof, preceded, terminated},
+    error::{ErrMode, FromExternalError as _},
+    token::{rest, take_until},
     PResult, Parser,
 };
 
-pub fn parse_account_directive<'s>(input: &mut &'s str) -> PResult<&'s str> {
-    preceded(("account", space1), till_line_ending)
-        .verify(|account_name: &str| !account_name.contains("  "))
-        .context(winnow::error::StrContext::Label("account name"))
+use crate::{
+    comment::parsers::parse_transaction_comment, transaction::parsers::parse_comments_tags,
+};
+
+use super::types::AccountType;
+
+fn parse_account_type_comment(input: &mut &str) -> PResult<Option<AccountType>> {
+    let tags = preceded(
+        space1,
+        alt((
+            parse_transaction_comment
+                .and_then(parse_comments_tags)
+                .map(|(_, tags)| tags),
+            parse_transaction_comment.value(vec![]),
+        )),
+    )
+    .parse_next(input)?;
+
+    match tags.iter().find(|t| t.name == "type") {
+        Some(tag) => tag
+            .value
+            .as_deref()
+            .unwrap_or_default()
+            .parse::<AccountType>()
+            .map(Some)
+            .map_err(|e| {
+                ErrMode::from_external_error(input, winnow::error::ErrorKind::Verify, e).cut()
+            }),
+        None => Ok(None),
+    }
+}
+
+pub fn parse_account_directive<'s>(input: &mut &'s str) -> PResult<(&'s str, Option<AccountType>)> {
+    preceded(
+        ("account", space1),
+        till_line_ending.and_then((
+            alt((take_until(1.., "  "), rest)),
+            terminated(alt((parse_account_type_comment, eof.value(None))), eof),
+        )),
+    )
+    .context(winnow::error::StrContext::Label("account name"))
+    .parse_next(input)
+}
+
+pub fn parse_apply_account_directive<'s>(input: &mut &'s str) -> PResult<&'s str> {
+    preceded(("apply", space1, "account", space1), till_line_ending)
+        .map(str::trim)
+        .verify(|account_name: &str| !account_name.is_empty() && !account_name.contains("  "))
+        .context(winnow::error::StrContext::Label("apply account directive"))
         .parse_next(input)
 }
+
+pub fn parse_end_apply_account_directive(input: &mut &str) -> PResult<()> {
+    terminated(("end", space1, "apply", space1, "account").void(), space0).parse_next(input)
+}
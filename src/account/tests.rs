@@ -1,6 +1,19 @@
+use std::collections::BTreeMap;
+
 use rstest::rstest;
+use rust_decimal_macros::dec;
 
-use crate::account::parsers::parse_account_directive;
+use crate::{
+    account::{
+        parsers::{
+            parse_account_directive, parse_apply_account_directive,
+            parse_end_apply_account_directive,
+        },
+        types::{Account, AccountTree, AccountType},
+    },
+    amount::types::{Amount, MixedAmount},
+    journal::parsers::parse_journal,
+};
 
 #[rstest]
 #[case("account assets:cash", "", "assets:cash")]
@@ -13,7 +26,7 @@ fn test_parse_account_directive(
     let mut input = input;
     assert_eq!(
         parse_account_directive(&mut input).unwrap(),
-        expected_account_name
+        (expected_account_name, None)
     );
     assert_eq!(input, expected_remaining);
 }
@@ -23,3 +36,187 @@ fn test_parse_invalid_account_directive() {
     parse_account_directive(&mut "account assets:cash  ").unwrap_err();
     assert!(parse_account_directive(&mut "account assets:cash  ").is_err(),);
 }
+
+#[rstest]
+#[case::short(
+    "account assets:receivable  ; type: A",
+    "assets:receivable",
+    AccountType::Asset
+)]
+#[case::long("account my:cash  ; type:Cash", "my:cash", AccountType::Cash)]
+#[case::other_tags(
+    "account revenues  ; note:x, type: R",
+    "revenues",
+    AccountType::Revenue
+)]
+fn test_parse_account_directive_with_type(
+    #[case] input: &str,
+    #[case] expected_account_name: &str,
+    #[case] expected_type: AccountType,
+) {
+    let mut input = input;
+    assert_eq!(
+        parse_account_directive(&mut input).unwrap(),
+        (expected_account_name, Some(expected_type))
+    );
+    assert_eq!(input, "");
+}
+
+#[test]
+fn test_parse_account_directive_with_comment() {
+    assert_eq!(
+        parse_account_directive(&mut "account assets:cash  ; just a comment").unwrap(),
+        ("assets:cash", None)
+    );
+}
+
+#[test]
+fn test_parse_account_directive_invalid_type() {
+    assert!(parse_account_directive(&mut "account assets:cash  ; type: Z").is_err());
+}
+
+#[rstest]
+#[case::child("assets:bank:checking", "assets:bank", true)]
+#[case::grandchild("assets:bank:checking", "assets", true)]
+#[case::same("assets:bank", "assets:bank", false)]
+#[case::parent("assets", "assets:bank", false)]
+#[case::common_prefix("assets:bankx", "assets:bank", false)]
+#[case::sibling("assets:cash", "assets:bank", false)]
+fn test_account_is_child_of(#[case] account: &str, #[case] parent: &str, #[case] expected: bool) {
+    assert_eq!(Account::from(account).is_child_of(&parent.into()), expected);
+}
+
+#[rstest]
+#[case::top_level("assets", None, 1, "assets")]
+#[case::nested("assets:bank:checking", Some("assets:bank"), 3, "checking")]
+fn test_account_hierarchy(
+    #[case] account: &str,
+    #[case] expected_parent: Option<&str>,
+    #[case] expected_depth: usize,
+    #[case] expected_leaf: &str,
+) {
+    let account = Account::from(account);
+    assert_eq!(account.parent(), expected_parent.map(Account::from));
+    assert_eq!(account.depth(), expected_depth);
+    assert_eq!(account.leaf(), expected_leaf);
+}
+
+#[test]
+fn test_account_ancestors() {
+    assert_eq!(
+        Account::from("assets:bank:checking")
+            .ancestors()
+            .collect::<Vec<Account>>(),
+        vec![Account::from("assets:bank"), Account::from("assets")]
+    );
+}
+
+#[test]
+fn test_account_tree_from_journal() {
+    let mut input = r#"account liabilities:card
+account assets:bank
+
+2024-01-01 groceries
+    expenses:food:groceries  10 EUR
+    assets:bank:checking
+"#;
+    let tree = parse_journal(&mut input, None).unwrap().account_tree();
+    assert_eq!(
+        tree.iter()
+            .map(|n| n.account.to_string())
+            .collect::<Vec<String>>(),
+        vec![
+            "assets",
+            "assets:bank",
+            "assets:bank:checking",
+            "expenses",
+            "expenses:food",
+            "expenses:food:groceries",
+            "liabilities",
+            "liabilities:card",
+        ]
+    );
+    assert_eq!(
+        tree.find(&"expenses:food".into())
+            .unwrap()
+            .children
+            .iter()
+            .map(|n| n.account.to_string())
+            .collect::<Vec<String>>(),
+        vec!["expenses:food:groceries"]
+    );
+    assert!(tree.find(&"expenses:rent".into()).is_none());
+}
+
+#[test]
+fn test_account_tree_clip() {
+    let tree = AccountTree::new(vec![
+        Account::from("assets:bank:checking"),
+        Account::from("expenses"),
+    ]);
+    assert_eq!(
+        tree.clip(2)
+            .iter()
+            .map(|n| n.account.to_string())
+            .collect::<Vec<String>>(),
+        vec!["assets", "assets:bank", "expenses"]
+    );
+    assert!(tree.clip(0).roots.is_empty());
+}
+
+#[test]
+fn test_account_tree_aggregate() {
+    let mut input = r#"2024-01-01 groceries
+    expenses:food:groceries  10 EUR
+    expenses:food  2 EUR
+    assets:bank:checking
+
+2024-01-02 rent
+    expenses:rent  500 EUR
+    assets:bank:checking
+"#;
+    let journal = parse_journal(&mut input, None).unwrap();
+    let tree = journal.account_tree().clip(2);
+    let totals = tree.aggregate(&journal.account_balances());
+
+    let eur = |value| -> MixedAmount {
+        Amount {
+            currency: "EUR".into(),
+            value,
+        }
+        .into()
+    };
+    assert_eq!(totals[&Account::from("expenses")], eur(dec!(512)));
+    assert_eq!(totals[&Account::from("expenses:food")], eur(dec!(12)));
+    assert_eq!(totals[&Account::from("assets:bank")], eur(dec!(-512)));
+    assert!(!totals.contains_key(&Account::from("assets:bank:checking")));
+
+    let counts = tree.aggregate(&BTreeMap::from([
+        (Account::from("expenses:food:groceries"), 1),
+        (Account::from("expenses:rent"), 1),
+    ]));
+    assert_eq!(counts[&Account::from("expenses")], 2);
+    assert_eq!(counts[&Account::from("assets")], 0);
+}
+
+#[rstest]
+#[case::simple("apply account business", "business")]
+#[case::nested_name("apply account  business:acme ", "business:acme")]
+fn test_parse_apply_account_directive(#[case] input: &str, #[case] expected: &str) {
+    let mut input = input;
+    assert_eq!(parse_apply_account_directive(&mut input).unwrap(), expected);
+    assert_eq!(input, "");
+}
+
+#[test]
+fn test_parse_invalid_apply_account_directive() {
+    assert!(parse_apply_account_directive(&mut "apply account ").is_err());
+    assert!(parse_apply_account_directive(&mut "apply account a  ; comment").is_err());
+}
+
+#[test]
+fn test_parse_end_apply_account_directive() {
+    let mut input = "end apply account\n";
+    parse_end_apply_account_directive(&mut input).unwrap();
+    assert_eq!(input, "\n");
+}
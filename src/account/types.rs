@@ -1,9 +1,15 @@
-use std::fmt::Display;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Display,
+    ops::AddAssign,
+    str::FromStr,
+};
 
 use crate::{journal::types::Value, HLParserError};
 
 /// A ledger account
-#[derive(Clone, Debug, PartialEq, Hash, Eq)]
+#[derive(Clone, Debug, PartialEq, Hash, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Account(String);
 
 impl Account {
@@ -12,9 +18,141 @@ impl Account {
         self.0.split(':').map(|v| v.to_string()).collect()
     }
 
-    /// Returns true if the account is a child of the given account
+    /// Returns the full name of the account
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns true if the account is a subaccount, at any depth, of the given account
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hledger_parse::Account;
+    ///
+    /// let checking = Account::from("assets:bank:checking");
+    /// assert!(checking.is_child_of(&"assets:bank".into()));
+    /// assert!(checking.is_child_of(&"assets".into()));
+    /// assert!(!checking.is_child_of(&"assets:bank:checking".into()));
+    /// assert!(!Account::from("assets:bankx").is_child_of(&"assets:bank".into()));
+    /// ```
     pub fn is_child_of(&self, account: &Account) -> bool {
-        self.0.starts_with(&account.to_string())
+        self.0.len() > account.0.len()
+            && self.0.starts_with(&account.0)
+            && self.0[account.0.len()..].starts_with(':')
+    }
+
+    /// Returns the parent account, or `None` for a top-level account
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hledger_parse::Account;
+    ///
+    /// assert_eq!(Account::from("assets:bank:checking").parent(), Some("assets:bank".into()));
+    /// assert_eq!(Account::from("assets").parent(), None);
+    /// ```
+    pub fn parent(&self) -> Option<Account> {
+        self.0
+            .rfind(':')
+            .map(|pos| Account(self.0[..pos].to_string()))
+    }
+
+    /// Returns all parent accounts, starting with the closest one
+    pub fn ancestors(&self) -> impl Iterator<Item = Account> {
+        std::iter::successors(self.parent(), |a| a.parent())
+    }
+
+    /// Returns the number of components of the account, e.g. 1 for a top-level account
+    pub fn depth(&self) -> usize {
+        self.0.matches(':').count() + 1
+    }
+
+    /// Returns the last component of the account
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hledger_parse::Account;
+    ///
+    /// assert_eq!(Account::from("assets:bank:checking").leaf(), "checking");
+    /// ```
+    pub fn leaf(&self) -> &str {
+        match self.0.rfind(':') {
+            Some(pos) => &self.0[pos + 1..],
+            None => &self.0,
+        }
+    }
+
+    /// Returns the account shortened to at most the given depth
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hledger_parse::Account;
+    ///
+    /// assert_eq!(Account::from("assets:bank:checking").clip(2), "assets:bank".into());
+    /// assert_eq!(Account::from("assets").clip(2), "assets".into());
+    /// ```
+    pub fn clip(&self, depth: usize) -> Account {
+        match self.0.match_indices(':').nth(depth.saturating_sub(1)) {
+            Some((pos, _)) => Account(self.0[..pos].to_string()),
+            None => self.clone(),
+        }
+    }
+
+    /// Returns the account type inferred from the account name
+    ///
+    /// This follows hledger's naming conventions, e.g. `assets:bank:checking` is a cash account,
+    /// `revenues:salary` is a revenue account and `debts:mortgage` is a liability account.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hledger_parse::{Account, AccountType};
+    ///
+    /// assert_eq!(Account::from("assets:bank:checking").inferred_type(), Some(AccountType::Cash));
+    /// assert_eq!(Account::from("Assets:Receivable").inferred_type(), Some(AccountType::Asset));
+    /// assert_eq!(Account::from("equity:conversion").inferred_type(), Some(AccountType::Conversion));
+    /// assert_eq!(Account::from("revenues:salary").inferred_type(), Some(AccountType::Revenue));
+    /// assert_eq!(Account::from("food").inferred_type(), None);
+    /// ```
+    pub fn inferred_type(&self) -> Option<AccountType> {
+        let components: Vec<String> = self.components().iter().map(|c| c.to_lowercase()).collect();
+        let second = components.get(1).map(String::as_str);
+
+        match components[0].as_str() {
+            "asset" | "assets" => {
+                let is_cash = components.iter().skip(1).any(|c| {
+                    matches!(
+                        c.as_str(),
+                        "cash"
+                            | "bank"
+                            | "check"
+                            | "checking"
+                            | "cheque"
+                            | "chequing"
+                            | "saving"
+                            | "savings"
+                            | "current"
+                    )
+                });
+                match is_cash {
+                    true => Some(AccountType::Cash),
+                    false => Some(AccountType::Asset),
+                }
+            }
+            "debt" | "debts" | "liability" | "liabilities" => Some(AccountType::Liability),
+            "equity" => match second {
+                Some(
+                    "trade" | "trades" | "trading" | "tradings" | "conversion" | "conversions",
+                ) => Some(AccountType::Conversion),
+                _ => Some(AccountType::Equity),
+            },
+            "income" | "revenue" | "revenues" => Some(AccountType::Revenue),
+            "expense" | "expenses" => Some(AccountType::Expense),
+            _ => None,
+        }
     }
 }
 
@@ -22,7 +160,7 @@ impl TryInto<Account> for Value {
     type Error = HLParserError;
 
     fn try_into(self) -> Result<Account, Self::Error> {
-        if let Value::Account(t) = self {
+        if let Value::Account(t, _) = self {
             Ok(t)
         } else {
             Err(HLParserError::Extract(self))
@@ -47,3 +185,205 @@ impl Display for Account {
         write!(f, "{}", self.0)
     }
 }
+
+/// The type of an account, used by financial statements to select accounts
+///
+/// Account types are declared with a `type:` tag on `account` directives, or inferred from the
+/// account name.
+///
+/// # Example
+///
+/// ```
+/// use hledger_parse::AccountType;
+///
+/// assert_eq!("A".parse::<AccountType>().unwrap(), AccountType::Asset);
+/// assert_eq!("Liability".parse::<AccountType>().unwrap(), AccountType::Liability);
+/// assert_eq!("R", format!("{}", AccountType::Revenue));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum AccountType {
+    Asset,
+    Liability,
+    Equity,
+    Revenue,
+    Expense,
+    /// A liquid asset account, a subtype of [`AccountType::Asset`]
+    Cash,
+    /// An equity account used for commodity conversions, a subtype of [`AccountType::Equity`]
+    Conversion,
+}
+
+impl AccountType {
+    /// Returns true if the account type is the given type or one of its subtypes
+    pub fn is(&self, account_type: AccountType) -> bool {
+        *self == account_type
+            || matches!(
+                (self, account_type),
+                (AccountType::Cash, AccountType::Asset)
+                    | (AccountType::Conversion, AccountType::Equity)
+            )
+    }
+}
+
+impl FromStr for AccountType {
+    type Err = HLParserError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "a" | "asset" | "assets" => Ok(AccountType::Asset),
+            "l" | "liability" | "liabilities" => Ok(AccountType::Liability),
+            "e" | "equity" => Ok(AccountType::Equity),
+            "r" | "revenue" | "revenues" => Ok(AccountType::Revenue),
+            "x" | "expense" | "expenses" => Ok(AccountType::Expense),
+            "c" | "cash" => Ok(AccountType::Cash),
+            "v" | "conversion" => Ok(AccountType::Conversion),
+            _ => Err(HLParserError::Parse(format!("Unknown account type: {}", s))),
+        }
+    }
+}
+
+impl Display for AccountType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AccountType::Asset => write!(f, "A"),
+            AccountType::Liability => write!(f, "L"),
+            AccountType::Equity => write!(f, "E"),
+            AccountType::Revenue => write!(f, "R"),
+            AccountType::Expense => write!(f, "X"),
+            AccountType::Cash => write!(f, "C"),
+            AccountType::Conversion => write!(f, "V"),
+        }
+    }
+}
+
+/// An account and its subaccounts
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AccountNode {
+    /// The account of the node
+    pub account: Account,
+    /// The direct subaccounts, ordered by name
+    pub children: Vec<AccountNode>,
+}
+
+impl AccountNode {
+    fn new(account: Account, accounts: &BTreeSet<Account>) -> AccountNode {
+        let children = accounts
+            .iter()
+            .filter(|a| a.parent().as_ref() == Some(&account))
+            .map(|a| AccountNode::new(a.clone(), accounts))
+            .collect();
+        AccountNode { account, children }
+    }
+
+    fn clip(&self, depth: usize) -> AccountNode {
+        AccountNode {
+            account: self.account.clone(),
+            children: match self.account.depth() < depth {
+                true => self.children.iter().map(|c| c.clip(depth)).collect(),
+                false => vec![],
+            },
+        }
+    }
+}
+
+/// The hierarchy of accounts
+///
+/// Parent accounts which are not used or declared themselves are added to the tree.
+///
+/// # Example
+///
+/// ```
+/// use std::collections::BTreeMap;
+/// use rust_decimal_macros::dec;
+/// use hledger_parse::{Account, AccountTree};
+///
+/// let tree = AccountTree::new(vec![
+///     Account::from("assets:bank:checking"),
+///     Account::from("assets:cash"),
+///     Account::from("expenses:food"),
+/// ]);
+/// assert_eq!(
+///     vec!["assets", "assets:bank", "assets:bank:checking", "assets:cash", "expenses", "expenses:food"],
+///     tree.iter().map(|n| n.account.to_string()).collect::<Vec<String>>()
+/// );
+///
+/// let totals = tree.aggregate(&BTreeMap::from([
+///     (Account::from("assets:bank:checking"), dec!(10)),
+///     (Account::from("assets:cash"), dec!(5)),
+/// ]));
+/// assert_eq!(totals[&Account::from("assets")], dec!(15));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AccountTree {
+    /// The top-level accounts, ordered by name
+    pub roots: Vec<AccountNode>,
+}
+
+impl AccountTree {
+    pub fn new(accounts: impl IntoIterator<Item = Account>) -> AccountTree {
+        let mut all_accounts = BTreeSet::new();
+        for account in accounts {
+            all_accounts.extend(account.ancestors());
+            all_accounts.insert(account);
+        }
+
+        AccountTree {
+            roots: all_accounts
+                .iter()
+                .filter(|a| a.depth() == 1)
+                .map(|a| AccountNode::new(a.clone(), &all_accounts))
+                .collect(),
+        }
+    }
+
+    /// Returns all nodes of the tree, parents before their children
+    pub fn iter(&self) -> impl Iterator<Item = &AccountNode> {
+        let mut stack: Vec<&AccountNode> = self.roots.iter().rev().collect();
+        std::iter::from_fn(move || {
+            let node = stack.pop()?;
+            stack.extend(node.children.iter().rev());
+            Some(node)
+        })
+    }
+
+    /// Returns the node of the given account
+    pub fn find(&self, account: &Account) -> Option<&AccountNode> {
+        self.iter().find(|n| &n.account == account)
+    }
+
+    /// Returns a copy of the tree without the accounts deeper than `depth`
+    pub fn clip(&self, depth: usize) -> AccountTree {
+        AccountTree {
+            roots: match depth {
+                0 => vec![],
+                _ => self.roots.iter().map(|r| r.clip(depth)).collect(),
+            },
+        }
+    }
+
+    /// Sums the values of each account and its subaccounts
+    ///
+    /// Values of accounts below the tree's depth are added to their closest account in the tree.
+    /// Every account of the tree gets a value, which is the default value if nothing was added.
+    pub fn aggregate<V>(&self, values: &BTreeMap<Account, V>) -> BTreeMap<Account, V>
+    where
+        V: Default + for<'a> AddAssign<&'a V>,
+    {
+        let mut totals: BTreeMap<Account, V> = self
+            .iter()
+            .map(|n| (n.account.clone(), V::default()))
+            .collect();
+        for (account, value) in values {
+            for a in std::iter::once(account.clone()).chain(account.ancestors()) {
+                if let Some(total) = totals.get_mut(&a) {
+                    *total += value;
+                }
+            }
+        }
+        totals
+    }
+}
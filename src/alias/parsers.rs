@@ -0,0 +1,66 @@
+use regex::RegexBuilder;
+use winnow::{
+    ascii::{space0, space1, till_line_ending},
+    combinator::{alt, delimited, peek, preceded, separated_pair, terminated},
+    error::{ErrMode, FromExternalError as _},
+    token::take_till,
+    PResult, Parser,
+};
+
+use crate::HLParserError;
+
+use super::types::AccountAlias;
+
+fn parse_regex_alias(input: &mut &str) -> PResult<AccountAlias> {
+    let line = till_line_ending.parse_next(input)?;
+    let (pattern, replacement) = (|| {
+        let (left, right) = line.rsplit_once('=')?;
+        let pattern = left.trim().strip_prefix('/')?.strip_suffix('/')?;
+        Some((pattern, right.trim()))
+    })()
+    .ok_or_else(|| ErrMode::Backtrack(winnow::error::ContextError::new()))?;
+
+    let regex = RegexBuilder::new(pattern)
+        .case_insensitive(true)
+        .build()
+        .map_err(|e| {
+            ErrMode::from_external_error(
+                input,
+                winnow::error::ErrorKind::Verify,
+                HLParserError::Parse(format!("Invalid alias regex {}: {}", pattern, e)),
+            )
+            .cut()
+        })?;
+
+    Ok(AccountAlias::Regex {
+        regex,
+        replacement: replacement.to_string(),
+    })
+}
+
+fn parse_simple_alias(input: &mut &str) -> PResult<AccountAlias> {
+    separated_pair(
+        take_till(1.., '='),
+        '=',
+        delimited(space0, till_line_ending, space0),
+    )
+    .verify(|(from, to): &(&str, &str)| !from.trim().is_empty() && !to.trim().is_empty())
+    .map(|(from, to)| AccountAlias::Simple {
+        from: from.trim().to_string(),
+        to: to.trim().to_string(),
+    })
+    .parse_next(input)
+}
+
+pub fn parse_alias_directive(input: &mut &str) -> PResult<AccountAlias> {
+    preceded(
+        ("alias", space1),
+        alt((preceded(peek('/'), parse_regex_alias), parse_simple_alias)),
+    )
+    .context(winnow::error::StrContext::Label("alias directive"))
+    .parse_next(input)
+}
+
+pub fn parse_end_aliases_directive(input: &mut &str) -> PResult<()> {
+    terminated(("end", space1, "aliases").void(), space0).parse_next(input)
+}
@@ -0,0 +1,115 @@
+use rstest::rstest;
+
+use crate::account::types::Account;
+
+use super::{
+    parsers::{parse_alias_directive, parse_end_aliases_directive},
+    types::AccountAlias,
+};
+
+#[rstest]
+#[case::simple(
+    "alias checking = assets:bank:checking",
+    "checking",
+    "assets:bank:checking"
+)]
+#[case::no_spaces(
+    "alias checking=assets:bank:checking",
+    "checking",
+    "assets:bank:checking"
+)]
+#[case::spaces_in_names("alias old name = new name", "old name", "new name")]
+fn test_parse_simple_alias(
+    #[case] input: &str,
+    #[case] expected_from: &str,
+    #[case] expected_to: &str,
+) {
+    let mut input = input;
+    assert_eq!(
+        parse_alias_directive(&mut input).unwrap(),
+        AccountAlias::Simple {
+            from: expected_from.to_string(),
+            to: expected_to.to_string(),
+        }
+    );
+    assert_eq!(input, "");
+}
+
+#[rstest]
+#[case::simple(r"alias /^expenses:(.*)/ = exp:\1", "^expenses:(.*)", r"exp:\1")]
+#[case::slash_in_regex(r"alias /a\/b/ = c", r"a\/b", "c")]
+#[case::equals_in_regex(r"alias /a=b/=c", "a=b", "c")]
+fn test_parse_regex_alias(
+    #[case] input: &str,
+    #[case] expected_regex: &str,
+    #[case] expected_replacement: &str,
+) {
+    let mut input = input;
+    match parse_alias_directive(&mut input).unwrap() {
+        AccountAlias::Regex { regex, replacement } => {
+            assert_eq!(regex.as_str(), expected_regex);
+            assert_eq!(replacement, expected_replacement);
+        }
+        alias => panic!("Expected a regex alias, got {:?}", alias),
+    }
+    assert_eq!(input, "");
+}
+
+#[rstest]
+#[case::missing_replacement("alias checking")]
+#[case::empty_from("alias = checking")]
+#[case::invalid_regex("alias /(/ = checking")]
+fn test_parse_invalid_alias(#[case] input: &str) {
+    let mut input = input;
+    assert!(parse_alias_directive(&mut input).is_err());
+}
+
+#[test]
+fn test_parse_end_aliases() {
+    let mut input = "end aliases\n";
+    parse_end_aliases_directive(&mut input).unwrap();
+    assert_eq!(input, "\n");
+}
+
+#[rstest]
+#[case::exact("checking", "assets:bank:checking")]
+#[case::subaccount("checking:joint", "assets:bank:checking:joint")]
+#[case::common_prefix("checkingx", "checkingx")]
+#[case::not_at_start("old:checking", "old:checking")]
+fn test_apply_simple_alias(#[case] account: &str, #[case] expected: &str) {
+    let alias = AccountAlias::Simple {
+        from: "checking".to_string(),
+        to: "assets:bank:checking".to_string(),
+    };
+    assert_eq!(alias.apply(&account.into()), Account::from(expected));
+}
+
+#[rstest]
+#[case::backreference(r"alias /^expenses:(.*)/ = exp:\1", "expenses:food", "exp:food")]
+#[case::case_insensitive(r"alias /^EXPENSES:(.*)/ = exp:\1", "expenses:food", "exp:food")]
+#[case::no_match(r"alias /^expenses:(.*)/ = exp:\1", "assets:cash", "assets:cash")]
+#[case::all_matches("alias /o/ = 0", "food:foo", "f00d:f00")]
+#[case::dollar_in_replacement("alias /usd/ = $", "assets:usd", "assets:$")]
+fn test_apply_regex_alias(#[case] directive: &str, #[case] account: &str, #[case] expected: &str) {
+    let mut directive = directive;
+    let alias = parse_alias_directive(&mut directive).unwrap();
+    assert_eq!(alias.apply(&account.into()), Account::from(expected));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_alias_serde_round_trip() {
+    let mut input = "alias /^Expenses:(.*)/ = exp:\\1";
+    let alias = parse_alias_directive(&mut input).unwrap();
+    let json = serde_json::to_string(&alias).unwrap();
+    assert_eq!(
+        json,
+        r#"{"regex":{"regex":"^Expenses:(.*)","replacement":"exp:\\1"}}"#
+    );
+
+    let deserialized: AccountAlias = serde_json::from_str(&json).unwrap();
+    assert_eq!(
+        deserialized.apply(&Account::from("expenses:food")),
+        Account::from("exp:food")
+    );
+}
@@ -0,0 +1,118 @@
+use std::fmt::Display;
+
+use regex::Regex;
+
+use crate::account::types::Account;
+
+/// Account alias, declared with the `alias` directive
+///
+/// # Example
+///
+/// ```
+/// use regex::RegexBuilder;
+/// use hledger_parse::{Account, AccountAlias};
+///
+/// let alias = AccountAlias::Simple {
+///     from: "checking".to_string(),
+///     to: "assets:bank:checking".to_string(),
+/// };
+/// assert_eq!(alias.apply(&"checking:joint".into()), Account::from("assets:bank:checking:joint"));
+/// assert_eq!(alias.apply(&"checkingx".into()), Account::from("checkingx"));
+/// assert_eq!("alias checking = assets:bank:checking", format!("{}", alias));
+///
+/// let alias = AccountAlias::Regex {
+///     regex: RegexBuilder::new("^expenses:(.*)").case_insensitive(true).build().unwrap(),
+///     replacement: "exp:\\1".to_string(),
+/// };
+/// assert_eq!(alias.apply(&"expenses:food".into()), Account::from("exp:food"));
+/// assert_eq!("alias /^expenses:(.*)/ = exp:\\1", format!("{}", alias));
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum AccountAlias {
+    /// Replaces an account name and the names of its subaccounts
+    Simple { from: String, to: String },
+    /// Replaces all matches of a regular expression, `\1` refers to the first match group
+    Regex {
+        #[cfg_attr(feature = "serde", serde(with = "crate::utils::serde_regex"))]
+        regex: Regex,
+        replacement: String,
+    },
+}
+
+impl AccountAlias {
+    /// Returns the account with the alias applied
+    pub fn apply(&self, account: &Account) -> Account {
+        match self {
+            AccountAlias::Simple { from, to } => {
+                let name = account.as_str();
+                match name.strip_prefix(from.as_str()) {
+                    Some(rest) if rest.is_empty() || rest.starts_with(':') => {
+                        format!("{}{}", to, rest).into()
+                    }
+                    _ => account.clone(),
+                }
+            }
+            AccountAlias::Regex { regex, replacement } => {
+                let replacement = replace_backreferences(replacement);
+                regex
+                    .replace_all(account.as_str(), replacement.as_str())
+                    .to_string()
+                    .into()
+            }
+        }
+    }
+}
+
+/// Converts `\N` back references to the `${N}` syntax of the regex crate
+fn replace_backreferences(replacement: &str) -> String {
+    let mut result = String::new();
+    let mut chars = replacement.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, chars.peek()) {
+            ('\\', Some(d)) if d.is_ascii_digit() => {
+                result.push_str(&format!("${{{}}}", d));
+                chars.next();
+            }
+            ('$', _) => result.push_str("$$"),
+            _ => result.push(c),
+        }
+    }
+    result
+}
+
+impl PartialEq for AccountAlias {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (
+                AccountAlias::Simple { from, to },
+                AccountAlias::Simple {
+                    from: other_from,
+                    to: other_to,
+                },
+            ) => from == other_from && to == other_to,
+            (
+                AccountAlias::Regex { regex, replacement },
+                AccountAlias::Regex {
+                    regex: other_regex,
+                    replacement: other_replacement,
+                },
+            ) => regex.as_str() == other_regex.as_str() && replacement == other_replacement,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for AccountAlias {}
+
+impl Display for AccountAlias {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AccountAlias::Simple { from, to } => write!(f, "alias {} = {}", from, to),
+            AccountAlias::Regex { regex, replacement } => {
+                write!(f, "alias /{}/ = {}", regex.as_str(), replacement)
+            }
+        }
+    }
+}
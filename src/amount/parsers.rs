@@ -2,41 +2,137 @@ use std::str::FromStr;
 
 use rust_decimal::Decimal;
 use winnow::{
-    ascii::{digit1, space0},
-    combinator::{alt, opt, separated, terminated},
+    ascii::{digit1, line_ending, space0, space1},
+    combinator::{alt, delimited, eof, opt, repeat, terminated},
     error::{ErrMode, FromExternalError as _},
     stream::AsChar,
-    token::take_till,
+    token::{one_of, take_till},
     PResult, Parser,
 };
 
 use crate::{
+    journal::types::ParseState,
     utils::{in_quotes, is_char_minus},
     ValidationError,
 };
 
 use super::types::{Amount, AmountSign};
 
-// TODO: no scientific notation parsing
-pub fn parse_money_amount(input: &mut &str) -> PResult<Decimal> {
-    let num = (
-        separated::<_, _, (), _, _, _, _>(1.., digit1, " ").void(),
-        opt(alt(('.', ','))).void(),
-        opt(digit1).void(),
+/// Characters separating groups of digits which are never a decimal mark
+const SPACE_GROUP_MARKS: [char; 2] = [' ', '\u{2009}'];
+
+/// Parses the text of a number: digits with digit group marks, a decimal mark and an exponent
+fn parse_number_text<'s>(input: &mut &'s str) -> PResult<&'s str> {
+    (
+        digit1,
+        repeat::<_, _, (), _, _>(
+            0..,
+            (
+                one_of(['.', ',', SPACE_GROUP_MARKS[0], SPACE_GROUP_MARKS[1]]),
+                digit1,
+            ),
+        ),
+        opt(one_of(['.', ','])),
+        opt((one_of(['e', 'E']), opt(one_of(['+', '-'])), digit1)),
     )
         .take()
-        .parse_next(input)?;
+        .parse_next(input)
+}
+
+/// Returns the decimal mark of a number when no decimal mark is declared
+///
+/// The last `.` or `,` is the decimal mark, unless it appears several times, in which case it
+/// separates digit groups.
+pub fn infer_decimal_mark(number: &str) -> Option<char> {
+    let mark = number.chars().rev().find(|c| ['.', ','].contains(c))?;
+    match number.matches(mark).count() {
+        1 => Some(mark),
+        _ => None,
+    }
+}
+
+/// Converts the text of a number to a decimal
+///
+/// Uses the given decimal mark, or the inferred one if there is none. All other marks separate
+/// digit groups, and are only allowed before the decimal mark. The decimal keeps the number of
+/// decimal places written, adjusted by the exponent, e.g. 3 for `1.5e-2`.
+fn interpret_number(number: &str, decimal_mark: Option<char>) -> Result<Decimal, String> {
+    let (number, exponent) = match number.split_once(['e', 'E']) {
+        Some((mantissa, exponent)) => (
+            mantissa,
+            exponent.parse::<i64>().map_err(|e| e.to_string())?,
+        ),
+        None => (number, 0),
+    };
+    let decimal_mark = decimal_mark.or_else(|| infer_decimal_mark(number));
+    let mut digits = String::with_capacity(number.len());
+    let mut has_decimal_mark = false;
+    for c in number.chars() {
+        match c {
+            '0'..='9' => digits.push(c),
+            c if Some(c) == decimal_mark && !has_decimal_mark => {
+                has_decimal_mark = true;
+                digits.push('.');
+            }
+            _ if has_decimal_mark => {
+                return Err(format!(
+                    "{}: unexpected '{}' after the decimal mark",
+                    number, c
+                ))
+            }
+            _ => {}
+        }
+    }
 
-    let num = Decimal::from_str(&num.replace(',', ".").replace(' ', "")).map_err(|e| {
+    let mantissa = Decimal::from_str(&digits).map_err(|e| e.to_string())?;
+    apply_exponent(mantissa, exponent).ok_or_else(|| format!("{}: number out of range", number))
+}
+
+/// Multiplies a decimal by a power of ten, returning `None` if the result is out of range
+fn apply_exponent(value: Decimal, exponent: i64) -> Option<Decimal> {
+    let scale = i64::from(value.scale()) - exponent;
+    match u32::try_from(scale) {
+        Ok(scale) => Decimal::try_from_i128_with_scale(value.mantissa(), scale).ok(),
+        Err(_) => {
+            let factor = 10_i128.checked_pow(u32::try_from(-scale).ok()?)?;
+            Decimal::try_from_i128_with_scale(value.mantissa().checked_mul(factor)?, 0).ok()
+        }
+    }
+}
+
+/// Parses a number and converts it with the given decimal mark
+fn parse_number_with_mark<'s>(
+    decimal_mark: Option<char>,
+) -> impl FnMut(&mut &'s str) -> PResult<Decimal> {
+    move |input: &mut &'s str| {
+        let number = parse_number_text(input)?;
+        to_decimal(input, number, decimal_mark)
+    }
+}
+
+fn to_decimal(input: &&str, number: &str, decimal_mark: Option<char>) -> PResult<Decimal> {
+    interpret_number(number, decimal_mark).map_err(|e| {
         ErrMode::from_external_error(
             input,
             winnow::error::ErrorKind::Verify,
-            ValidationError::InvalidAmount(e.to_string()),
+            ValidationError::InvalidAmount(e),
         )
         .cut()
-    })?;
+    })
+}
 
-    Ok(num)
+pub fn parse_money_amount(input: &mut &str) -> PResult<Decimal> {
+    parse_number_with_mark(None).parse_next(input)
+}
+
+/// Parses a `decimal-mark` directive, returning the declared decimal mark
+pub fn parse_decimal_mark_directive(input: &mut &str) -> PResult<char> {
+    delimited(
+        ("decimal-mark", space1),
+        one_of(['.', ',']),
+        (space0, alt((line_ending, eof))),
+    )
+    .parse_next(input)
 }
 
 fn parse_sign(input: &mut &str) -> PResult<Option<AmountSign>> {
@@ -53,50 +149,73 @@ pub fn parse_currency_string<'s>(input: &mut &'s str) -> PResult<&'s str> {
     alt((
         in_quotes,
         take_till(0.., |c: char| {
-            c.is_dec_digit() || is_char_minus(c) || c.is_space() || c.is_newline()
+            c.is_dec_digit()
+                || is_char_minus(c)
+                || c.is_space()
+                || c.is_newline()
+                || ['@', '=', ';'].contains(&c)
         }),
     ))
     .parse_next(input)
 }
 
-fn parse_amount_prefix_currency(input: &mut &str) -> PResult<Amount> {
-    let sign = terminated(parse_sign, space0).parse_next(input)?;
-    let currency = terminated(parse_currency_string, space0).parse_next(input)?;
-    let sign = match sign {
-        Some(s) => Some(s),
-        None => terminated(parse_sign, space0).parse_next(input)?,
-    };
+fn parse_amount_prefix_currency<'s>(
+    state: &ParseState,
+) -> impl FnMut(&mut &'s str) -> PResult<Amount> + '_ {
+    move |input: &mut &'s str| {
+        let sign = terminated(parse_sign, space0).parse_next(input)?;
+        let currency = terminated(parse_currency_string, space0).parse_next(input)?;
+        let sign = match sign {
+            Some(s) => Some(s),
+            None => terminated(parse_sign, space0).parse_next(input)?,
+        };
 
-    let mut value = parse_money_amount(input)?;
-    if let Some(AmountSign::Minus) = sign {
-        value.set_sign_negative(true);
-    }
+        let currency = currency.trim();
+        let mut value = parse_number_with_mark(state.decimal_mark(currency)).parse_next(input)?;
+        if let Some(AmountSign::Minus) = sign {
+            value.set_sign_negative(true);
+        }
 
-    Ok(Amount {
-        currency: currency.trim().into(),
-        value,
-    })
+        Ok(Amount {
+            currency: currency.into(),
+            value,
+        })
+    }
 }
 
-fn parse_amount_suffix_currency(input: &mut &str) -> PResult<Amount> {
-    let sign = terminated(parse_sign, space0).parse_next(input)?;
-    let mut value = terminated(parse_money_amount, space0).parse_next(input)?;
-    if let Some(AmountSign::Minus) = sign {
-        value.set_sign_negative(true);
-    }
+fn parse_amount_suffix_currency<'s>(
+    state: &ParseState,
+) -> impl FnMut(&mut &'s str) -> PResult<Amount> + '_ {
+    move |input: &mut &'s str| {
+        let sign = terminated(parse_sign, space0).parse_next(input)?;
+        let number = terminated(parse_number_text, space0).parse_next(input)?;
+        let currency = parse_currency_string(input)?.trim();
 
-    let currency = parse_currency_string(input)?;
+        let mut value = to_decimal(input, number, state.decimal_mark(currency))?;
+        if let Some(AmountSign::Minus) = sign {
+            value.set_sign_negative(true);
+        }
 
-    Ok(Amount {
-        currency: currency.trim().into(),
-        value,
-    })
+        Ok(Amount {
+            currency: currency.into(),
+            value,
+        })
+    }
 }
 
 pub fn parse_amount(input: &mut &str) -> PResult<Amount> {
-    alt((
-        parse_amount_suffix_currency, // this needs to go first
-        parse_amount_prefix_currency,
-    ))
-    .parse_next(input)
+    parse_amount_with_state(&ParseState::default()).parse_next(input)
+}
+
+/// Parses an amount, using the decimal marks declared in the journal parser state
+pub fn parse_amount_with_state<'s>(
+    state: &ParseState,
+) -> impl FnMut(&mut &'s str) -> PResult<Amount> + '_ {
+    move |input: &mut &'s str| {
+        alt((
+            parse_amount_suffix_currency(state), // this needs to go first
+            parse_amount_prefix_currency(state),
+        ))
+        .parse_next(input)
+    }
 }
@@ -1,9 +1,17 @@
 use rstest::rstest;
 use rust_decimal_macros::dec;
+use winnow::{error::ErrMode, Parser};
 
-use crate::amount::{
-    parsers::{parse_amount, parse_currency_string},
-    types::Amount,
+use crate::{
+    amount::{
+        parsers::{
+            parse_amount, parse_amount_with_state, parse_currency_string,
+            parse_decimal_mark_directive,
+        },
+        types::Amount,
+    },
+    commodity::types::Commodity,
+    journal::types::ParseState,
 };
 
 use super::parsers::parse_money_amount;
@@ -56,6 +64,15 @@ fn test_parse_amount_currency(
 #[case::double_dot("100.95EUR", "EUR", dec!(100.95))]
 #[case::double_comma("100,95EUR", "EUR", dec!(100.95))]
 #[case::double_small_amount("0.007EUR", "EUR", dec!(0.007))]
+#[case::comma_thousands("1,234.56 EUR", " EUR", dec!(1234.56))]
+#[case::dot_thousands("1.234,56 EUR", " EUR", dec!(1234.56))]
+#[case::repeated_group_marks("1,000,000 EUR", " EUR", dec!(1000000))]
+#[case::thin_space_thousands("1\u{2009}000,5", "", dec!(1000.5))]
+#[case::trailing_decimal_mark("100.", "", dec!(100))]
+#[case::exponent("1E3", "", dec!(1000))]
+#[case::negative_exponent("1.5e-2", "", dec!(0.015))]
+#[case::positive_exponent("2.5e+1EUR", "EUR", dec!(25))]
+#[case::no_exponent_digits("100EUR", "EUR", dec!(100))]
 fn test_parse_money_amount(
     #[case] input: &str,
     #[case] expected_remaining: &str,
@@ -70,6 +87,8 @@ fn test_parse_money_amount(
 #[case::simple("$", "", "$")]
 #[case::simple_space("$ ", " ", "$")]
 #[case::in_quotes("\"Imaginary money\"", "", "Imaginary money")]
+#[case::before_balance_assertion("= 5", "= 5", "")]
+#[case::before_price("@ 5", "@ 5", "")]
 #[case::in_quotes_space("\"Imaginary money\" ", " ", "Imaginary money")]
 #[case::iso("USD", "", "USD")]
 #[case::iso_space("USD ", " ", "USD")]
@@ -85,3 +104,128 @@ fn test_parse_currency_string(
     );
     assert_eq!(input, expected_remaining);
 }
+
+#[rstest]
+#[case::declared_dot("1,234", Some('.'), dec!(1234))]
+#[case::declared_comma("1.234", Some(','), dec!(1234))]
+#[case::declared_comma_with_decimals("1.234.567,8", Some(','), dec!(1234567.8))]
+#[case::inferred("1,234", None, dec!(1.234))]
+fn test_parse_number_with_declared_mark(
+    #[case] input: &str,
+    #[case] decimal_mark: Option<char>,
+    #[case] expected_value: rust_decimal::Decimal,
+) {
+    let state = ParseState {
+        decimal_mark,
+        ..Default::default()
+    };
+    let mut input = input;
+    assert_eq!(
+        parse_amount_with_state(&state)
+            .parse_next(&mut input)
+            .unwrap()
+            .value,
+        expected_value
+    );
+}
+
+#[rstest]
+#[case::group_mark_after_decimal_mark("1.234,56", Some('.'))]
+#[case::two_decimal_marks("1,234,56", Some(','))]
+fn test_parse_number_invalid_marks(#[case] input: &str, #[case] decimal_mark: Option<char>) {
+    let state = ParseState {
+        decimal_mark,
+        ..Default::default()
+    };
+    let mut input = input;
+    assert!(matches!(
+        parse_amount_with_state(&state).parse_next(&mut input),
+        Err(ErrMode::Cut(_))
+    ));
+}
+
+#[test]
+fn test_parse_number_commodity_decimal_mark() {
+    let mut state = ParseState {
+        decimal_mark: Some('.'),
+        ..Default::default()
+    };
+    state.declare_commodity(&Commodity {
+        name: "EUR".to_string(),
+        format: Some("1.000,00 EUR".to_string()),
+    });
+    let mut input = "1.234,5 EUR";
+    assert_eq!(
+        parse_amount_with_state(&state)
+            .parse_next(&mut input)
+            .unwrap(),
+        Amount {
+            currency: "EUR".into(),
+            value: dec!(1234.5)
+        }
+    );
+    let mut input = "$1,234.5";
+    assert_eq!(
+        parse_amount_with_state(&state)
+            .parse_next(&mut input)
+            .unwrap(),
+        Amount {
+            currency: "$".into(),
+            value: dec!(1234.5)
+        }
+    );
+}
+
+#[rstest]
+#[case::dot("decimal-mark .\n", Ok('.'))]
+#[case::comma("decimal-mark ,", Ok(','))]
+#[case::invalid("decimal-mark ;", Err(()))]
+fn test_parse_decimal_mark_directive(#[case] input: &str, #[case] expected: Result<char, ()>) {
+    let mut input = input;
+    assert_eq!(
+        parse_decimal_mark_directive(&mut input).map_err(|_| ()),
+        expected
+    );
+}
+
+#[rstest]
+#[case::decimals("1.50 EUR", 2)]
+#[case::integer("100 EUR", 0)]
+#[case::exponent("1E3", 0)]
+#[case::negative_exponent("1.5e-2", 3)]
+#[case::positive_exponent("1.25e1", 1)]
+fn test_parse_amount_precision(#[case] input: &str, #[case] expected_precision: u32) {
+    let mut input = input;
+    assert_eq!(
+        parse_amount(&mut input).unwrap().precision(),
+        expected_precision
+    );
+}
+
+#[test]
+fn test_parse_amount_exponent_out_of_range() {
+    let mut input = "1e40";
+    assert!(matches!(
+        parse_money_amount(&mut input),
+        Err(ErrMode::Cut(_))
+    ));
+}
+
+#[cfg(feature = "serde")]
+#[rstest]
+#[case::trailing_zeros(dec!(10.50), r#"{"currency":"$","value":"10.50"}"#)]
+#[case::many_digits(
+    dec!(0.1234567890123456789012345678),
+    r#"{"currency":"$","value":"0.1234567890123456789012345678"}"#
+)]
+#[case::negative(dec!(-3), r#"{"currency":"$","value":"-3"}"#)]
+fn test_amount_serde(#[case] value: rust_decimal::Decimal, #[case] expected: &str) {
+    let amount = Amount {
+        currency: "$".to_string(),
+        value,
+    };
+    assert_eq!(serde_json::to_string(&amount).unwrap(), expected);
+    let deserialized: Amount = serde_json::from_str(expected).unwrap();
+    assert_eq!(deserialized.value.scale(), value.scale());
+    assert_eq!(deserialized, amount);
+}
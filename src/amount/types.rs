@@ -1,4 +1,4 @@
-use std::fmt::Display;
+use std::{collections::BTreeMap, fmt::Display, ops::AddAssign};
 
 use rust_decimal::Decimal;
 
@@ -21,7 +21,8 @@ pub enum AmountSign {
 /// let amount = Amount { currency: "EUR".to_string(), value: dec!(19.99) };
 /// assert_eq!("19.99 EUR", format!("{}", amount));
 /// ```
-#[derive(PartialEq, Eq, Debug, Clone)]
+#[derive(PartialEq, Eq, Hash, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Amount {
     /// The currency of the amount
     pub currency: String,
@@ -44,6 +45,63 @@ impl Amount {
     pub fn is_positive(&self) -> bool {
         self.value.is_sign_positive()
     }
+
+    /// Returns the number of decimal places of the amount, as written in the journal
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rust_decimal_macros::dec;
+    /// use hledger_parse::Amount;
+    ///
+    /// let amount = Amount { currency: "EUR".to_string(), value: dec!(1.50) };
+    /// assert_eq!(2, amount.precision());
+    /// ```
+    pub fn precision(&self) -> u32 {
+        self.value.scale()
+    }
+
+    /// Returns the amount with the given number of decimal places
+    ///
+    /// The value is rounded half away from zero, or padded with zeros.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rust_decimal_macros::dec;
+    /// use hledger_parse::Amount;
+    ///
+    /// let amount = Amount { currency: "EUR".to_string(), value: dec!(1.5) };
+    /// assert_eq!("1.50 EUR", format!("{}", amount.with_precision(2)));
+    /// let amount = Amount { currency: "EUR".to_string(), value: dec!(0.125) };
+    /// assert_eq!("0.13 EUR", format!("{}", amount.with_precision(2)));
+    /// ```
+    pub fn with_precision(&self, precision: u32) -> Amount {
+        let mut value = self.value;
+        value.rescale(precision);
+        Amount {
+            currency: self.currency.clone(),
+            value,
+        }
+    }
+
+    /// Returns the amount without trailing zero decimal places
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rust_decimal_macros::dec;
+    /// use hledger_parse::Amount;
+    ///
+    /// let amount = Amount { currency: "EUR".to_string(), value: dec!(1.500) };
+    /// assert_eq!("1.5 EUR", format!("{}", amount.normalized()));
+    /// ```
+    pub fn normalized(&self) -> Amount {
+        Amount {
+            currency: self.currency.clone(),
+            value: self.value.normalize(),
+        }
+    }
 }
 
 impl Display for Amount {
@@ -51,3 +109,102 @@ impl Display for Amount {
         write!(f, "{} {}", self.value, self.currency)
     }
 }
+
+/// A sum of amounts in possibly several commodities
+///
+/// Zero amounts are not kept, so an empty mixed amount is zero.
+///
+/// # Example:
+///
+/// ```
+/// use rust_decimal_macros::dec;
+/// use hledger_parse::{Amount, MixedAmount};
+///
+/// let mut total = MixedAmount::default();
+/// total.add_amount(&Amount { currency: "EUR".to_string(), value: dec!(10) });
+/// total.add_amount(&Amount { currency: "USD".to_string(), value: dec!(5) });
+/// total.add_amount(&Amount { currency: "EUR".to_string(), value: dec!(-2.5) });
+/// assert_eq!("7.5 EUR, 5 USD", format!("{}", total));
+/// assert_eq!("0", format!("{}", MixedAmount::default()));
+/// ```
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MixedAmount(BTreeMap<String, Decimal>);
+
+impl MixedAmount {
+    /// Adds a single amount
+    pub fn add_amount(&mut self, amount: &Amount) {
+        let value = self.0.entry(amount.currency.clone()).or_default();
+        *value += amount.value;
+        if value.is_zero() {
+            self.0.remove(&amount.currency);
+        }
+    }
+
+    /// Returns the amounts, one per commodity, ordered by commodity
+    pub fn amounts(&self) -> Vec<Amount> {
+        self.0
+            .iter()
+            .map(|(currency, value)| Amount {
+                currency: currency.clone(),
+                value: *value,
+            })
+            .collect()
+    }
+
+    /// Returns the value in the given commodity
+    pub fn value(&self, currency: &str) -> Decimal {
+        self.0.get(currency).copied().unwrap_or_default()
+    }
+
+    pub fn negate(&self) -> MixedAmount {
+        MixedAmount(self.0.iter().map(|(c, v)| (c.clone(), -v)).collect())
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl From<Amount> for MixedAmount {
+    fn from(value: Amount) -> Self {
+        let mut mixed = MixedAmount::default();
+        mixed.add_amount(&value);
+        mixed
+    }
+}
+
+impl AddAssign<&MixedAmount> for MixedAmount {
+    fn add_assign(&mut self, rhs: &MixedAmount) {
+        for amount in rhs.amounts() {
+            self.add_amount(&amount);
+        }
+    }
+}
+
+impl<'a> std::iter::Sum<&'a MixedAmount> for MixedAmount {
+    fn sum<I: Iterator<Item = &'a MixedAmount>>(iter: I) -> Self {
+        let mut total = MixedAmount::default();
+        for amount in iter {
+            total += amount;
+        }
+        total
+    }
+}
+
+impl Display for MixedAmount {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_zero() {
+            return write!(f, "0");
+        }
+        write!(
+            f,
+            "{}",
+            self.amounts()
+                .iter()
+                .map(|a| format!("{}", a))
+                .collect::<Vec<String>>()
+                .join(", ")
+        )
+    }
+}
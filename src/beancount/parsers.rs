@@ -0,0 +1,665 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use chrono::{Days, NaiveDate};
+use rust_decimal::Decimal;
+use thiserror::Error;
+use winnow::{
+    ascii::{line_ending, space0, space1, till_line_ending},
+    combinator::{alt, delimited, eof, opt, preceded, repeat, terminated},
+    error::{ErrMode, FromExternalError},
+    token::{any, none_of, one_of, take_till, take_while},
+    PResult, Parser,
+};
+
+use crate::{
+    account::types::{Account, AccountType},
+    amount::{parsers::parse_amount_with_state, types::Amount},
+    commodity::types::Commodity,
+    date::parsers::parse_date,
+    description::types::Description,
+    journal::{
+        parsers::{journal_from_values, read_included_file, resolve_include},
+        types::{IncludedFile, InputFormat, Journal, Location, ParseState, Value},
+    },
+    posting::types::{Posting, PostingKind},
+    price::types::Price,
+    status::types::Status,
+    tag::types::Tag,
+    transaction::types::Transaction,
+    utils::LineCounter,
+    HLParserError,
+};
+
+/// A Beancount construct which cannot be converted to a journal, the cause of a parse error
+#[derive(Debug, Error)]
+#[error("{0}")]
+struct Unsupported(String);
+
+/// Returns the error for an unsupported construct, which stops the parsing
+fn unsupported(input: &&str, message: String) -> ErrMode<winnow::error::ContextError> {
+    ErrMode::from_external_error(
+        input,
+        winnow::error::ErrorKind::Verify,
+        Unsupported(message),
+    )
+    .cut()
+}
+
+/// The state of the numbers of a Beancount file, which always use `.` as decimal mark
+fn number_state() -> ParseState {
+    ParseState {
+        decimal_mark: Some('.'),
+        ..ParseState::default()
+    }
+}
+
+/// Parses a double-quoted string, with `\"` and `\\` escapes
+fn parse_string(input: &mut &str) -> PResult<String> {
+    delimited(
+        '"',
+        repeat(0.., alt((preceded('\\', any), none_of(['"', '\\'])))),
+        '"',
+    )
+    .parse_next(input)
+}
+
+/// Parses an account name, capitalized components separated by colons
+fn parse_account(input: &mut &str) -> PResult<Account> {
+    take_while(1.., |c: char| c.is_alphanumeric() || c == ':' || c == '-')
+        .verify(|a: &str| a.contains(':') && a.starts_with(|c: char| c.is_uppercase()))
+        .map(Account::from)
+        .parse_next(input)
+}
+
+fn parse_commodity(input: &mut &str) -> PResult<String> {
+    take_while(1.., |c: char| {
+        c.is_ascii_uppercase() || c.is_ascii_digit() || ['\'', '.', '_', '-'].contains(&c)
+    })
+    .map(str::to_string)
+    .parse_next(input)
+}
+
+fn parse_line_end(input: &mut &str) -> PResult<()> {
+    (
+        space0,
+        opt((';', till_line_ending)),
+        alt((line_ending, eof)),
+    )
+        .void()
+        .parse_next(input)
+}
+
+/// Parses a metadata line, an indented `key: value` pair
+fn parse_metadata<'s>(input: &mut &'s str) -> PResult<(&'s str, String)> {
+    let key = preceded(
+        space1,
+        (
+            one_of(|c: char| c.is_ascii_lowercase()),
+            take_while(0.., |c: char| c.is_alphanumeric() || c == '-' || c == '_'),
+        )
+            .take(),
+    )
+    .parse_next(input)?;
+    let value = preceded(
+        (':', space0),
+        alt((
+            parse_string,
+            take_till(0.., [';', '\n', '\r']).map(|v: &str| v.trim_end().to_string()),
+        )),
+    )
+    .parse_next(input)?;
+    parse_line_end(input)?;
+    Ok((key, value))
+}
+
+/// Parses the metadata lines following a directive
+fn parse_metadata_lines(input: &mut &str) -> PResult<Vec<(String, String)>> {
+    repeat(
+        0..,
+        alt((
+            parse_metadata.map(|(key, value)| Some((key.to_string(), value))),
+            parse_indented_comment.value(None),
+        )),
+    )
+    .map(|metadata: Vec<Option<(String, String)>>| metadata.into_iter().flatten().collect())
+    .parse_next(input)
+}
+
+fn parse_indented_comment(input: &mut &str) -> PResult<()> {
+    (space1, ';', till_line_ending, alt((line_ending, eof)))
+        .void()
+        .parse_next(input)
+}
+
+/// Parses the cost of a posting, `{unit cost}` or `{{total cost}}`
+///
+/// Only the amount of a cost is kept; lot dates and labels are ignored, and a cost without an
+/// amount, like `{}`, is unsupported. The flag is true for a total cost.
+fn parse_cost(input: &mut &str) -> PResult<(Amount, bool)> {
+    let ((total, spec), cost) = alt((
+        delimited("{{", take_till(0.., '}'), "}}").map(|spec| (true, spec)),
+        delimited('{', take_till(0.., '}'), '}').map(|spec| (false, spec)),
+    ))
+    .with_taken()
+    .parse_next(input)?;
+    let state = number_state();
+    match spec
+        .split(',')
+        .find_map(|part| parse_amount_with_state(&state).parse(part.trim()).ok())
+    {
+        Some(amount) => Ok((amount, total)),
+        None => Err(unsupported(
+            input,
+            format!("costs without an amount are not supported: {}", cost),
+        )),
+    }
+}
+
+/// Parses a posting line, `[flag] Account [amount] [{cost}] [@ price | @@ total price]`
+fn parse_posting(input: &mut &str) -> PResult<Posting> {
+    let status = preceded(
+        space1,
+        opt(terminated(
+            alt(('*'.value(Status::Cleared), '!'.value(Status::Pending))),
+            space1,
+        )),
+    )
+    .parse_next(input)?
+    .unwrap_or(Status::Unmarked);
+    let account = parse_account(input)?;
+    let rest = *input;
+    let state = number_state();
+    let amount = opt(preceded(space1, parse_amount_with_state(&state))).parse_next(input)?;
+    let cost = opt(preceded(space0, parse_cost)).parse_next(input)?;
+    let price = opt(preceded(
+        space0,
+        (
+            alt(("@@".value(true), "@".value(false))),
+            preceded(space0, parse_amount_with_state(&state)),
+        ),
+    ))
+    .parse_next(input)?;
+    if parse_line_end(input).is_err() {
+        let amount = rest.split([';', '\n', '\r']).next().unwrap_or_default();
+        return Err(unsupported(
+            input,
+            format!("posting amount is not supported: {}", amount.trim()),
+        ));
+    }
+
+    let (unit_price, total_price) = match cost.or(price.map(|(total, amount)| (amount, total))) {
+        Some((amount, true)) => (None, Some(amount)),
+        Some((amount, false)) => (Some(amount), None),
+        None => (None, None),
+    };
+    Ok(Posting {
+        status,
+        account,
+        kind: PostingKind::Regular,
+        amount,
+        unit_price,
+        total_price,
+        balance_assertion: None,
+        inclusive_assertion: false,
+    })
+}
+
+#[derive(Clone)]
+enum HeaderItem {
+    String(String),
+    Tag(String),
+    Link(String),
+}
+
+fn parse_header_item(input: &mut &str) -> PResult<HeaderItem> {
+    let name = |input: &mut &str| {
+        take_while(1.., |c: char| {
+            c.is_alphanumeric() || ['-', '_', '/', '.'].contains(&c)
+        })
+        .map(str::to_string)
+        .parse_next(input)
+    };
+    alt((
+        parse_string.map(HeaderItem::String),
+        preceded('#', name).map(HeaderItem::Tag),
+        preceded('^', name).map(HeaderItem::Link),
+    ))
+    .parse_next(input)
+}
+
+/// Parses a transaction, its header line followed by its metadata and postings
+fn parse_transaction<'s>(date: NaiveDate) -> impl FnMut(&mut &'s str) -> PResult<Transaction> {
+    move |input: &mut &'s str| {
+        let status = alt((
+            '*'.value(Status::Cleared),
+            '!'.value(Status::Pending),
+            "txn".value(Status::Unmarked),
+        ))
+        .parse_next(input)?;
+        let items: Vec<HeaderItem> =
+            repeat(0.., preceded(space1, parse_header_item)).parse_next(input)?;
+        parse_line_end(input)?;
+        let metadata = parse_metadata_lines(input)?;
+        let (postings, posting_metadata): (Vec<Posting>, Vec<Vec<(String, String)>>) =
+            repeat(0.., (parse_posting, parse_metadata_lines))
+                .map(|postings: Vec<_>| postings.into_iter().unzip())
+                .parse_next(input)?;
+
+        let mut strings = vec![];
+        let mut tags = vec![];
+        for item in items {
+            match item {
+                HeaderItem::String(s) => strings.push(s),
+                HeaderItem::Tag(name) => tags.push(Tag { name, value: None }),
+                HeaderItem::Link(link) => tags.push(Tag {
+                    name: "link".to_string(),
+                    value: Some(link),
+                }),
+            }
+        }
+        // The narration is the last string, preceded by the payee; empty strings are missing
+        let mut strings = strings
+            .into_iter()
+            .map(|s| Some(s).filter(|s| !s.is_empty()));
+        let description = match (strings.next().flatten(), strings.next()) {
+            (note, None) => Description { payee: None, note },
+            (payee, Some(note)) => Description { payee, note },
+        };
+
+        let mut transaction = Transaction {
+            primary_date: date,
+            secondary_date: None,
+            status,
+            code: None,
+            description,
+            postings,
+            tags,
+        };
+        for (key, value) in metadata {
+            match key.as_str() {
+                "code" => transaction.code = Some(value),
+                "date2" => {
+                    let (date2, _) = parse_date(&mut value.as_str())?;
+                    transaction.secondary_date = Some(date2);
+                }
+                _ => transaction.tags.push(Tag {
+                    name: key,
+                    value: Some(value).filter(|v| v != "TRUE"),
+                }),
+            }
+        }
+        transaction.tags.extend(
+            posting_metadata
+                .into_iter()
+                .flatten()
+                .map(|(key, value)| Tag {
+                    name: key,
+                    value: Some(value).filter(|v| v != "TRUE"),
+                }),
+        );
+        Ok(transaction)
+    }
+}
+
+#[derive(Clone)]
+enum Directive {
+    Option(String, String),
+    Include(String),
+    PushTag(String),
+    PopTag(String),
+    Open(Account),
+    Commodity(Commodity),
+    Price(Price),
+    Balance(NaiveDate, Account, Amount),
+    Transaction(Transaction),
+    Pad,
+    Ignored,
+}
+
+/// Parses a directive starting with a date
+fn parse_dated_directive(input: &mut &str) -> PResult<Directive> {
+    let (date, _) = terminated(parse_date, space1).parse_next(input)?;
+    let state = number_state();
+    let directive = alt((
+        parse_transaction(date).map(Directive::Transaction),
+        (
+            preceded(("open", space1), parse_account),
+            take_till(0.., [';', '\n', '\r']),
+        )
+            .map(|(account, _)| Directive::Open(account)),
+        (
+            preceded(("commodity", space1), parse_commodity),
+            preceded(parse_line_end, parse_metadata_lines),
+        )
+            .map(|(name, metadata)| {
+                let format = metadata
+                    .into_iter()
+                    .find(|(key, _)| key == "format")
+                    .map(|(_, format)| format);
+                Directive::Commodity(Commodity { name, format })
+            }),
+        (
+            preceded(("price", space1), parse_commodity),
+            preceded(space1, parse_amount_with_state(&state)),
+        )
+            .map(|(commodity, amount)| {
+                Directive::Price(Price {
+                    commodity,
+                    date,
+                    amount,
+                })
+            }),
+        (
+            preceded(("balance", space1), parse_account),
+            preceded(space1, parse_amount_with_state(&state)),
+        )
+            .map(|(account, amount)| Directive::Balance(date, account, amount)),
+        "pad".value(Directive::Pad),
+        (
+            alt(("close", "note", "document", "event", "query", "custom")),
+            till_line_ending,
+        )
+            .value(Directive::Ignored),
+    ))
+    .parse_next(input)?;
+    match directive {
+        Directive::Transaction(_) | Directive::Commodity(_) | Directive::Pad => {}
+        _ => {
+            parse_line_end(input)?;
+            parse_metadata_lines(input)?;
+        }
+    }
+    Ok(directive)
+}
+
+/// Parses a directive or an ignored line
+fn parse_directive(input: &mut &str) -> PResult<Directive> {
+    alt((
+        parse_dated_directive,
+        terminated(
+            alt((
+                (
+                    preceded(("option", space1), parse_string),
+                    preceded(space1, parse_string),
+                )
+                    .map(|(name, value)| Directive::Option(name, value)),
+                preceded(("include", space1), parse_string).map(Directive::Include),
+                preceded(
+                    ("pushtag", space1, '#'),
+                    take_till(1.., [' ', '\t', '\n', '\r']),
+                )
+                .map(|t: &str| Directive::PushTag(t.to_string())),
+                preceded(
+                    ("poptag", space1, '#'),
+                    take_till(1.., [' ', '\t', '\n', '\r']),
+                )
+                .map(|t: &str| Directive::PopTag(t.to_string())),
+                ("plugin", space1, till_line_ending).value(Directive::Ignored),
+                (one_of([';', '*', '#']), till_line_ending).value(Directive::Ignored),
+                space0.value(Directive::Ignored),
+            )),
+            parse_line_end,
+        ),
+    ))
+    .parse_next(input)
+}
+
+/// Returns the type of the accounts under each root, renamed with the `name_*` options
+fn default_roots() -> BTreeMap<String, AccountType> {
+    [
+        ("Assets", AccountType::Asset),
+        ("Liabilities", AccountType::Liability),
+        ("Equity", AccountType::Equity),
+        ("Income", AccountType::Revenue),
+        ("Expenses", AccountType::Expense),
+    ]
+    .into_iter()
+    .map(|(root, account_type)| (root.to_string(), account_type))
+    .collect()
+}
+
+/// Returns the declared type of an opened account, unless its name already implies it
+fn account_type(roots: &BTreeMap<String, AccountType>, account: &Account) -> Option<AccountType> {
+    let root_type = *roots.get(&account.components()[0])?;
+    match account.inferred_type() {
+        Some(inferred) if inferred.is(root_type) => None,
+        _ => Some(root_type),
+    }
+}
+
+/// Parses the contents of a Beancount file
+///
+/// Include statements are resolved relative to `base_path`. The locations of the transactions
+/// are added to `locations` in the order of the flattened values, and the included files to
+/// `includes`. Balance directives become transactions with an inclusive balance assertion on the
+/// day before, placed before the first transaction of the file on or after the date of the
+/// directive.
+fn parse_beancount_contents(
+    input: &mut &str,
+    base_path: PathBuf,
+    path: Option<PathBuf>,
+    state: ParseState,
+    chain: &[PathBuf],
+    locations: &mut Vec<Location>,
+    includes: &mut Vec<IncludedFile>,
+) -> Result<Vec<Value>, HLParserError> {
+    let mut lines = LineCounter::new(input);
+    let mut roots = default_roots();
+    let mut pushed_tags: Vec<String> = vec![];
+    // The values of the file, each with the locations of its transactions
+    let mut values: Vec<(Value, Vec<Location>)> = vec![];
+    let mut balances = vec![];
+
+    while !input.is_empty() {
+        let line = lines.line(input);
+        let location = Location {
+            path: path.clone(),
+            line,
+        };
+        let directive = parse_directive.parse_next(input).map_err(|e| {
+            let cause = e
+                .clone()
+                .into_inner()
+                .and_then(|e| e.cause().map(ToString::to_string));
+            HLParserError::Parse(format!("line {}: {}", line, cause.unwrap_or(e.to_string())))
+        })?;
+        match directive {
+            Directive::Option(name, value) => {
+                let account_type = match name.as_str() {
+                    "name_assets" => AccountType::Asset,
+                    "name_liabilities" => AccountType::Liability,
+                    "name_equity" => AccountType::Equity,
+                    "name_income" => AccountType::Revenue,
+                    "name_expenses" => AccountType::Expense,
+                    _ => continue,
+                };
+                roots.retain(|_, t| *t != account_type);
+                roots.insert(value, account_type);
+            }
+            Directive::Include(pattern) => {
+                for path in resolve_include(&base_path, &pattern)? {
+                    let mut nested_locations = vec![];
+                    let mut nested_includes = vec![];
+                    let included = read_included_file(
+                        path.clone(),
+                        Some(InputFormat::Beancount),
+                        state.clone(),
+                        chain,
+                        &mut nested_locations,
+                        &mut nested_includes,
+                    )?;
+                    values.push((Value::Included(included), nested_locations));
+                    includes.push(IncludedFile {
+                        path,
+                        line,
+                        includes: nested_includes,
+                    });
+                }
+            }
+            Directive::PushTag(tag) => pushed_tags.push(tag),
+            Directive::PopTag(tag) => {
+                match pushed_tags.iter().rposition(|t| *t == tag) {
+                    Some(index) => pushed_tags.remove(index),
+                    None => {
+                        return Err(HLParserError::Parse(format!(
+                            "line {}: poptag #{} without a matching pushtag",
+                            line, tag
+                        )))
+                    }
+                };
+            }
+            Directive::Open(account) => {
+                let account_type = account_type(&roots, &account);
+                values.push((
+                    Value::Account(state.apply_to_account(&account), account_type),
+                    vec![],
+                ));
+            }
+            Directive::Commodity(commodity) => values.push((Value::Commodity(commodity), vec![])),
+            Directive::Price(price) => values.push((Value::Price(price), vec![])),
+            Directive::Balance(date, account, amount) => {
+                let transaction = Transaction {
+                    primary_date: date.checked_sub_days(Days::new(1)).unwrap_or(date),
+                    secondary_date: None,
+                    status: Status::Unmarked,
+                    code: None,
+                    description: Description {
+                        payee: None,
+                        note: Some("balance".to_string()),
+                    },
+                    postings: vec![Posting {
+                        status: Status::Unmarked,
+                        account,
+                        kind: PostingKind::Regular,
+                        amount: Some(Amount {
+                            currency: amount.currency.clone(),
+                            value: Decimal::ZERO,
+                        }),
+                        unit_price: None,
+                        total_price: None,
+                        balance_assertion: Some(amount),
+                        inclusive_assertion: true,
+                    }],
+                    tags: vec![],
+                };
+                balances.push((date, state.apply_to_transaction(transaction), location));
+            }
+            Directive::Transaction(mut transaction) => {
+                transaction.tags.extend(pushed_tags.iter().map(|name| Tag {
+                    name: name.clone(),
+                    value: None,
+                }));
+                values.push((
+                    Value::Transaction(state.apply_to_transaction(transaction)),
+                    vec![location],
+                ));
+            }
+            Directive::Pad => {
+                return Err(HLParserError::Parse(format!(
+                    "line {}: pad directives are not supported",
+                    line
+                )))
+            }
+            Directive::Ignored => {}
+        }
+    }
+
+    for (date, transaction, location) in balances {
+        let index = values
+            .iter()
+            .position(|(value, _)| matches!(value, Value::Transaction(t) if t.primary_date >= date))
+            .unwrap_or(values.len());
+        values.insert(index, (Value::Transaction(transaction), vec![location]));
+    }
+    Ok(values
+        .into_iter()
+        .map(|(value, value_locations)| {
+            locations.extend(value_locations);
+            value
+        })
+        .collect())
+}
+
+/// Parses a Beancount ledger into a journal
+///
+/// Transactions, `open`, `commodity`, `price` and `balance` directives, `include` statements and
+/// the `name_*` options are supported. Transaction metadata becomes tags, except `code` and
+/// `date2`, and links become `link` tags. Posting metadata, which journal postings cannot hold,
+/// becomes tags of the transaction. Balance directives become inclusive balance assertions, which
+/// include the subaccounts like in Beancount, on the day before, since Beancount checks them at
+/// the start of the day. `close` is ignored, so closed accounts stay open in the journal.
+/// `note`, `document`, `event`, `query`, `custom` and `plugin` are ignored too, and `pad` and
+/// amount expressions are rejected.
+///
+/// # Example
+///
+/// ```
+/// use hledger_parse::parse_beancount;
+///
+/// let mut input = r#"2024-01-01 open Assets:Bank
+/// 2024-01-01 open Expenses:Food
+///
+/// 2024-01-05 * "Shop" "groceries" #trip
+///   Expenses:Food  10.00 EUR
+///   Assets:Bank
+/// "#;
+/// let journal = parse_beancount(&mut input, None).unwrap();
+/// assert_eq!(
+///     "2024-01-05 * Shop | groceries ; trip:\n   Expenses:Food  10.00 EUR\n   Assets:Bank\n",
+///     format!("{}", journal.transactions()[0])
+/// );
+/// ```
+pub fn parse_beancount(
+    input: &mut &str,
+    base_path: Option<PathBuf>,
+) -> Result<Journal, HLParserError> {
+    parse_beancount_with_path(input, base_path, None)
+}
+
+/// Parses a Beancount ledger whose contents were read from `path`, which is used in locations
+pub(crate) fn parse_beancount_with_path(
+    input: &mut &str,
+    base_path: Option<PathBuf>,
+    path: Option<PathBuf>,
+) -> Result<Journal, HLParserError> {
+    let mut locations = vec![];
+    let mut includes = vec![];
+    let chain: Vec<PathBuf> = path.iter().filter_map(|p| p.canonicalize().ok()).collect();
+    let values = parse_beancount_contents(
+        input,
+        base_path.unwrap_or(std::env::current_dir().map_err(|e| HLParserError::IO(e.to_string()))?),
+        path,
+        ParseState::default(),
+        &chain,
+        &mut locations,
+        &mut includes,
+    )?;
+    Ok(journal_from_values(values, locations, includes))
+}
+
+/// Reads a Beancount file included by a journal or by another Beancount file
+///
+/// The journal parser state, e.g. account aliases, is applied to the accounts and transactions.
+pub fn read_beancount_from_path(
+    path: PathBuf,
+    state: &ParseState,
+    chain: &[PathBuf],
+    locations: &mut Vec<Location>,
+    includes: &mut Vec<IncludedFile>,
+) -> Result<Vec<Value>, HLParserError> {
+    let contents = std::fs::read_to_string(&path).map_err(|e| HLParserError::IO(e.to_string()))?;
+    let mut input = &contents[..];
+    let base_path = path.parent().map(Path::to_path_buf).unwrap_or_default();
+    parse_beancount_contents(
+        &mut input,
+        base_path,
+        Some(path),
+        state.clone(),
+        chain,
+        locations,
+        includes,
+    )
+}
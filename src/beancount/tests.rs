@@ -0,0 +1,470 @@
+use std::path::PathBuf;
+
+use rstest::rstest;
+use rust_decimal_macros::dec;
+
+use crate::{
+    amount::types::MixedAmount,
+    journal::{
+        parsers::parse_journal,
+        types::{IncludedFile, Journal, Location},
+    },
+    AccountType, BeancountIssue, Check, HLParserError,
+};
+
+use super::{
+    parsers::parse_beancount,
+    types::{write_beancount, BeancountMapping},
+};
+
+fn export(mut input: &str) -> (String, Vec<String>) {
+    let journal = parse_journal(&mut input, None).unwrap();
+    let export = write_beancount(&journal, &BeancountMapping::default());
+    (
+        export.text,
+        export.issues.iter().map(|i| i.to_string()).collect(),
+    )
+}
+
+#[rstest]
+#[case::roots("assets:bank:checking", "Assets:Bank:Checking")]
+#[case::revenue_root("revenue:salary", "Income:Salary")]
+#[case::singular_root("expense:food", "Expenses:Food")]
+#[case::spaces("liabilities:credit card", "Liabilities:Credit-card")]
+#[case::punctuation("equity:opening/closing", "Equity:Opening-closing")]
+#[case::leading_symbol("assets:_hidden", "Assets:X-hidden")]
+fn test_write_beancount_account_names(#[case] account: &str, #[case] expected: &str) {
+    let (text, issues) = export(&format!("P 2024-01-01 EUR 1.1 USD\naccount {}\n", account));
+    assert_eq!(
+        text.lines().next(),
+        Some(format!("2024-01-01 open {}", expected).as_str())
+    );
+    assert!(issues.is_empty());
+}
+
+#[test]
+fn test_write_beancount() {
+    let (text, issues) = export(
+        r#"commodity 1.000,00 EUR
+account assets:bank
+account assets:unused
+
+P 2024-01-01 BTC 40000 $
+
+2024-01-03 ! (42) Shop | groceries  ; trip:japan, reviewed:
+    expenses:food  10 EUR
+    * assets:bank  -10 EUR = 90 EUR
+
+2024-01-02=2024-01-05 exchange
+    assets:bank  100 EUR @@ 110 $
+    assets:wallet  -110 $
+"#,
+    );
+    assert_eq!(
+        text,
+        r#"2024-01-01 open Assets:Unused
+2024-01-02 open Assets:Bank
+2024-01-02 open Assets:Wallet
+2024-01-03 open Expenses:Food
+
+2024-01-01 commodity EUR
+  format: "1.000,00 EUR"
+
+2024-01-01 price BTC 40000 USD
+
+2024-01-02 txn "exchange"
+  date2: 2024-01-05
+  Assets:Bank  100 EUR @@ 110 USD
+  Assets:Wallet  -110 USD
+
+2024-01-03 ! "Shop" "groceries"
+  code: "42"
+  trip: "japan"
+  reviewed: TRUE
+  Expenses:Food  10 EUR
+  * Assets:Bank  -10 EUR
+
+2024-01-04 balance Assets:Bank  90 EUR
+"#
+    );
+    assert!(issues.is_empty());
+}
+
+#[test]
+fn test_write_beancount_assertions() {
+    let (text, issues) = export(
+        r#"2024-01-02 opening
+    assets:bank:checking  100 EUR = 100 EUR
+    assets:bank  0 EUR =* 100 EUR
+    assets:bank  0 EUR = 0 EUR
+    equity:opening
+"#,
+    );
+    assert!(text.ends_with(
+        r#"
+2024-01-03 balance Assets:Bank:Checking  100 EUR
+
+2024-01-03 balance Assets:Bank  100 EUR
+"#
+    ));
+    assert_eq!(
+        issues,
+        vec!["line 1: balance assertion on assets:bank excludes its subaccounts, unlike Beancount balances, and is not exported"]
+    );
+}
+
+#[rstest]
+#[case::later_transaction(
+    r#"2024-01-02 A
+    assets:bank  -10 EUR = -10 EUR
+    expenses:food
+
+2024-01-02 B
+    assets:bank  -5 EUR
+    expenses:food
+"#,
+    "line 1: balance assertion on assets:bank is followed by other postings on the same day, unlike Beancount balances, and is not exported"
+)]
+#[case::later_subaccount_posting(
+    r#"2024-01-02 A
+    assets:bank  -10 EUR =* -10 EUR
+    assets:bank:savings  -5 EUR
+    expenses:food
+"#,
+    "line 1: balance assertion on assets:bank is followed by other postings on the same day, unlike Beancount balances, and is not exported"
+)]
+fn test_write_beancount_shifted_assertions(#[case] input: &str, #[case] expected: &str) {
+    let (text, issues) = export(input);
+    assert!(!text.contains("balance"));
+    assert_eq!(issues, vec![expected]);
+}
+
+#[test]
+fn test_write_beancount_last_assertion_of_day() {
+    let (text, issues) = export(
+        r#"2024-01-02 A
+    assets:bank  -10 EUR
+    expenses:food
+
+2024-01-02 B
+    assets:bank  -5 EUR = -15 EUR
+    expenses:food
+
+2024-01-03 C
+    assets:bank  -1 EUR
+    expenses:food
+"#,
+    );
+    assert!(text.contains("2024-01-03 balance Assets:Bank  -15 EUR\n"));
+    assert_eq!(issues, Vec::<String>::new());
+}
+
+#[test]
+fn test_write_beancount_without_dates() {
+    let (text, issues) = export("account assets:bank\ncommodity EUR\n");
+    assert_eq!(text, "");
+    assert_eq!(
+        issues,
+        vec![
+            "account assets:bank cannot be dated without transactions or prices and is not exported",
+            "commodity EUR cannot be dated without transactions or prices and is not exported"
+        ]
+    );
+}
+
+#[test]
+fn test_write_beancount_issues() {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/beancount");
+    let contents = std::fs::read_to_string(path.join("export.journal")).unwrap();
+    let journal = parse_journal(&mut &contents[..], Some(path)).unwrap();
+    let export = write_beancount(&journal, &BeancountMapping::default());
+    let amount = |currency: &str, value| crate::Amount {
+        currency: currency.to_string(),
+        value,
+    };
+    let mut residual = MixedAmount::from(amount("h", dec!(3)));
+    residual.add_amount(&amount("€", dec!(-3)));
+
+    assert_eq!(
+        export.issues,
+        vec![
+            BeancountIssue::UntypedAccount {
+                account: "client:acme".into(),
+                renamed: "Expenses:Client:Acme".to_string()
+            },
+            BeancountIssue::VirtualPosting {
+                account: "client:acme".into(),
+                location: journal.transaction_location(0).cloned()
+            },
+            BeancountIssue::RenamedCommodity {
+                commodity: "h".to_string(),
+                renamed: "H".to_string()
+            },
+            BeancountIssue::AccountCollision {
+                accounts: vec!["assets:Cash".into(), "assets:cash".into()],
+                renamed: "Assets:Cash".to_string()
+            },
+            BeancountIssue::UnbalancedTransaction {
+                residual,
+                location: journal.transaction_location(2).cloned()
+            },
+            BeancountIssue::UnsupportedDirective {
+                directive: "payee Shop".to_string()
+            },
+            BeancountIssue::UnsupportedDirective {
+                directive: "tag trip".to_string()
+            },
+        ]
+    );
+    assert_eq!(
+        export.issues[1].to_string(),
+        format!(
+            "{}: virtual posting to client:acme has no Beancount equivalent and is not exported",
+            journal.transaction_location(0).unwrap()
+        )
+    );
+    assert_eq!(
+        export.issues[4].to_string(),
+        "line 10: transaction is unbalanced by 3 h, -3 €, which Beancount rejects"
+    );
+}
+
+#[test]
+fn test_write_beancount_virtual_postings() {
+    let mut input = r#"2024-01-05 Shop
+    expenses:food  10 EUR
+    assets:bank  = 90 EUR
+    (budget:food)  -10 EUR = -10 EUR
+"#;
+    let journal = parse_journal(&mut input, None).unwrap();
+    let export = write_beancount(&journal, &BeancountMapping::default());
+
+    assert!(!export.text.contains("Budget"));
+    assert_eq!(
+        export.issues,
+        vec![BeancountIssue::VirtualPosting {
+            account: "budget:food".into(),
+            location: journal.transaction_location(0).cloned()
+        }]
+    );
+}
+
+fn transactions(mut input: &str) -> String {
+    let journal = parse_beancount(&mut input, None).unwrap();
+    journal
+        .transactions()
+        .iter()
+        .map(|t| t.to_string())
+        .collect()
+}
+
+#[rstest]
+#[case::narration("2024-01-05 * \"groceries\"", "2024-01-05 * groceries\n")]
+#[case::payee(
+    "2024-01-05 ! \"Shop\" \"groceries\"",
+    "2024-01-05 ! Shop | groceries\n"
+)]
+#[case::empty_narration("2024-01-05 txn \"Shop\" \"\"", "2024-01-05 Shop |\n")]
+#[case::escapes(
+    r#"2024-01-05 * "the \"best\" shop""#,
+    "2024-01-05 * the \"best\" shop\n"
+)]
+#[case::tags_links(
+    "2024-01-05 * \"groceries\" #trip ^receipt",
+    "2024-01-05 * groceries ; trip:, link:receipt\n"
+)]
+fn test_parse_beancount_headers(#[case] header: &str, #[case] expected: &str) {
+    assert_eq!(transactions(&format!("{}\n", header)), expected.to_string());
+}
+
+#[rstest]
+#[case::amount("Assets:Bank  -10.00 EUR", "Assets:Bank  -10.00 EUR")]
+#[case::missing_amount("Assets:Bank", "Assets:Bank")]
+#[case::flag("! Assets:Bank  -10 EUR  ; pending", "! Assets:Bank  -10 EUR")]
+#[case::grouping("Assets:Bank  1,234.50 EUR", "Assets:Bank  1234.50 EUR")]
+#[case::unit_price("Assets:Bank  10 USD @ 0.90 EUR", "Assets:Bank  10 USD @ 0.90 EUR")]
+#[case::total_price("Assets:Bank  10 USD @@ 9 EUR", "Assets:Bank  10 USD @@ 9 EUR")]
+#[case::unit_cost("Assets:Broker  2 ACME {150 EUR}", "Assets:Broker  2 ACME @ 150 EUR")]
+#[case::total_cost(
+    "Assets:Broker  2 ACME {{300 EUR}}",
+    "Assets:Broker  2 ACME @@ 300 EUR"
+)]
+#[case::lot(
+    "Assets:Broker  2 ACME {150 EUR, 2024-01-01, \"lot\"}",
+    "Assets:Broker  2 ACME @ 150 EUR"
+)]
+#[case::cost_over_price(
+    "Assets:Broker  -2 ACME {150 EUR} @ 170 EUR",
+    "Assets:Broker  -2 ACME @ 150 EUR"
+)]
+fn test_parse_beancount_postings(#[case] posting: &str, #[case] expected: &str) {
+    let text = transactions(&format!("2024-01-05 txn\n  {}\n", posting));
+    assert_eq!(text.lines().nth(1).unwrap().trim(), expected);
+}
+
+#[test]
+fn test_parse_beancount() {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/beancount");
+    let journal = Journal::try_from(path.join("ledger.beancount")).unwrap();
+
+    assert_eq!(
+        journal
+            .transactions()
+            .iter()
+            .map(|t| t.to_string())
+            .collect::<Vec<String>>(),
+        vec![
+            "2024-01-03=2024-01-05! Shop | groceries ; trip:, link:receipt-42, reviewed:, receipt:r-42, household:\n   Expenses:Food  10.00 EUR\n  * Assets:Bank  -10.00 EUR\n",
+            "2024-01-10 * salary\n   Assets:Bank  1000.00 EUR\n   Earnings:Salary\n",
+            "2024-01-14 balance\n   Assets:Bank  0 EUR\n",
+            "2024-01-20 * buy shares\n   Assets:Broker  2 ACME @ 150.00 EUR\n   Assets:Bank  -300.00 EUR\n",
+        ]
+    );
+    assert_eq!(journal.transactions()[0].code, Some("42".to_string()));
+    assert_eq!(
+        journal.transactions()[2].postings[0]
+            .balance_assertion
+            .as_ref()
+            .map(|a| a.to_string()),
+        Some("990.00 EUR".to_string())
+    );
+    assert_eq!(
+        (0..4)
+            .map(|i| journal.transaction_location(i).unwrap().line)
+            .collect::<Vec<usize>>(),
+        vec![17, 27, 31, 33]
+    );
+    assert_eq!(
+        journal.account_type(&"Earnings:Salary".into()),
+        Some(AccountType::Revenue)
+    );
+    assert_eq!(
+        journal.account_type(&"Assets:Bank".into()),
+        Some(AccountType::Cash)
+    );
+    assert_eq!(journal.accounts().len(), 4);
+    assert_eq!(
+        journal.commodities()[0].format,
+        Some("1.000,00 EUR".to_string())
+    );
+    assert_eq!(journal.prices().len(), 2);
+    assert_eq!(
+        journal.includes(),
+        &[IncludedFile {
+            path: path.join("prices.beancount"),
+            line: 13,
+            includes: vec![],
+        }]
+    );
+    assert_eq!(
+        journal.check(&[
+            Check::Accounts,
+            Check::OrderedDates,
+            Check::BalancedNoAutoConversion,
+            Check::Assertions,
+        ]),
+        vec![]
+    );
+}
+
+#[test]
+fn test_parse_beancount_included_by_journal() {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/beancount");
+    let journal = Journal::try_from(path.join("main.journal")).unwrap();
+
+    let transactions = journal.transactions();
+    assert_eq!(transactions.len(), 5);
+    assert_eq!(
+        transactions[0].postings[0].account,
+        "expenses:groceries".into()
+    );
+    assert_eq!(
+        journal.transaction_location(4),
+        Some(&Location {
+            path: Some(path.join("main.journal")),
+            line: 5
+        })
+    );
+    assert_eq!(
+        journal.transaction_location(0),
+        Some(&Location {
+            path: Some(path.join("ledger.beancount")),
+            line: 17
+        })
+    );
+    assert_eq!(journal.includes()[0].includes.len(), 1);
+}
+
+#[test]
+fn test_parse_beancount_inclusive_balance() {
+    let mut input = r#"2024-01-01 open Assets:Bank:Checking
+2024-01-01 open Equity:Opening
+
+2024-01-02 * "opening"
+  Assets:Bank:Checking  100.00 EUR
+  Equity:Opening
+
+2024-01-03 balance Assets:Bank  100.00 EUR
+"#;
+    let journal = parse_beancount(&mut input, None).unwrap();
+    assert!(journal.transactions()[1].postings[0].inclusive_assertion);
+    assert!(journal.check(&[Check::Assertions]).is_empty());
+}
+
+#[rstest]
+#[case::pad(
+    "2024-01-01 pad Assets:Bank Equity:Opening\n",
+    "line 1: pad directives are not supported"
+)]
+#[case::poptag(
+    "pushtag #a\npoptag #b\n",
+    "line 2: poptag #b without a matching pushtag"
+)]
+#[case::unknown_directive("\n2024-01-01 budget Expenses:Food\n", "line 2: ")]
+#[case::lowercase_account("2024-01-01 open assets:bank\n", "line 1: ")]
+#[case::empty_cost(
+    "2024-01-01 * \"sell\"\n  Assets:Broker  -2 ACME {}\n  Assets:Bank\n",
+    "line 1: costs without an amount are not supported: {}"
+)]
+#[case::cost_without_amount(
+    "2024-01-01 * \"sell\"\n  Assets:Broker  -2 ACME {{2023-01-01}}\n  Assets:Bank\n",
+    "line 1: costs without an amount are not supported: {{2023-01-01}}"
+)]
+#[case::amount_expression(
+    "2024-01-01 * \"shop\"\n  Expenses:Food  (10 + 2) USD\n  Assets:Bank\n",
+    "line 1: posting amount is not supported: (10 + 2) USD"
+)]
+fn test_parse_beancount_errors(#[case] mut input: &str, #[case] expected: &str) {
+    match parse_beancount(&mut input, None) {
+        Err(HLParserError::Parse(message)) => assert!(
+            message.starts_with(expected),
+            "{} does not start with {}",
+            message,
+            expected
+        ),
+        result => panic!("unexpected result {:?}", result),
+    }
+}
+
+#[test]
+fn test_parse_beancount_round_trip() {
+    let mut input = r#"commodity 1.000,00 EUR
+
+P 2024-01-01 ACME 150 EUR
+
+2024-01-02 * (7) Shop | groceries  ; trip:japan, reviewed:
+    Expenses:Food  10.00 EUR
+    ! Assets:Bank  -10.00 EUR
+
+2024-01-03=2024-01-04 transfer
+    Assets:Broker  2 ACME @@ 300 EUR
+    Assets:Bank  -300 EUR
+"#;
+    let journal = parse_journal(&mut input, None).unwrap();
+    let export = write_beancount(&journal, &BeancountMapping::default());
+    let imported = parse_beancount(&mut &export.text[..], None).unwrap();
+
+    assert_eq!(imported.transactions(), journal.transactions());
+    assert_eq!(imported.prices(), journal.prices());
+    assert_eq!(imported.commodities(), journal.commodities());
+}
@@ -0,0 +1,481 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use chrono::{Days, NaiveDate};
+
+use crate::{
+    account::types::{Account, AccountType},
+    amount::types::Amount,
+    check::types::Check,
+    journal::types::{Journal, Location},
+    posting::types::{Posting, PostingKind},
+    status::types::Status,
+    transaction::types::Transaction,
+    BeancountIssue, CheckError,
+};
+
+/// How commodities are named in Beancount
+///
+/// Commodities missing from the map are uppercased and stripped of the characters Beancount
+/// does not allow.
+///
+/// # Example
+///
+/// ```
+/// use hledger_parse::BeancountMapping;
+///
+/// let mapping = BeancountMapping::default();
+/// assert_eq!(Some(&"USD".to_string()), mapping.commodities.get("$"));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BeancountMapping {
+    /// The Beancount name of each journal commodity
+    pub commodities: BTreeMap<String, String>,
+}
+
+impl Default for BeancountMapping {
+    fn default() -> Self {
+        Self {
+            commodities: [("$", "USD"), ("€", "EUR"), ("£", "GBP"), ("¥", "JPY")]
+                .into_iter()
+                .map(|(commodity, name)| (commodity.to_string(), name.to_string()))
+                .collect(),
+        }
+    }
+}
+
+/// A journal converted to Beancount, with what could not be expressed as is
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BeancountExport {
+    /// The Beancount ledger
+    pub text: String,
+    /// The renamings and the parts of the journal which Beancount cannot represent
+    pub issues: Vec<BeancountIssue>,
+}
+
+struct Exporter<'a> {
+    journal: &'a Journal,
+    mapping: &'a BeancountMapping,
+    accounts: BTreeMap<Account, String>,
+    commodities: BTreeMap<String, String>,
+    issues: Vec<BeancountIssue>,
+}
+
+impl Exporter<'_> {
+    fn account(&mut self, account: &Account) -> String {
+        if let Some(name) = self.accounts.get(account) {
+            return name.clone();
+        }
+        let components = account.components();
+        let root = match self.journal.account_type(account) {
+            Some(AccountType::Asset | AccountType::Cash) => Some("Assets"),
+            Some(AccountType::Liability) => Some("Liabilities"),
+            Some(AccountType::Equity | AccountType::Conversion) => Some("Equity"),
+            Some(AccountType::Revenue) => Some("Income"),
+            Some(AccountType::Expense) => Some("Expenses"),
+            None => None,
+        };
+        let has_type_root = Account::from(components[0].as_str())
+            .inferred_type()
+            .is_some();
+        let name = std::iter::once(root.unwrap_or("Expenses").to_string())
+            .chain(
+                components
+                    .iter()
+                    .skip(usize::from(has_type_root))
+                    .map(|c| sanitize_component(c)),
+            )
+            .collect::<Vec<String>>()
+            .join(":");
+        if root.is_none() {
+            self.issues.push(BeancountIssue::UntypedAccount {
+                account: account.clone(),
+                renamed: name.clone(),
+            });
+        }
+        self.accounts.insert(account.clone(), name.clone());
+        name
+    }
+
+    fn commodity(&mut self, commodity: &str) -> String {
+        if let Some(name) = self.mapping.commodities.get(commodity) {
+            return name.clone();
+        }
+        if let Some(name) = self.commodities.get(commodity) {
+            return name.clone();
+        }
+        let name = sanitize_commodity(commodity);
+        if name != commodity {
+            self.issues.push(BeancountIssue::RenamedCommodity {
+                commodity: commodity.to_string(),
+                renamed: name.clone(),
+            });
+        }
+        self.commodities.insert(commodity.to_string(), name.clone());
+        name
+    }
+
+    fn amount(&mut self, amount: &Amount) -> String {
+        format!("{} {}", amount.value, self.commodity(&amount.currency))
+    }
+
+    fn posting(&mut self, posting: &Posting) -> String {
+        let mut line = format!("  {}", flag(&posting.status, ""));
+        line.push_str(&self.account(&posting.account));
+        if let Some(amount) = &posting.amount {
+            line.push_str(&format!("  {}", self.amount(amount)));
+        }
+        if let Some(price) = &posting.unit_price {
+            line.push_str(&format!(" @ {}", self.amount(price)));
+        }
+        if let Some(price) = &posting.total_price {
+            line.push_str(&format!(" @@ {}", self.amount(price)));
+        }
+        line
+    }
+
+    fn transaction(&mut self, transaction: &Transaction, location: Option<&Location>) -> String {
+        let mut text = format!(
+            "{} {}",
+            transaction.primary_date,
+            flag(&transaction.status, "txn ").trim_end()
+        );
+        if let Some(payee) = &transaction.description.payee {
+            text.push_str(&format!(" {}", quote(payee)));
+        }
+        text.push_str(&format!(
+            " {}\n",
+            quote(transaction.description.note.as_deref().unwrap_or_default())
+        ));
+        if let Some(code) = &transaction.code {
+            text.push_str(&format!("  code: {}\n", quote(code)));
+        }
+        if let Some(date) = transaction.secondary_date {
+            text.push_str(&format!("  date2: {}\n", date));
+        }
+        for tag in &transaction.tags {
+            let value = match &tag.value {
+                Some(value) => quote(value),
+                None => "TRUE".to_string(),
+            };
+            text.push_str(&format!("  {}: {}\n", metadata_key(&tag.name), value));
+        }
+        for posting in &transaction.postings {
+            if posting.kind == PostingKind::Virtual {
+                self.issues.push(BeancountIssue::VirtualPosting {
+                    account: posting.account.clone(),
+                    location: location.cloned(),
+                });
+                continue;
+            }
+            let line = self.posting(posting);
+            text.push_str(&line);
+            text.push('\n');
+        }
+        text
+    }
+}
+
+/// Returns the postings of a transaction which Beancount can express, all but the virtual ones
+fn exported_postings(transaction: &Transaction) -> impl Iterator<Item = &Posting> {
+    transaction
+        .postings
+        .iter()
+        .filter(|p| p.kind != PostingKind::Virtual)
+}
+
+fn flag(status: &Status, unmarked: &str) -> String {
+    match status {
+        Status::Unmarked => unmarked.to_string(),
+        status => format!("{} ", status),
+    }
+}
+
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Makes an account name component valid in Beancount: capitalised, without spaces
+fn sanitize_component(component: &str) -> String {
+    let sanitized: String = component
+        .trim()
+        .chars()
+        .map(|c| match c.is_alphanumeric() || c == '-' {
+            true => c,
+            false => '-',
+        })
+        .collect();
+    let mut chars = sanitized.chars();
+    match chars.next() {
+        Some(first) if first.is_alphanumeric() => first.to_uppercase().chain(chars).collect(),
+        _ => format!("X{}", sanitized),
+    }
+}
+
+/// Makes a commodity name valid in Beancount: uppercase letters, digits and `'._-`, starting
+/// with a letter and ending with a letter or digit, at most 24 characters
+fn sanitize_commodity(commodity: &str) -> String {
+    let sanitized: String = commodity
+        .to_uppercase()
+        .chars()
+        .filter(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || "'._-".contains(*c))
+        .skip_while(|c| !c.is_ascii_uppercase())
+        .take(24)
+        .collect();
+    let sanitized = sanitized.trim_end_matches(|c: char| !c.is_ascii_alphanumeric());
+    match sanitized.is_empty() {
+        true => "UNKNOWN".to_string(),
+        false => sanitized.to_string(),
+    }
+}
+
+/// Makes a tag name a valid Beancount metadata key, starting with a lowercase letter
+fn metadata_key(name: &str) -> String {
+    let key: String = name
+        .chars()
+        .map(
+            |c| match c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                true => c,
+                false => '-',
+            },
+        )
+        .collect();
+    let mut chars = key.chars();
+    match chars.next() {
+        Some(first) if first.is_ascii_alphabetic() => {
+            first.to_ascii_lowercase().to_string() + chars.as_str()
+        }
+        _ => format!("x{}", key),
+    }
+}
+
+/// Converts a journal to a Beancount ledger
+///
+/// Accounts are renamed under the Beancount root of their type and opened on their first use, or
+/// on the first date of the journal if they are only declared. Commodity declarations and prices
+/// become `commodity` and `price` directives, and balance assertions become `balance` directives
+/// on the next day, since Beancount checks balances at the start of the day. Codes, secondary
+/// dates and tags become transaction metadata.
+///
+/// Renamed untyped accounts, colliding account names, renamed commodities, unbalanced
+/// transactions, virtual postings, declarations without Beancount equivalent and declarations
+/// of a journal without dates are reported as issues. So are the assertions which exclude the
+/// subaccounts of an account that has some, since Beancount balances include them, and the
+/// assertions followed by other postings to the account on the same day, since Beancount would
+/// check them after those postings.
+///
+/// # Example
+///
+/// ```
+/// use hledger_parse::{parse_journal, write_beancount, BeancountMapping};
+///
+/// let mut input = r#"2024-01-02 * Shop | groceries  ; trip:
+///     expenses:food  10 EUR
+///     assets:bank account  = 90 EUR
+/// "#;
+/// let journal = parse_journal(&mut input, None).unwrap();
+/// let export = write_beancount(&journal, &BeancountMapping::default());
+/// assert_eq!(
+///     r#"2024-01-02 open Assets:Bank-account
+/// 2024-01-02 open Expenses:Food
+///
+/// 2024-01-02 * "Shop" "groceries"
+///   trip: TRUE
+///   Expenses:Food  10 EUR
+///   Assets:Bank-account
+///
+/// 2024-01-03 balance Assets:Bank-account  90 EUR
+/// "#,
+///     export.text
+/// );
+/// assert!(export.issues.is_empty());
+/// ```
+pub fn write_beancount(journal: &Journal, mapping: &BeancountMapping) -> BeancountExport {
+    let mut exporter = Exporter {
+        journal,
+        mapping,
+        accounts: BTreeMap::new(),
+        commodities: BTreeMap::new(),
+        issues: vec![],
+    };
+    let transactions = journal.transactions();
+    let prices = journal.prices();
+    let first_date = transactions
+        .iter()
+        .map(|t| t.primary_date)
+        .chain(prices.iter().map(|p| p.date))
+        .min();
+
+    let mut first_uses: BTreeMap<Account, NaiveDate> = BTreeMap::new();
+    for transaction in &transactions {
+        for posting in exported_postings(transaction) {
+            let date = first_uses
+                .entry(posting.account.clone())
+                .or_insert(transaction.primary_date);
+            *date = (*date).min(transaction.primary_date);
+        }
+    }
+    for account in journal.accounts() {
+        match first_date {
+            Some(date) => {
+                first_uses.entry(account).or_insert(date);
+            }
+            None => exporter.issues.push(BeancountIssue::UndatedDirective {
+                directive: format!("account {}", account),
+            }),
+        }
+    }
+    let mut openings: Vec<(NaiveDate, Account)> = first_uses
+        .into_iter()
+        .map(|(account, date)| (date, account))
+        .collect();
+    openings.sort();
+    let accounts: BTreeSet<&Account> = openings.iter().map(|(_, a)| a).collect();
+    let has_subaccounts = |account: &Account| {
+        accounts
+            .range::<&Account, _>(account..)
+            .take_while(|a| a.as_str().starts_with(account.as_str()))
+            .any(|a| a.is_child_of(account))
+    };
+
+    let mut text = String::new();
+    for (date, account) in &openings {
+        let name = exporter.account(account);
+        text.push_str(&format!("{} open {}\n", date, name));
+    }
+
+    let mut directives: Vec<(NaiveDate, String)> = vec![];
+    for commodity in journal.commodities() {
+        let Some(date) = first_date else {
+            exporter.issues.push(BeancountIssue::UndatedDirective {
+                directive: format!("commodity {}", commodity.name),
+            });
+            continue;
+        };
+        let mut directive = format!(
+            "{} commodity {}\n",
+            date,
+            exporter.commodity(&commodity.name)
+        );
+        if let Some(format) = &commodity.format {
+            directive.push_str(&format!("  format: {}\n", quote(format)));
+        }
+        directives.push((date, directive));
+    }
+    for price in &prices {
+        let directive = format!(
+            "{} price {} {}\n",
+            price.date,
+            exporter.commodity(&price.commodity),
+            exporter.amount(&price.amount)
+        );
+        directives.push((price.date, directive));
+    }
+    let mut last_changes: BTreeMap<(NaiveDate, &Account), (usize, usize)> = BTreeMap::new();
+    for (index, transaction) in transactions.iter().enumerate() {
+        let amounts = transaction.posting_amounts();
+        for (position, (posting, amount)) in transaction.postings.iter().zip(amounts).enumerate() {
+            if posting.kind != PostingKind::Virtual && !amount.is_zero() {
+                last_changes.insert(
+                    (transaction.primary_date, &posting.account),
+                    (index, position),
+                );
+            }
+        }
+    }
+    let changed_later = |date: NaiveDate, posting: &Posting, asserted: (usize, usize)| {
+        last_changes
+            .range::<(NaiveDate, &Account), _>((date, &posting.account)..)
+            .take_while(|((d, a), _)| {
+                *d == date && a.as_str().starts_with(posting.account.as_str())
+            })
+            .any(|((_, account), changed)| {
+                (**account == posting.account
+                    || (posting.inclusive_assertion && account.is_child_of(&posting.account)))
+                    && *changed > asserted
+            })
+    };
+    let mut balances = vec![];
+    for (index, transaction) in transactions.iter().enumerate() {
+        let location = journal.transaction_location(index);
+        directives.push((
+            transaction.primary_date,
+            exporter.transaction(transaction, location),
+        ));
+        for (position, posting) in transaction.postings.iter().enumerate() {
+            let Some(assertion) = &posting.balance_assertion else {
+                continue;
+            };
+            if posting.kind == PostingKind::Virtual {
+                continue;
+            }
+            if !posting.inclusive_assertion && has_subaccounts(&posting.account) {
+                exporter.issues.push(BeancountIssue::ExclusiveAssertion {
+                    account: posting.account.clone(),
+                    location: location.cloned(),
+                });
+                continue;
+            }
+            if changed_later(transaction.primary_date, posting, (index, position)) {
+                exporter.issues.push(BeancountIssue::ShiftedAssertion {
+                    account: posting.account.clone(),
+                    location: location.cloned(),
+                });
+                continue;
+            }
+            let date = transaction
+                .primary_date
+                .checked_add_days(Days::new(1))
+                .unwrap_or(transaction.primary_date);
+            let account = exporter.account(&posting.account);
+            let amount = exporter.amount(assertion);
+            balances.push((date, format!("{} balance {}  {}\n", date, account, amount)));
+        }
+    }
+    directives.extend(balances);
+    directives.sort_by_key(|(date, _)| *date);
+    for (_, directive) in directives {
+        text.push('\n');
+        text.push_str(&directive);
+    }
+
+    let mut renamed: BTreeMap<&String, Vec<Account>> = BTreeMap::new();
+    for (account, name) in &exporter.accounts {
+        renamed.entry(name).or_default().push(account.clone());
+    }
+    let collisions: Vec<BeancountIssue> = renamed
+        .into_iter()
+        .filter(|(_, accounts)| accounts.len() > 1)
+        .map(|(name, accounts)| BeancountIssue::AccountCollision {
+            accounts,
+            renamed: name.clone(),
+        })
+        .collect();
+
+    let mut issues = exporter.issues;
+    issues.extend(collisions);
+    for error in Check::BalancedNoAutoConversion.run(journal) {
+        if let CheckError::UnbalancedTransaction { residual, location } = error {
+            issues.push(BeancountIssue::UnbalancedTransaction { residual, location });
+        }
+    }
+    let declarations: BTreeSet<String> = journal
+        .declared_payees()
+        .into_iter()
+        .map(|payee| format!("payee {}", payee))
+        .chain(
+            journal
+                .declared_tags()
+                .into_iter()
+                .map(|tag| format!("tag {}", tag)),
+        )
+        .collect();
+    issues.extend(
+        declarations
+            .into_iter()
+            .map(|directive| BeancountIssue::UnsupportedDirective { directive }),
+    );
+
+    BeancountExport { text, issues }
+}
@@ -0,0 +1,273 @@
+use rstest::rstest;
+
+use crate::{journal::parsers::parse_journal, journal::types::Journal, CheckError};
+
+use super::types::Check;
+
+fn journal(mut input: &str) -> Journal {
+    parse_journal(&mut input, None).unwrap()
+}
+
+fn messages(journal: &Journal, check: Check) -> Vec<String> {
+    journal
+        .check(&[check])
+        .iter()
+        .map(|e| format!("{}", e))
+        .collect()
+}
+
+#[rstest]
+#[case("accounts", Check::Accounts)]
+#[case("commodities", Check::Commodities)]
+#[case("payees", Check::Payees)]
+#[case("tags", Check::Tags)]
+#[case("ordereddates", Check::OrderedDates)]
+#[case("uniqueleafnames", Check::UniqueLeafNames)]
+#[case("balancednoautoconversion", Check::BalancedNoAutoConversion)]
+#[case("assertions", Check::Assertions)]
+#[case("duplicates", Check::Duplicates)]
+fn test_check_names(#[case] name: &str, #[case] check: Check) {
+    assert_eq!(name.parse::<Check>().unwrap(), check);
+    assert_eq!(format!("{}", check), name);
+}
+
+#[test]
+fn test_check_unknown_name() {
+    assert!("strict".parse::<Check>().is_err());
+}
+
+#[test]
+fn test_check_accounts() {
+    let journal = journal(
+        r#"account assets:cash
+
+2024-01-01 a
+    expenses:food  10 EUR
+    assets:cash
+
+2024-01-02 b
+    expenses:food  10 EUR
+    expenses:rent  10 EUR
+    assets:cash
+"#,
+    );
+    assert_eq!(
+        messages(&journal, Check::Accounts),
+        vec![
+            "line 3: account expenses:food is not declared",
+            "line 7: account expenses:rent is not declared"
+        ]
+    );
+}
+
+#[test]
+fn test_check_commodities() {
+    let journal = journal(
+        r#"commodity EUR
+
+P 2024-01-01 BTC 40000 EUR
+
+2024-01-02 a
+    assets:cash  10 EUR @@ 11 USD
+    assets:bank  -11 USD
+"#,
+    );
+    assert_eq!(
+        messages(&journal, Check::Commodities),
+        vec![
+            "line 5: commodity USD is not declared",
+            "commodity BTC is not declared"
+        ]
+    );
+}
+
+#[test]
+fn test_check_payees_and_tags() {
+    let journal = journal(
+        r#"payee Shop
+tag trip
+
+2024-01-01 Shop | food  ; trip:
+    expenses:food  10 EUR
+    assets:cash
+
+2024-01-02 Bar  ; trip:, project:x
+    expenses:drinks  10 EUR
+    assets:cash
+"#,
+    );
+    assert_eq!(
+        messages(&journal, Check::Payees),
+        vec!["line 8: payee Bar is not declared"]
+    );
+    assert_eq!(
+        messages(&journal, Check::Tags),
+        vec!["line 8: tag project is not declared"]
+    );
+}
+
+#[test]
+fn test_check_ordered_dates() {
+    let journal = journal(
+        r#"2024-01-05 a
+    expenses:food  10 EUR
+    assets:cash
+
+2024-01-03 b
+    expenses:food  10 EUR
+    assets:cash
+
+2024-01-05 c
+    expenses:food  10 EUR
+    assets:cash
+"#,
+    );
+    assert_eq!(
+        messages(&journal, Check::OrderedDates),
+        vec!["line 5: date 2024-01-03 is before the previous date 2024-01-05"]
+    );
+}
+
+#[test]
+fn test_check_unique_leaf_names() {
+    let journal = journal(
+        r#"account assets:bank:checking
+account liabilities:checking
+
+2024-01-01 a
+    expenses:food  10 EUR
+    liabilities:checking
+"#,
+    );
+    assert_eq!(
+        messages(&journal, Check::UniqueLeafNames),
+        vec!["line 4: accounts assets:bank:checking, liabilities:checking have the same leaf name checking"]
+    );
+}
+
+#[test]
+fn test_check_balanced_no_auto_conversion() {
+    let journal = journal(
+        r#"2024-01-01 converted
+    assets:cash  10 EUR @@ 11 USD
+    assets:bank  -11 USD
+
+2024-01-02 not converted
+    assets:cash  10 EUR
+    assets:bank  -10 USD
+
+2024-01-03 inferred
+    assets:cash  10 EUR
+    assets:bank
+"#,
+    );
+    assert_eq!(
+        messages(&journal, Check::BalancedNoAutoConversion),
+        vec!["line 5: transaction is unbalanced by 10 EUR, -10 USD"]
+    );
+}
+
+#[test]
+fn test_check_assertions() {
+    let journal = journal(
+        r#"2024-01-03 b
+    assets:cash  -10 EUR = 90 EUR
+    expenses:food
+
+2024-01-01 a
+    assets:cash  100 EUR = 100 EUR
+    equity:opening
+
+2024-01-04 c
+    assets:cash  -10 EUR = 90 EUR
+    expenses:food
+"#,
+    );
+    let violations = journal.check(&[Check::Assertions]);
+    assert_eq!(
+        violations
+            .iter()
+            .map(|e| format!("{}", e))
+            .collect::<Vec<String>>(),
+        vec!["line 9: balance assertion failed for assets:cash: expected 90 EUR, found 80 EUR"]
+    );
+    assert_eq!(violations[0].check(), Check::Assertions);
+    assert_eq!(violations[0].location().map(|l| l.line), Some(9));
+}
+
+#[test]
+fn test_check_inclusive_assertions() {
+    let journal = journal(
+        r#"2024-01-01 opening
+    assets:bank:checking  100 EUR
+    assets:bank:savings  50 EUR
+    assets:bank  10 EUR
+    equity:opening
+
+2024-01-02 inclusive
+    assets:bank  0 EUR =* 160 EUR
+
+2024-01-03 exclusive
+    assets:bank  0 EUR = 160 EUR
+"#,
+    );
+    assert_eq!(
+        messages(&journal, Check::Assertions),
+        vec!["line 10: balance assertion failed for assets:bank: expected 160 EUR, found 10 EUR"]
+    );
+}
+
+#[test]
+fn test_check_duplicates() {
+    let journal = journal(
+        r#"2024-01-01 a
+    expenses:food  10 EUR
+    assets:cash
+
+2024-01-01 a
+    expenses:food  10 EUR
+    assets:cash
+"#,
+    );
+    assert_eq!(
+        journal.check(&[Check::Duplicates]),
+        vec![CheckError::DuplicateTransaction {
+            original: journal.transaction_location(0).cloned(),
+            location: journal.transaction_location(1).cloned(),
+        }]
+    );
+}
+
+#[test]
+fn test_check_strict_passes() {
+    let journal = journal(
+        r#"account assets:cash
+account expenses:food
+commodity EUR
+
+2024-01-01 a
+    expenses:food  10 EUR
+    assets:cash
+"#,
+    );
+    assert!(journal.check(&Check::STRICT).is_empty());
+    assert!(journal.validate_accounts().is_ok());
+}
+
+#[test]
+fn test_validate_accounts_without_duplicates() {
+    let journal = journal(
+        r#"2024-01-01 a
+    expenses:food  10 EUR
+    assets:cash
+
+2024-01-02 b
+    expenses:food  10 EUR
+    assets:cash
+"#,
+    );
+    assert_eq!(
+        format!("{}", journal.validate_accounts().unwrap_err()),
+        "Validation error: These accounts are not defined:\nexpenses:food\nassets:cash"
+    );
+}
@@ -0,0 +1,311 @@
+use std::{
+    collections::{hash_map::Entry, BTreeMap, BTreeSet, HashMap},
+    fmt::Display,
+    str::FromStr,
+};
+
+use crate::{
+    account::types::Account,
+    amount::types::{Amount, MixedAmount},
+    journal::types::{Journal, Location},
+    posting::types::PostingKind,
+    transaction::types::Transaction,
+    CheckError, HLParserError,
+};
+
+/// A check run by [`Journal::check`], named like the checks of `hledger check`
+///
+/// # Example
+///
+/// ```
+/// use hledger_parse::Check;
+///
+/// let check: Check = "ordereddates".parse().unwrap();
+/// assert_eq!(Check::OrderedDates, check);
+/// assert_eq!("ordereddates", format!("{}", check));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Check {
+    /// All posting accounts are declared with `account` directives
+    Accounts,
+    /// All commodities of postings and prices are declared with `commodity` directives
+    Commodities,
+    /// All payees are declared with `payee` directives
+    Payees,
+    /// All transaction tags are declared with `tag` directives
+    Tags,
+    /// Transactions are ordered by date in each file
+    OrderedDates,
+    /// No two accounts have the same last component
+    UniqueLeafNames,
+    /// Transactions balance in each commodity, converting only with explicit costs
+    BalancedNoAutoConversion,
+    /// Balance assertions hold
+    Assertions,
+    /// No transaction is an exact copy of a previous one
+    Duplicates,
+}
+
+impl Check {
+    /// The checks run by `hledger --strict`
+    pub const STRICT: [Check; 3] = [
+        Check::Accounts,
+        Check::Commodities,
+        Check::BalancedNoAutoConversion,
+    ];
+
+    /// All the available checks
+    pub const ALL: [Check; 9] = [
+        Check::Accounts,
+        Check::Commodities,
+        Check::Payees,
+        Check::Tags,
+        Check::OrderedDates,
+        Check::UniqueLeafNames,
+        Check::BalancedNoAutoConversion,
+        Check::Assertions,
+        Check::Duplicates,
+    ];
+
+    /// Runs the check and returns its violations, in journal order
+    pub(crate) fn run(&self, journal: &Journal) -> Vec<CheckError> {
+        match self {
+            Check::Accounts => check_accounts(journal),
+            Check::Commodities => check_commodities(journal),
+            Check::Payees => check_payees(journal),
+            Check::Tags => check_tags(journal),
+            Check::OrderedDates => check_ordered_dates(journal),
+            Check::UniqueLeafNames => check_unique_leaf_names(journal),
+            Check::BalancedNoAutoConversion => check_balanced(journal),
+            Check::Assertions => check_assertions(journal),
+            Check::Duplicates => check_duplicates(journal),
+        }
+    }
+}
+
+impl FromStr for Check {
+    type Err = HLParserError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "accounts" => Ok(Check::Accounts),
+            "commodities" => Ok(Check::Commodities),
+            "payees" => Ok(Check::Payees),
+            "tags" => Ok(Check::Tags),
+            "ordereddates" => Ok(Check::OrderedDates),
+            "uniqueleafnames" => Ok(Check::UniqueLeafNames),
+            "balancednoautoconversion" => Ok(Check::BalancedNoAutoConversion),
+            "assertions" => Ok(Check::Assertions),
+            "duplicates" => Ok(Check::Duplicates),
+            _ => Err(HLParserError::Parse(format!("Unknown check: {}", s))),
+        }
+    }
+}
+
+impl Display for Check {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Check::Accounts => write!(f, "accounts"),
+            Check::Commodities => write!(f, "commodities"),
+            Check::Payees => write!(f, "payees"),
+            Check::Tags => write!(f, "tags"),
+            Check::OrderedDates => write!(f, "ordereddates"),
+            Check::UniqueLeafNames => write!(f, "uniqueleafnames"),
+            Check::BalancedNoAutoConversion => write!(f, "balancednoautoconversion"),
+            Check::Assertions => write!(f, "assertions"),
+            Check::Duplicates => write!(f, "duplicates"),
+        }
+    }
+}
+
+fn check_accounts(journal: &Journal) -> Vec<CheckError> {
+    let accounts = journal.accounts();
+    journal
+        .undeclared_names(
+            |t| t.postings.iter().map(|p| p.account.as_str()),
+            |name| accounts.iter().any(|a| a.as_str() == name),
+        )
+        .into_iter()
+        .map(|(account, location)| CheckError::UndeclaredAccount {
+            account: account.into(),
+            location,
+        })
+        .collect()
+}
+
+fn check_commodities(journal: &Journal) -> Vec<CheckError> {
+    journal
+        .undeclared_commodities()
+        .into_iter()
+        .map(|(commodity, location)| CheckError::UndeclaredCommodity {
+            commodity,
+            location,
+        })
+        .collect()
+}
+
+fn check_payees(journal: &Journal) -> Vec<CheckError> {
+    let payees = journal.declared_payees();
+    journal
+        .undeclared_names(
+            |t| t.payee().into_iter(),
+            |name| payees.iter().any(|p| p == name),
+        )
+        .into_iter()
+        .map(|(payee, location)| CheckError::UndeclaredPayee { payee, location })
+        .collect()
+}
+
+fn check_tags(journal: &Journal) -> Vec<CheckError> {
+    let tags = journal.declared_tags();
+    journal
+        .undeclared_names(
+            |t| t.tags.iter().map(|tag| tag.name.as_str()),
+            |name| tags.iter().any(|t| t == name),
+        )
+        .into_iter()
+        .map(|(tag, location)| CheckError::UndeclaredTag { tag, location })
+        .collect()
+}
+
+fn check_ordered_dates(journal: &Journal) -> Vec<CheckError> {
+    let mut previous_dates = BTreeMap::new();
+    let mut violations = vec![];
+    for (transaction, location) in journal.located_transactions() {
+        let file = location.and_then(|l| l.path.clone());
+        let date = transaction.primary_date;
+        match previous_dates.get(&file) {
+            Some(&previous) if date < previous => violations.push(CheckError::UnorderedDate {
+                date,
+                previous,
+                location: location.cloned(),
+            }),
+            _ => {
+                previous_dates.insert(file, date);
+            }
+        }
+    }
+    violations
+}
+
+fn check_unique_leaf_names(journal: &Journal) -> Vec<CheckError> {
+    let accounts: BTreeSet<Account> = journal
+        .accounts()
+        .into_iter()
+        .chain(
+            journal
+                .located_transactions()
+                .flat_map(|(t, _)| t.postings.iter().map(|p| p.account.clone())),
+        )
+        .collect();
+    let mut leaves: BTreeMap<String, Vec<Account>> = BTreeMap::new();
+    for account in accounts {
+        leaves
+            .entry(account.leaf().to_string())
+            .or_default()
+            .push(account);
+    }
+
+    leaves
+        .into_iter()
+        .filter(|(_, accounts)| accounts.len() > 1)
+        .map(|(leaf, accounts)| {
+            let location = journal
+                .located_transactions()
+                .find(|(t, _)| t.postings.iter().any(|p| accounts.contains(&p.account)))
+                .and_then(|(_, location)| location.cloned());
+            CheckError::DuplicateLeafName {
+                leaf,
+                accounts,
+                location,
+            }
+        })
+        .collect()
+}
+
+fn check_balanced(journal: &Journal) -> Vec<CheckError> {
+    journal
+        .located_transactions()
+        .filter_map(|(transaction, location)| {
+            PostingKind::BALANCED.into_iter().find_map(|kind| {
+                let postings = || transaction.postings.iter().filter(move |p| p.kind == kind);
+                if postings().any(|p| p.amount.is_none()) {
+                    return None;
+                }
+                let mut residual = MixedAmount::default();
+                for cost in postings().filter_map(|p| p.cost()) {
+                    residual.add_amount(&cost);
+                }
+                match residual.is_zero() {
+                    true => None,
+                    false => Some(CheckError::UnbalancedTransaction {
+                        residual,
+                        location: location.cloned(),
+                    }),
+                }
+            })
+        })
+        .collect()
+}
+
+fn check_assertions(journal: &Journal) -> Vec<CheckError> {
+    let mut transactions: Vec<(&Transaction, Option<&Location>)> =
+        journal.located_transactions().collect();
+    transactions.sort_by_key(|(t, _)| t.primary_date);
+
+    let mut balances: BTreeMap<Account, MixedAmount> = BTreeMap::new();
+    let mut violations = vec![];
+    for (transaction, location) in transactions {
+        for (posting, amount) in transaction
+            .postings
+            .iter()
+            .zip(transaction.posting_amounts())
+        {
+            *balances.entry(posting.account.clone()).or_default() += &amount;
+            if let Some(expected) = &posting.balance_assertion {
+                let actual = balances
+                    .range(&posting.account..)
+                    .take_while(|(a, _)| a.as_str().starts_with(posting.account.as_str()))
+                    .filter(|(a, _)| {
+                        **a == posting.account
+                            || (posting.inclusive_assertion && a.is_child_of(&posting.account))
+                    })
+                    .map(|(_, balance)| balance.value(&expected.currency))
+                    .sum();
+                if actual != expected.value {
+                    violations.push(CheckError::FailedAssertion {
+                        account: posting.account.clone(),
+                        expected: expected.clone(),
+                        actual: Amount {
+                            currency: expected.currency.clone(),
+                            value: actual,
+                        },
+                        location: location.cloned(),
+                    });
+                }
+            }
+        }
+    }
+    violations
+}
+
+fn check_duplicates(journal: &Journal) -> Vec<CheckError> {
+    let mut originals: HashMap<&Transaction, Option<&Location>> = HashMap::new();
+    journal
+        .located_transactions()
+        .filter_map(
+            |(transaction, location)| match originals.entry(transaction) {
+                Entry::Occupied(original) => Some(CheckError::DuplicateTransaction {
+                    original: original.get().cloned(),
+                    location: location.cloned(),
+                }),
+                Entry::Vacant(entry) => {
+                    entry.insert(location);
+                    None
+                }
+            },
+        )
+        .collect()
+}
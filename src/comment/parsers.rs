@@ -1,6 +1,6 @@
 use winnow::{
-    ascii::{space0, till_line_ending},
-    combinator::{alt, preceded},
+    ascii::{line_ending, space0, space1, till_line_ending},
+    combinator::{alt, eof, opt, peek, preceded, repeat_till},
     PResult, Parser,
 };
 
@@ -11,3 +11,26 @@ pub fn parse_line_comment<'s>(input: &mut &'s str) -> PResult<&'s str> {
 pub fn parse_transaction_comment<'s>(input: &mut &'s str) -> PResult<&'s str> {
     preceded(';', preceded(space0, till_line_ending)).parse_next(input)
 }
+
+fn parse_end_comment(input: &mut &str) -> PResult<()> {
+    ("end", space1, "comment", space0, alt((line_ending, eof)))
+        .void()
+        .parse_next(input)
+}
+
+/// Parses a `comment` ... `end comment` block and returns the lines inside it
+///
+/// A block without `end comment` runs until the end of the input.
+pub fn parse_block_comment<'s>(input: &mut &'s str) -> PResult<&'s str> {
+    let _ = ("comment", space0, alt((line_ending, eof))).parse_next(input)?;
+    let contents = repeat_till::<_, _, (), _, _, _, _>(
+        0..,
+        (till_line_ending, alt((line_ending, eof))),
+        peek(alt((parse_end_comment, eof.void()))),
+    )
+    .take()
+    .parse_next(input)?;
+    let _ = opt(parse_end_comment).parse_next(input)?;
+
+    Ok(contents)
+}
@@ -1,7 +1,7 @@
 use rstest::rstest;
 use winnow::error::{ContextError, ErrMode};
 
-use crate::comment::parsers::{parse_line_comment, parse_transaction_comment};
+use crate::comment::parsers::{parse_block_comment, parse_line_comment, parse_transaction_comment};
 
 #[rstest]
 #[case::line_comment_temporary(";comment", "", "comment")]
@@ -47,3 +47,29 @@ fn test_parse_line_comment_as_transaction_comment() {
         ErrMode::Backtrack(ContextError::new()) // TODO: errors
     );
 }
+
+#[rstest]
+#[case::simple(
+    "comment\nline 1\nline 2\nend comment\nrest",
+    "rest",
+    "line 1\nline 2\n"
+)]
+#[case::empty("comment\nend comment\n", "", "")]
+#[case::trailing_spaces("comment  \n2024-01-01 x\nend comment  ", "", "2024-01-01 x\n")]
+#[case::unterminated("comment\nline 1\nline 2", "", "line 1\nline 2")]
+#[case::unterminated_at_eof("comment", "", "")]
+#[case::indented_end("comment\n  end comment\nend comment\n", "", "  end comment\n")]
+fn test_parse_block_comment(
+    #[case] input: &str,
+    #[case] expected_remaining: &str,
+    #[case] expected_comment: &str,
+) {
+    let mut input = input;
+    assert_eq!(parse_block_comment(&mut input).unwrap(), expected_comment);
+    assert_eq!(input, expected_remaining);
+}
+
+#[test]
+fn test_parse_block_comment_requires_own_line() {
+    assert!(parse_block_comment(&mut "comment here\nend comment").is_err());
+}
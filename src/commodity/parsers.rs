@@ -4,7 +4,7 @@ use winnow::{
     PResult, Parser,
 };
 
-use crate::amount::parsers::{parse_currency_string, parse_money_amount};
+use crate::amount::parsers::{parse_amount, parse_currency_string, parse_money_amount};
 
 use super::types::Commodity;
 
@@ -58,3 +58,21 @@ pub fn parse_commodity_directive(input: &mut &str) -> PResult<Commodity> {
     )
     .parse_next(input)
 }
+
+/// Parses a default commodity directive, e.g. `D $1,000.00`
+///
+/// The commodity of the amount is used for amounts written without a commodity.
+pub fn parse_default_commodity_directive(input: &mut &str) -> PResult<Commodity> {
+    let _ = terminated("D", space1).parse_next(input)?;
+    let format = terminated(till_line_ending, alt((line_ending, eof)))
+        .map(str::trim_end)
+        .parse_next(input)?;
+    let amount = parse_amount
+        .verify(|a| !a.currency.is_empty())
+        .parse_next(&mut &*format)?;
+
+    Ok(Commodity {
+        name: amount.currency,
+        format: Some(format.to_string()),
+    })
+}
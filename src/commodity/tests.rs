@@ -2,7 +2,7 @@ use rstest::rstest;
 
 use crate::commodity::types::Commodity;
 
-use super::parsers::parse_commodity_directive;
+use super::parsers::{parse_commodity_directive, parse_default_commodity_directive};
 
 #[rstest]
 #[case("commodity $1000.00", "", "$", "$1000.00")]
@@ -53,3 +53,29 @@ fn test_parse_commodity_name_only() {
     );
     assert_eq!(input, "");
 }
+
+#[rstest]
+#[case("D $1,000.00", "", "$", "$1,000.00")]
+#[case("D 1000.00 EUR  \nrest", "rest", "EUR", "1000.00 EUR")]
+fn test_parse_default_commodity_directive(
+    #[case] input: &str,
+    #[case] expected_remaining: &str,
+    #[case] expected_currency: &str,
+    #[case] expected_format: &str,
+) {
+    let mut input = input;
+    assert_eq!(
+        parse_default_commodity_directive(&mut input).unwrap(),
+        Commodity {
+            name: expected_currency.to_string(),
+            format: Some(expected_format.to_string()),
+        }
+    );
+    assert_eq!(input, expected_remaining);
+}
+
+#[test]
+fn test_parse_default_commodity_directive_without_commodity() {
+    let mut input = "D 1000.00";
+    assert!(parse_default_commodity_directive(&mut input).is_err());
+}
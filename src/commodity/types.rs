@@ -1,6 +1,6 @@
 use std::fmt::Display;
 
-use crate::{journal::types::Value, HLParserError};
+use crate::{amount::parsers::infer_decimal_mark, journal::types::Value, HLParserError};
 
 /// Declared commodity
 ///
@@ -15,6 +15,7 @@ use crate::{journal::types::Value, HLParserError};
 /// assert_eq!("commodity INR\n  format INR 1,00,00,000.00", format!("{}", commodity));
 /// ```
 #[derive(PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Commodity {
     /// The name of the commodity
     pub name: String,
@@ -22,6 +23,61 @@ pub struct Commodity {
     pub format: Option<String>, // TODO: temp before I decide how to store the format properly
 }
 
+impl Commodity {
+    /// Returns the decimal mark used in the format of the commodity
+    ///
+    /// A `.` or `,` which appears once in the format is the decimal mark, e.g. `,` for
+    /// `1.000,00 EUR`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hledger_parse::Commodity;
+    ///
+    /// let commodity = Commodity { name: "EUR".to_string(), format: Some("1.000,00 EUR".to_string()) };
+    /// assert_eq!(Some(','), commodity.decimal_mark());
+    /// let commodity = Commodity { name: "EUR".to_string(), format: None };
+    /// assert_eq!(None, commodity.decimal_mark());
+    /// ```
+    pub fn decimal_mark(&self) -> Option<char> {
+        infer_decimal_mark(&self.format_number()?)
+    }
+
+    /// Returns the number of decimal places in the format of the commodity
+    ///
+    /// Amounts can be displayed in the commodity's style with [`Amount::with_precision`](crate::Amount::with_precision).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rust_decimal_macros::dec;
+    /// use hledger_parse::{Amount, Commodity};
+    ///
+    /// let commodity = Commodity { name: "EUR".to_string(), format: Some("1.000,00 EUR".to_string()) };
+    /// assert_eq!(Some(2), commodity.precision());
+    /// let amount = Amount { currency: "EUR".to_string(), value: dec!(1.5) };
+    /// assert_eq!("1.50 EUR", format!("{}", amount.with_precision(commodity.precision().unwrap())));
+    /// ```
+    pub fn precision(&self) -> Option<u32> {
+        let number = self.format_number()?;
+        match self.decimal_mark().and_then(|mark| number.split_once(mark)) {
+            Some((_, decimals)) => Some(decimals.len() as u32),
+            None => Some(0),
+        }
+    }
+
+    /// Returns the digits and the `.` and `,` marks of the format
+    fn format_number(&self) -> Option<String> {
+        Some(
+            self.format
+                .as_ref()?
+                .chars()
+                .filter(|c| c.is_ascii_digit() || ['.', ','].contains(c))
+                .collect(),
+        )
+    }
+}
+
 impl TryInto<Commodity> for Value {
     type Error = HLParserError;
 
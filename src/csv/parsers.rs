@@ -0,0 +1,356 @@
+use std::path::PathBuf;
+
+use regex::Regex;
+use winnow::{
+    ascii::{digit1, line_ending, space0, space1, till_line_ending},
+    combinator::{
+        alt, cut_err, delimited, eof, opt, peek, preceded, repeat, separated, terminated,
+    },
+    token::{none_of, one_of, take_till, take_while},
+    PResult, Parser,
+};
+
+use crate::{
+    comment::parsers::parse_line_comment,
+    journal::types::{Journal, Location, ParseState, Value},
+    transaction::types::Transaction,
+    utils::LineCounter,
+    HLParserError,
+};
+
+use super::types::{ConditionalBlock, CsvRules, Matcher, FIELD_NAMES};
+
+#[derive(Clone, Debug)]
+enum Rule {
+    Skip(usize),
+    Separator(char),
+    Fields(Vec<String>),
+    DateFormat(String),
+    DecimalMark(char),
+    NewestFirst,
+    Assignment(String, String),
+    Conditional(ConditionalBlock),
+    Ignore,
+}
+
+fn end_of_line<'s>(input: &mut &'s str) -> PResult<&'s str> {
+    alt((line_ending, eof)).parse_next(input)
+}
+
+fn parse_skip(input: &mut &str) -> PResult<Rule> {
+    delimited(
+        "skip",
+        opt(preceded(space1, digit1.parse_to())),
+        (space0, end_of_line),
+    )
+    .map(|skip| Rule::Skip(skip.unwrap_or(1)))
+    .parse_next(input)
+}
+
+fn parse_separator(input: &mut &str) -> PResult<Rule> {
+    delimited(
+        ("separator", space1),
+        alt((
+            "TAB".value('\t'),
+            "SPACE".value(' '),
+            none_of([' ', '\t', '\n', '\r']),
+        )),
+        (space0, end_of_line),
+    )
+    .map(Rule::Separator)
+    .parse_next(input)
+}
+
+fn parse_fields(input: &mut &str) -> PResult<Rule> {
+    delimited(
+        ("fields", space1),
+        separated(
+            1..,
+            take_till(0.., [',', '\n', '\r']).map(|f: &str| f.trim().to_lowercase()),
+            ',',
+        ),
+        end_of_line,
+    )
+    .map(Rule::Fields)
+    .parse_next(input)
+}
+
+fn parse_date_format(input: &mut &str) -> PResult<Rule> {
+    delimited(("date-format", space1), till_line_ending, end_of_line)
+        .map(|format: &str| Rule::DateFormat(format.trim().to_string()))
+        .parse_next(input)
+}
+
+fn parse_decimal_mark(input: &mut &str) -> PResult<Rule> {
+    delimited(
+        ("decimal-mark", space1),
+        one_of(['.', ',']),
+        (space0, end_of_line),
+    )
+    .map(Rule::DecimalMark)
+    .parse_next(input)
+}
+
+fn parse_newest_first(input: &mut &str) -> PResult<Rule> {
+    ("newest-first", space0, end_of_line)
+        .value(Rule::NewestFirst)
+        .parse_next(input)
+}
+
+fn parse_field_name<'s>(input: &mut &'s str) -> PResult<&'s str> {
+    take_while(1.., |c: char| c.is_alphanumeric() || c == '-')
+        .verify(|name: &str| FIELD_NAMES.contains(&name))
+        .parse_next(input)
+}
+
+fn parse_assignment(input: &mut &str) -> PResult<(String, String)> {
+    (
+        parse_field_name,
+        terminated(opt(preceded(space1, till_line_ending)), end_of_line),
+    )
+        .map(|(name, value)| {
+            (
+                name.to_string(),
+                value.map(str::trim).unwrap_or_default().to_string(),
+            )
+        })
+        .parse_next(input)
+}
+
+fn parse_matcher(input: &mut &str) -> PResult<Matcher> {
+    peek(none_of([' ', '\t', '\n', '\r'])).parse_next(input)?;
+    let and = opt(terminated('&', space0)).parse_next(input)?.is_some();
+    let negated = opt(terminated('!', space0)).parse_next(input)?.is_some();
+    let field = opt(delimited(
+        '%',
+        take_while(1.., |c: char| c.is_alphanumeric() || c == '_' || c == '-'),
+        space1,
+    ))
+    .parse_next(input)?;
+    let pattern = terminated(till_line_ending, end_of_line)
+        .map(str::trim)
+        .verify(|pattern: &str| !pattern.is_empty())
+        .try_map(|pattern| Regex::new(&format!("(?i){}", pattern)))
+        .parse_next(input)?;
+    Ok(Matcher {
+        field: field.map(str::to_string),
+        pattern,
+        negated,
+        and,
+    })
+}
+
+fn parse_conditional_block(input: &mut &str) -> PResult<Rule> {
+    let matchers = preceded(
+        "if",
+        alt((
+            preceded(space1, parse_matcher).map(|matcher| vec![matcher]),
+            preceded((space0, line_ending), repeat(1.., parse_matcher)),
+        )),
+    )
+    .parse_next(input)?;
+    let assignments = repeat(1.., preceded(space1, parse_assignment)).parse_next(input)?;
+    Ok(Rule::Conditional(ConditionalBlock {
+        matchers,
+        assignments,
+    }))
+}
+
+fn parse_rule(input: &mut &str) -> PResult<Rule> {
+    alt((
+        terminated(parse_line_comment, end_of_line).value(Rule::Ignore),
+        terminated(space0, line_ending).value(Rule::Ignore),
+        parse_skip,
+        parse_separator,
+        parse_fields,
+        parse_date_format,
+        parse_decimal_mark,
+        parse_newest_first,
+        parse_conditional_block,
+        parse_assignment.map(|(name, value)| Rule::Assignment(name, value)),
+    ))
+    .parse_next(input)
+}
+
+/// Parses a CSV rules file, in the format of hledger rules files
+///
+/// The supported rules are `skip`, `separator`, `fields`, `date-format`, `decimal-mark`,
+/// `newest-first`, field assignments and `if` blocks.
+///
+/// # Example
+///
+/// ```
+/// use hledger_parse::parse_csv_rules;
+///
+/// let mut input = r#"skip 1
+/// fields date, description, amount
+/// date-format %d/%m/%Y
+/// currency EUR
+/// account1 assets:bank
+///
+/// if %description coffee
+///   account2 expenses:coffee
+/// "#;
+/// let rules = parse_csv_rules(&mut input).unwrap();
+/// assert_eq!(1, rules.skip);
+/// assert_eq!(vec!["date", "description", "amount"], rules.fields);
+/// assert_eq!(1, rules.conditional_blocks.len());
+/// ```
+pub fn parse_csv_rules(input: &mut &str) -> Result<CsvRules, HLParserError> {
+    let mut lines = LineCounter::new(input);
+    let mut rules = CsvRules::default();
+    while !input.is_empty() {
+        let line = lines.line(input);
+        let rule = parse_rule
+            .parse_next(input)
+            .map_err(|e| HLParserError::Parse(format!("line {}: {}", line, e)))?;
+        match rule {
+            Rule::Skip(skip) => rules.skip = skip,
+            Rule::Separator(separator) => rules.separator = separator,
+            Rule::Fields(fields) => rules.fields = fields,
+            Rule::DateFormat(format) => rules.date_format = Some(format),
+            Rule::DecimalMark(mark) => rules.decimal_mark = Some(mark),
+            Rule::NewestFirst => rules.newest_first = true,
+            Rule::Assignment(name, value) => rules.assignments.push((name, value)),
+            Rule::Conditional(block) => rules.conditional_blocks.push(block),
+            Rule::Ignore => {}
+        }
+    }
+    Ok(rules)
+}
+
+fn parse_quoted_field(input: &mut &str) -> PResult<String> {
+    preceded(
+        '"',
+        cut_err(terminated(
+            repeat(0.., alt(("\"\"".value('"'), none_of('"')))),
+            '"',
+        )),
+    )
+    .parse_next(input)
+}
+
+fn parse_csv_field(separator: char) -> impl FnMut(&mut &str) -> PResult<String> {
+    move |input: &mut &str| {
+        alt((
+            parse_quoted_field,
+            take_till(0.., [separator, '\n', '\r']).map(str::to_string),
+        ))
+        .parse_next(input)
+    }
+}
+
+/// Parses a CSV record, whose quoted fields may contain separators, quotes and line breaks
+fn parse_csv_record(separator: char) -> impl FnMut(&mut &str) -> PResult<Vec<String>> {
+    move |input: &mut &str| {
+        terminated(
+            separated(1.., parse_csv_field(separator), separator),
+            end_of_line,
+        )
+        .parse_next(input)
+    }
+}
+
+/// Parses CSV contents into transactions with their locations
+fn parse_csv_contents(
+    input: &mut &str,
+    rules: &CsvRules,
+    path: Option<PathBuf>,
+) -> Result<Vec<(Transaction, Location)>, HLParserError> {
+    let mut lines = LineCounter::new(input);
+    let mut transactions = vec![];
+    let mut index = 0;
+    while !input.is_empty() {
+        let line = lines.line(input);
+        let record = parse_csv_record(rules.separator)
+            .parse_next(input)
+            .map_err(|e| HLParserError::Parse(format!("line {}: {}", line, e)))?;
+        index += 1;
+        if index <= rules.skip || record.iter().all(|field| field.trim().is_empty()) {
+            continue;
+        }
+        let transaction = rules.transaction(&record).map_err(|e| match e {
+            HLParserError::Parse(e) => HLParserError::Parse(format!("line {}: {}", line, e)),
+            e => e,
+        })?;
+        transactions.push((
+            transaction,
+            Location {
+                path: path.clone(),
+                line,
+            },
+        ));
+    }
+    if rules.newest_first {
+        transactions.reverse();
+    }
+    transactions.sort_by_key(|(transaction, _)| transaction.primary_date);
+    Ok(transactions)
+}
+
+/// Converts CSV contents into a journal, following rules read with [`parse_csv_rules`]
+///
+/// The transactions are sorted by date, keeping the order of the records on the same date, or
+/// reversing it with the `newest-first` rule. `path` is the file the input was read from, if
+/// any, and is used in locations.
+///
+/// # Example
+///
+/// ```
+/// use hledger_parse::{parse_csv, parse_csv_rules};
+///
+/// let mut rules = r#"skip 1
+/// fields date, description, amount
+/// currency $
+/// account1 assets:bank
+///
+/// if coffee
+///   account2 expenses:coffee
+/// "#;
+/// let rules = parse_csv_rules(&mut rules).unwrap();
+/// let mut input = r#"Date,Description,Amount
+/// 2024-03-01,"Coffee, large",-3.50
+/// "#;
+/// let journal = parse_csv(&mut input, &rules, None).unwrap();
+/// assert_eq!(
+///     "2024-03-01 Coffee, large\n   assets:bank  -3.50 $\n   expenses:coffee  3.50 $\n",
+///     format!("{}", journal.transactions()[0])
+/// );
+/// ```
+pub fn parse_csv(
+    input: &mut &str,
+    rules: &CsvRules,
+    path: Option<PathBuf>,
+) -> Result<Journal, HLParserError> {
+    let (transactions, locations): (Vec<_>, Vec<_>) =
+        parse_csv_contents(input, rules, path)?.into_iter().unzip();
+    let mut journal = Journal::new(transactions, vec![], vec![], vec![]);
+    journal.set_transaction_locations(locations);
+    Ok(journal)
+}
+
+/// Reads a CSV file included by a journal, with the rules of the `.rules` file next to it
+///
+/// The rules of `bank.csv` are read from `bank.csv.rules`, like hledger does. The journal parser
+/// state, e.g. account aliases, is applied to the transactions.
+pub fn read_csv_from_path(
+    path: PathBuf,
+    state: &ParseState,
+    locations: &mut Vec<Location>,
+) -> Result<Vec<Value>, HLParserError> {
+    let mut rules_path = path.clone().into_os_string();
+    rules_path.push(".rules");
+    let rules_path = PathBuf::from(rules_path);
+    let rules = std::fs::read_to_string(&rules_path)
+        .map_err(|e| HLParserError::IO(format!("{}: {}", rules_path.display(), e)))?;
+    let rules = parse_csv_rules(&mut &rules[..])?;
+    let contents = std::fs::read_to_string(&path).map_err(|e| HLParserError::IO(e.to_string()))?;
+    let mut input = &contents[..];
+    Ok(parse_csv_contents(&mut input, &rules, Some(path))?
+        .into_iter()
+        .map(|(transaction, location)| {
+            locations.push(location);
+            Value::Transaction(state.apply_to_transaction(transaction))
+        })
+        .collect())
+}
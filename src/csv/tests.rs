@@ -0,0 +1,184 @@
+use std::path::PathBuf;
+
+use rstest::rstest;
+use rust_decimal_macros::dec;
+
+use crate::{
+    amount::types::Amount,
+    journal::{parsers::parse_journal, types::Location},
+    HLParserError,
+};
+
+use super::parsers::{parse_csv, parse_csv_rules};
+
+fn fixture(path: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/csv")
+        .join(path)
+}
+
+fn convert(csv: &str) -> String {
+    let rules = std::fs::read_to_string(fixture(&format!("{}.rules", csv))).unwrap();
+    let rules = parse_csv_rules(&mut &rules[..]).unwrap();
+    let contents = std::fs::read_to_string(fixture(csv)).unwrap();
+    let journal = parse_csv(&mut &contents[..], &rules, Some(fixture(csv))).unwrap();
+    journal
+        .transactions()
+        .iter()
+        .map(|t| t.to_string())
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+#[test]
+fn test_parse_csv_rules() {
+    let rules = std::fs::read_to_string(fixture("bank.csv.rules")).unwrap();
+    let rules = parse_csv_rules(&mut &rules[..]).unwrap();
+
+    assert_eq!(rules.skip, 1);
+    assert_eq!(rules.separator, ',');
+    assert_eq!(
+        rules.fields,
+        vec!["date", "description", "amount", "balance", "reference"]
+    );
+    assert_eq!(
+        rules.assignments,
+        vec![
+            ("currency".to_string(), "$".to_string()),
+            ("account1".to_string(), "assets:bank:checking".to_string()),
+            ("code".to_string(), "%reference".to_string()),
+            ("status".to_string(), "*".to_string()),
+        ]
+    );
+    assert_eq!(rules.conditional_blocks.len(), 3);
+    let transfer = &rules.conditional_blocks[2];
+    assert_eq!(
+        transfer
+            .matchers
+            .iter()
+            .map(|m| (m.field.as_deref(), m.pattern.as_str(), m.and))
+            .collect::<Vec<_>>(),
+        vec![
+            (Some("description"), "(?i)^transfer", false),
+            (Some("amount"), "(?i)^-", true)
+        ]
+    );
+}
+
+#[rstest]
+#[case::unknown_rule("skip 1\nbalance-type ==\n", "line 2")]
+#[case::invalid_pattern("if %description (\n  account2 expenses\n", "line 1")]
+#[case::if_without_assignments("if coffee\naccount1 assets\n", "line 1")]
+fn test_parse_csv_rules_errors(#[case] input: &str, #[case] expected: &str) {
+    match parse_csv_rules(&mut &input[..]) {
+        Err(e @ HLParserError::Parse(_)) => assert!(e.to_string().contains(expected), "{}", e),
+        r => panic!("Unexpected result {:?}", r),
+    }
+}
+
+#[test]
+fn test_parse_csv_bank() {
+    assert_eq!(
+        convert("bank.csv"),
+        r#"2024-03-01 * Grocery Store, Main St
+   assets:bank:checking  -45.20 $
+   expenses:food:groceries  45.20 $
+
+2024-03-02 * Salary ACME Corp
+   assets:bank:checking  2500.00 $
+   income:unknown  -2500.00 $
+
+2024-03-03 * Coffee "Bean" Bar ; place:Coffee "Bean" Bar
+   assets:bank:checking  -3.50 $
+   expenses:food:dining  3.50 $
+
+2024-03-05 * Transfer to savings
+   assets:bank:checking  -500.00 $
+   assets:bank:savings  500.00 $
+"#
+    );
+}
+
+#[test]
+fn test_parse_csv_code_and_balance() {
+    let rules = std::fs::read_to_string(fixture("bank.csv.rules")).unwrap();
+    let rules = parse_csv_rules(&mut &rules[..]).unwrap();
+    let record = ["2024-03-02", "Salary", "2500.00", "3454.80", "A2"].map(str::to_string);
+    let transaction = rules.transaction(&record).unwrap();
+
+    assert_eq!(transaction.code, Some("A2".to_string()));
+    assert_eq!(
+        transaction.postings[0].balance_assertion,
+        Some(Amount {
+            currency: "$".to_string(),
+            value: dec!(3454.80)
+        })
+    );
+}
+
+#[test]
+fn test_parse_csv_card() {
+    assert_eq!(
+        convert("card.csv"),
+        r#"2024-03-01 Bookstore | card
+   liabilities:card  -1234.50 EUR
+   expenses:books  1234.50 EUR
+
+2024-03-01 Refund Shop | card
+   liabilities:card  20.00 EUR
+   income:unknown  -20.00 EUR
+
+2024-03-05 Online Shop | card
+   liabilities:card  -12.99 EUR
+   expenses:unknown  12.99 EUR
+"#
+    );
+}
+
+#[rstest]
+#[case::no_amount("2024-03-01,Nothing,,\n", "line 1: No amount")]
+#[case::both_amounts("2024-03-01,Both,1,2\n", "line 1: Both amount-in and amount-out")]
+#[case::invalid_date("01/03/2024,Date,1,\n", "line 1: Invalid date: 01/03/2024")]
+#[case::invalid_amount("2024-03-01,Amount,ten,\n", "line 1: Invalid amount in amount-in")]
+#[case::unterminated_quote("2024-03-01,\"Quote,1,\n", "line 1")]
+fn test_parse_csv_errors(#[case] input: &str, #[case] expected: &str) {
+    let rules =
+        parse_csv_rules(&mut "fields date, description, amount-in, amount-out\naccount1 a\n")
+            .unwrap();
+    match parse_csv(&mut &input[..], &rules, None) {
+        Err(e @ HLParserError::Parse(_)) => assert!(e.to_string().contains(expected), "{}", e),
+        r => panic!("Unexpected result {:?}", r),
+    }
+}
+
+#[test]
+fn test_parse_csv_parenthesized_amount_and_positions() {
+    let rules = parse_csv_rules(
+        &mut "account1 assets:cash\ndate %2\ndescription %1\namount %3\ncurrency EUR\n",
+    )
+    .unwrap();
+    let journal = parse_csv(&mut "Refund,2024-03-01,(10)\n", &rules, None).unwrap();
+    assert_eq!(
+        journal.transactions()[0].to_string(),
+        "2024-03-01 Refund\n   assets:cash  -10 EUR\n   expenses:unknown  10 EUR\n"
+    );
+}
+
+#[test]
+fn test_include_csv() {
+    let mut input = "alias assets:bank:checking = assets:checking\ninclude bank.csv\n";
+    let journal = parse_journal(&mut input, Some(fixture(""))).unwrap();
+
+    assert_eq!(journal.transactions().len(), 4);
+    assert_eq!(
+        journal.transactions()[0].postings[0].account,
+        "assets:checking".into()
+    );
+    assert_eq!(
+        journal.transaction_location(1),
+        Some(&Location {
+            path: Some(fixture("bank.csv").canonicalize().unwrap()),
+            line: 3
+        })
+    );
+}
@@ -0,0 +1,343 @@
+use chrono::NaiveDate;
+use regex::Regex;
+use winnow::{combinator::eof, Parser};
+
+use crate::{
+    amount::{parsers::parse_amount_with_state, types::Amount},
+    date::parsers::parse_date,
+    description::{parsers::parse_description, types::Description},
+    journal::types::ParseState,
+    posting::types::{Posting, PostingKind},
+    status::types::Status,
+    transaction::{parsers::parse_comments_tags, types::Transaction},
+    HLParserError,
+};
+
+/// The transaction fields which CSV rules can assign
+pub const FIELD_NAMES: [&str; 13] = [
+    "date",
+    "date2",
+    "status",
+    "code",
+    "description",
+    "comment",
+    "account1",
+    "account2",
+    "amount",
+    "amount-in",
+    "amount-out",
+    "currency",
+    "balance",
+];
+
+/// A condition of an `if` block, matching a CSV record or one of its fields
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Matcher {
+    /// The field to match, e.g. `description` for `%description`, or the whole record if `None`
+    pub field: Option<String>,
+    /// The case-insensitive pattern to search for
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::serde_regex"))]
+    pub pattern: Regex,
+    /// Whether the matcher succeeds when the pattern is not found, written with `!`
+    pub negated: bool,
+    /// Whether the matcher is combined with the previous one, written with `&`
+    pub and: bool,
+}
+
+impl Matcher {
+    fn matches(&self, rules: &CsvRules, record: &[String]) -> bool {
+        let found = match &self.field {
+            Some(field) => self.pattern.is_match(&rules.field_value(field, record)),
+            None => self.pattern.is_match(&record.join(",")),
+        };
+        found != self.negated
+    }
+}
+
+/// An `if` block, assigning fields to the records matching any of its matcher groups
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ConditionalBlock {
+    /// The matchers, where a matcher combined with `&` belongs to the group of the previous one
+    pub matchers: Vec<Matcher>,
+    /// The field assignments of the block, e.g. `("account2", "expenses:food")`
+    pub assignments: Vec<(String, String)>,
+}
+
+impl ConditionalBlock {
+    fn matches(&self, rules: &CsvRules, record: &[String]) -> bool {
+        let mut groups: Vec<Vec<&Matcher>> = vec![];
+        for matcher in &self.matchers {
+            match groups.last_mut() {
+                Some(group) if matcher.and => group.push(matcher),
+                _ => groups.push(vec![matcher]),
+            }
+        }
+        groups
+            .iter()
+            .any(|group| group.iter().all(|m| m.matches(rules, record)))
+    }
+}
+
+/// Rules converting CSV records to transactions, in the format of hledger rules files
+///
+/// Field values may reference the CSV fields by name, e.g. `%description`, or by position,
+/// e.g. `%2`. Values assigned by a matching `if` block take precedence over top-level
+/// assignments, which take precedence over the fields named after transaction fields in the
+/// `fields` list. The last matching `if` block wins.
+///
+/// # Example
+///
+/// ```
+/// use hledger_parse::parse_csv_rules;
+///
+/// let mut input = r#"fields date, description, amount
+/// currency EUR
+/// account1 assets:bank
+/// "#;
+/// let rules = parse_csv_rules(&mut input).unwrap();
+/// let record = ["2024-03-01", "Coffee", "-3.50"].map(str::to_string);
+/// assert_eq!(
+///     "2024-03-01 Coffee\n   assets:bank  -3.50 EUR\n   expenses:unknown  3.50 EUR\n",
+///     format!("{}", rules.transaction(&record).unwrap())
+/// );
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CsvRules {
+    /// The number of header lines to skip
+    pub skip: usize,
+    /// The field separator, `,` by default
+    pub separator: char,
+    /// The names of the CSV fields, in order
+    pub fields: Vec<String>,
+    /// The `chrono` format of dates, if they are not in a journal date format
+    pub date_format: Option<String>,
+    /// The decimal mark of amounts, if it cannot be inferred
+    pub decimal_mark: Option<char>,
+    /// Whether records on the same date are listed newest first
+    pub newest_first: bool,
+    /// The top-level field assignments, e.g. `("account1", "assets:bank")`
+    pub assignments: Vec<(String, String)>,
+    /// The `if` blocks, in order
+    pub conditional_blocks: Vec<ConditionalBlock>,
+}
+
+impl Default for CsvRules {
+    fn default() -> Self {
+        Self {
+            skip: 0,
+            separator: ',',
+            fields: vec![],
+            date_format: None,
+            decimal_mark: None,
+            newest_first: false,
+            assignments: vec![],
+            conditional_blocks: vec![],
+        }
+    }
+}
+
+impl CsvRules {
+    /// Returns the value of a CSV field, referenced by name or by 1-based position
+    fn field_value(&self, field: &str, record: &[String]) -> String {
+        let index = match field.parse::<usize>() {
+            Ok(position) => position.checked_sub(1),
+            Err(_) => self.fields.iter().position(|f| f == field),
+        };
+        index
+            .and_then(|i| record.get(i))
+            .map(|value| value.trim().to_string())
+            .unwrap_or_default()
+    }
+
+    /// Replaces the `%field` references of an assigned value with the values of the record
+    fn interpolate(&self, value: &str, record: &[String]) -> String {
+        let mut result = String::new();
+        let mut rest = value;
+        while let Some(start) = rest.find('%') {
+            result.push_str(&rest[..start]);
+            let reference = &rest[start + 1..];
+            let end = reference
+                .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '-'))
+                .unwrap_or(reference.len());
+            match end {
+                0 => result.push('%'),
+                _ => result.push_str(&self.field_value(&reference[..end], record)),
+            }
+            rest = &reference[end..];
+        }
+        result.push_str(rest);
+        result.trim().to_string()
+    }
+
+    /// Returns the value of a transaction field for a record, if it is assigned
+    fn value(&self, name: &str, record: &[String]) -> Option<String> {
+        let assigned = |assignments: &[(String, String)]| {
+            assignments
+                .iter()
+                .rev()
+                .find(|(field, _)| field == name)
+                .map(|(_, value)| self.interpolate(value, record))
+        };
+        self.conditional_blocks
+            .iter()
+            .rev()
+            .filter(|block| block.matches(self, record))
+            .find_map(|block| assigned(&block.assignments))
+            .or_else(|| assigned(&self.assignments))
+            .or_else(|| {
+                self.fields
+                    .iter()
+                    .any(|f| f == name)
+                    .then(|| self.field_value(name, record))
+            })
+    }
+
+    fn non_empty_value(&self, name: &str, record: &[String]) -> Option<String> {
+        self.value(name, record).filter(|value| !value.is_empty())
+    }
+
+    fn date(&self, value: &str) -> Result<NaiveDate, HLParserError> {
+        let date = match &self.date_format {
+            Some(format) => NaiveDate::parse_from_str(value, format).ok(),
+            None => (parse_date, eof)
+                .parse(value)
+                .ok()
+                .map(|((date, _), _)| date),
+        };
+        date.ok_or_else(|| HLParserError::Parse(format!("Invalid date: {}", value)))
+    }
+
+    fn amount(&self, name: &str, record: &[String]) -> Result<Option<Amount>, HLParserError> {
+        let Some(value) = self.non_empty_value(name, record) else {
+            return Ok(None);
+        };
+        let (number, negate) = match value.strip_prefix('(').and_then(|v| v.strip_suffix(')')) {
+            Some(number) => (number.trim(), true),
+            None => (value.as_str(), false),
+        };
+        let number = number
+            .strip_prefix("--")
+            .or_else(|| number.strip_prefix('+'))
+            .unwrap_or(number);
+        let state = ParseState {
+            decimal_mark: self.decimal_mark,
+            ..ParseState::default()
+        };
+        let mut amount = (parse_amount_with_state(&state), eof)
+            .parse(number)
+            .map(|(amount, _)| amount)
+            .map_err(|_| HLParserError::Parse(format!("Invalid amount in {}: {}", name, value)))?;
+        if amount.currency.is_empty() {
+            amount.currency = self.non_empty_value("currency", record).unwrap_or_default();
+        }
+        Ok(Some(match negate {
+            true => amount.negate(),
+            false => amount,
+        }))
+    }
+
+    /// Converts a CSV record to a transaction
+    ///
+    /// The first posting goes to `account1` with the record amount, and the second one balances
+    /// it in `account2`, which defaults to `income:unknown` or `expenses:unknown`. The tags of the
+    /// `comment` field are added to the transaction.
+    pub fn transaction(&self, record: &[String]) -> Result<Transaction, HLParserError> {
+        let date = self
+            .non_empty_value("date", record)
+            .ok_or_else(|| HLParserError::Parse("No date".to_string()))?;
+        let secondary_date = match self.non_empty_value("date2", record) {
+            Some(date2) => Some(self.date(&date2)?),
+            None => None,
+        };
+
+        let amount = match (
+            self.amount("amount", record)?,
+            self.amount("amount-in", record)?
+                .filter(|a| !a.value.is_zero()),
+            self.amount("amount-out", record)?
+                .filter(|a| !a.value.is_zero()),
+        ) {
+            (_, Some(_), Some(_)) => {
+                return Err(HLParserError::Parse(
+                    "Both amount-in and amount-out have a value".to_string(),
+                ))
+            }
+            (_, Some(amount_in), None) => amount_in,
+            (_, None, Some(amount_out)) => amount_out.negate(),
+            (Some(amount), None, None) => amount,
+            (None, None, None) => return Err(HLParserError::Parse("No amount".to_string())),
+        };
+
+        let account1 = self
+            .non_empty_value("account1", record)
+            .ok_or_else(|| HLParserError::Parse("No account1".to_string()))?;
+        let account2 = self.non_empty_value("account2", record).unwrap_or_else(|| {
+            match amount.is_negative() {
+                true => "expenses:unknown".to_string(),
+                false => "income:unknown".to_string(),
+            }
+        });
+        let balance_assertion = self.amount("balance", record)?;
+
+        let tags = match self.non_empty_value("comment", record) {
+            Some(comment) => {
+                parse_comments_tags
+                    .parse(&comment)
+                    .map_err(|_| HLParserError::Parse(format!("Invalid comment: {}", comment)))?
+                    .1
+            }
+            None => vec![],
+        };
+
+        Ok(Transaction {
+            primary_date: self.date(&date)?,
+            secondary_date,
+            status: match self.value("status", record).as_deref() {
+                Some("*") => Status::Cleared,
+                Some("!") => Status::Pending,
+                _ => Status::Unmarked,
+            },
+            code: self.non_empty_value("code", record),
+            description: match self.value("description", record) {
+                Some(description) => {
+                    parse_description
+                        .parse(&description)
+                        .unwrap_or_else(|_| Description {
+                            payee: None,
+                            note: Some(description.clone()),
+                        })
+                }
+                None => Description {
+                    payee: None,
+                    note: None,
+                },
+            },
+            postings: vec![
+                Posting {
+                    status: Status::Unmarked,
+                    account: account1.into(),
+                    kind: PostingKind::Regular,
+                    amount: Some(amount.clone()),
+                    unit_price: None,
+                    total_price: None,
+                    balance_assertion,
+                    inclusive_assertion: false,
+                },
+                Posting {
+                    status: Status::Unmarked,
+                    account: account2.into(),
+                    kind: PostingKind::Regular,
+                    amount: Some(amount.negate()),
+                    unit_price: None,
+                    total_price: None,
+                    balance_assertion: None,
+                    inclusive_assertion: false,
+                },
+            ],
+            tags,
+        })
+    }
+}
@@ -1,5 +1,6 @@
 use chrono::{Datelike, NaiveDate};
 use winnow::{
+    ascii::{dec_int, space0, space1},
     combinator::{alt, opt, preceded, terminated},
     error::{ErrMode, FromExternalError as _, StrContext},
     PResult, Parser,
@@ -28,6 +29,7 @@ fn parse_date_components<'s>(
 // TODO: this is not great, do something about it
 fn parse_separator_date<'s>(
     separator: char,
+    default_year: Option<i32>,
 ) -> impl FnMut(&mut &'s str) -> PResult<(NaiveDate, Option<NaiveDate>)> {
     move |i: &mut &'s str| {
         let (primary_date_components, secondary_date_components) = (
@@ -38,8 +40,9 @@ fn parse_separator_date<'s>(
         )
             .parse_next(i)?;
 
-        let (y, m, d) = match primary_date_components {
-            (Some(y), m, d) => (y, m, d),
+        let (y, m, d) = match (primary_date_components, default_year) {
+            ((Some(y), m, d), _) => (y, m, d),
+            ((None, m, d), Some(y)) => (y, m, d),
             _ => {
                 return Err(ErrMode::from_external_error(
                     i,
@@ -94,10 +97,28 @@ fn parse_separator_date<'s>(
 }
 
 pub fn parse_date(input: &mut &str) -> PResult<(NaiveDate, Option<NaiveDate>)> {
-    alt((
-        parse_separator_date('-'),
-        parse_separator_date('/'),
-        parse_separator_date('.'),
-    ))
+    parse_date_with_year(None).parse_next(input)
+}
+
+/// Parses a date, using the default year for dates written without a year
+pub fn parse_date_with_year<'s>(
+    default_year: Option<i32>,
+) -> impl FnMut(&mut &'s str) -> PResult<(NaiveDate, Option<NaiveDate>)> {
+    move |input: &mut &'s str| {
+        alt((
+            parse_separator_date('-', default_year),
+            parse_separator_date('/', default_year),
+            parse_separator_date('.', default_year),
+        ))
+        .parse_next(input)
+    }
+}
+
+pub fn parse_year_directive(input: &mut &str) -> PResult<i32> {
+    preceded(
+        (alt(("year", "Y", "apply year")), space1),
+        terminated(dec_int, space0),
+    )
+    .context(StrContext::Label("year directive"))
     .parse_next(input)
 }
@@ -1,7 +1,7 @@
 use chrono::NaiveDate;
 use rstest::rstest;
 
-use crate::date::parsers::parse_date;
+use crate::date::parsers::{parse_date, parse_date_with_year, parse_year_directive};
 
 #[rstest]
 #[case::date_dash("2020-01-01", "", NaiveDate::from_ymd_opt(2020, 1, 1).unwrap(), None)]
@@ -56,3 +56,49 @@ fn test_parse_date_invalid(#[case] input: &str) {
     let mut input = input;
     assert!(parse_date(&mut input).is_err(),);
 }
+
+#[rstest]
+#[case::dash("03-15", NaiveDate::from_ymd_opt(2024, 3, 15).unwrap(), None)]
+#[case::slash("3/5", NaiveDate::from_ymd_opt(2024, 3, 5).unwrap(), None)]
+#[case::full_date_ignores_default_year("2020-01-01", NaiveDate::from_ymd_opt(2020, 1, 1).unwrap(), None)]
+#[case::secondary(
+    "12/31=1/2",
+    NaiveDate::from_ymd_opt(2024, 12, 31).unwrap(),
+    Some(NaiveDate::from_ymd_opt(2024, 1, 2).unwrap())
+)]
+fn test_parse_date_with_default_year(
+    #[case] input: &str,
+    #[case] expected_date: NaiveDate,
+    #[case] expected_secondary_date: Option<NaiveDate>,
+) {
+    let mut input = input;
+    assert_eq!(
+        parse_date_with_year(Some(2024))(&mut input).unwrap(),
+        (expected_date, expected_secondary_date)
+    );
+    assert_eq!(input, "");
+}
+
+#[rstest]
+#[case::no_default_year("03-15", None)]
+#[case::invalid_day("02-30", Some(2024))]
+fn test_parse_partial_date_invalid(#[case] input: &str, #[case] default_year: Option<i32>) {
+    let mut input = input;
+    assert!(parse_date_with_year(default_year)(&mut input).is_err());
+}
+
+#[rstest]
+#[case::short("Y 2024", 2024)]
+#[case::long("year 2023", 2023)]
+#[case::apply("apply year 2022", 2022)]
+#[case::trailing_space("Y 2024  ", 2024)]
+fn test_parse_year_directive(#[case] input: &str, #[case] expected: i32) {
+    let mut input = input;
+    assert_eq!(parse_year_directive(&mut input).unwrap(), expected);
+    assert_eq!(input, "");
+}
+
+#[test]
+fn test_parse_invalid_year_directive() {
+    assert!(parse_year_directive(&mut "Y twenty").is_err());
+}
@@ -1,7 +1,7 @@
 use winnow::{
-    ascii::{line_ending, space0},
-    combinator::{alt, delimited, eof, opt, peek, repeat_till, terminated},
-    token::{any, take_until},
+    ascii::{line_ending, space0, space1, till_line_ending},
+    combinator::{alt, delimited, eof, opt, peek, preceded, repeat_till, terminated},
+    token::{any, rest, take_till, take_until},
     PResult, Parser,
 };
 
@@ -18,7 +18,7 @@ fn parse_only_note<'s>(input: &mut &'s str) -> PResult<&'s str> {
 }
 
 pub fn parse_description(input: &mut &str) -> PResult<Description> {
-    let payee = opt(terminated(take_until(0.., "|"), "|")).parse_next(input)?;
+    let payee = opt(terminated(take_till(0.., ['|', ';', '\n', '\r']), "|")).parse_next(input)?;
     let note = opt(parse_only_note).parse_next(input)?;
 
     Ok(Description {
@@ -32,3 +32,14 @@ pub fn parse_description(input: &mut &str) -> PResult<Description> {
             .map(str::to_string),
     })
 }
+
+pub fn parse_payee_directive<'s>(input: &mut &'s str) -> PResult<&'s str> {
+    preceded(
+        ("payee", space1),
+        till_line_ending.and_then(alt((take_until(1.., "  "), rest))),
+    )
+    .map(str::trim)
+    .verify(|payee: &str| !payee.is_empty())
+    .context(winnow::error::StrContext::Label("payee directive"))
+    .parse_next(input)
+}
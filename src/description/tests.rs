@@ -1,6 +1,9 @@
 use rstest::rstest;
 
-use super::{parsers::parse_description, types::Description};
+use super::{
+    parsers::{parse_description, parse_payee_directive},
+    types::Description,
+};
 
 #[rstest]
 #[case::simple("some description", "", None, Some("some description".to_string()))]
@@ -31,6 +34,8 @@ use super::{parsers::parse_description, types::Description};
 #[case::empty_space(" ", "", None, None)]
 #[case::empty("", "", None, None)]
 #[case::with_comment(" ; blah", " ; blah", None, None)]
+#[case::pipe_in_comment("drinks ; a|b", " ; a|b", None, Some("drinks".to_string()))]
+#[case::pipe_on_next_line("drinks\nnext | line", "\nnext | line", None, Some("drinks".to_string()))]
 fn test_parse_description(
     #[case] input: &str,
     #[case] expected_remaining: &str,
@@ -47,3 +52,18 @@ fn test_parse_description(
     );
     assert_eq!(input, expected_remaining);
 }
+
+#[rstest]
+#[case::simple("payee Acme", "Acme")]
+#[case::spaces("payee Acme Corp", "Acme Corp")]
+#[case::comment("payee Acme  ; a comment", "Acme")]
+fn test_parse_payee_directive(#[case] input: &str, #[case] expected: &str) {
+    let mut input = input;
+    assert_eq!(parse_payee_directive(&mut input).unwrap(), expected);
+    assert_eq!(input, "");
+}
+
+#[test]
+fn test_parse_empty_payee_directive() {
+    assert!(parse_payee_directive(&mut "payee  ").is_err());
+}
@@ -16,7 +16,8 @@ use std::fmt::Display;
 /// let description = Description { payee: Some("Cheers bar".to_string()), note: None };
 /// assert_eq!("Cheers bar |", format!("{}", description));
 /// ```
-#[derive(PartialEq, Eq, Debug, Clone)]
+#[derive(PartialEq, Eq, Hash, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Description {
     /// The payee of the transaction
     pub payee: Option<String>,
@@ -0,0 +1,125 @@
+use rstest::rstest;
+
+use crate::{journal::parsers::parse_journal, transaction::types::Transaction};
+
+use super::types::Fingerprint;
+
+fn transactions(mut input: &str) -> Vec<Transaction> {
+    parse_journal(&mut input, None).unwrap().transactions()
+}
+
+#[rstest]
+#[case::same(
+    "2024-03-01 Coffee\n    expenses:coffee  3.50 EUR\n    assets:bank\n",
+    "2024-03-01 Coffee\n    expenses:coffee  3.50 EUR\n    assets:bank\n",
+    true
+)]
+#[case::description_case_and_spaces(
+    "2024-03-01 Coffee  Bar\n    expenses:coffee  3.50 EUR\n    assets:bank\n",
+    "2024-03-01 * coffee bar\n    expenses:coffee  3.5 EUR\n    assets:bank  -3.50 EUR\n",
+    true
+)]
+#[case::posting_order(
+    "2024-03-01 Coffee\n    expenses:coffee  3.50 EUR\n    assets:bank\n",
+    "2024-03-01 Coffee\n    assets:bank  -3.50 EUR\n    expenses:coffee\n",
+    true
+)]
+#[case::date(
+    "2024-03-01 Coffee\n    expenses:coffee  3.50 EUR\n    assets:bank\n",
+    "2024-03-02 Coffee\n    expenses:coffee  3.50 EUR\n    assets:bank\n",
+    false
+)]
+#[case::amount(
+    "2024-03-01 Coffee\n    expenses:coffee  3.50 EUR\n    assets:bank\n",
+    "2024-03-01 Coffee\n    expenses:coffee  4.50 EUR\n    assets:bank\n",
+    false
+)]
+#[case::account(
+    "2024-03-01 Coffee\n    expenses:coffee  3.50 EUR\n    assets:bank\n",
+    "2024-03-01 Coffee\n    expenses:food  3.50 EUR\n    assets:bank\n",
+    false
+)]
+#[case::description(
+    "2024-03-01 Coffee\n    expenses:coffee  3.50 EUR\n    assets:bank\n",
+    "2024-03-01 Tea\n    expenses:coffee  3.50 EUR\n    assets:bank\n",
+    false
+)]
+fn test_fingerprint_content(#[case] first: &str, #[case] second: &str, #[case] equal: bool) {
+    assert_eq!(
+        Fingerprint::new(&transactions(first)[0], None)
+            == Fingerprint::new(&transactions(second)[0], None),
+        equal
+    );
+}
+
+#[test]
+fn test_fingerprint_id_tag() {
+    let transactions = transactions(
+        r#"2024-03-01 Coffee  ; id:A1
+    expenses:coffee  3.50 EUR
+    assets:bank
+
+2024-03-02 Corrected coffee  ; id: A1
+    expenses:coffee  3.80 EUR
+    assets:bank
+
+2024-03-01 Coffee  ; id:
+    expenses:coffee  3.50 EUR
+    assets:bank
+"#,
+    );
+    assert_eq!(
+        Fingerprint::new(&transactions[0], Some("id")),
+        Fingerprint::Id("A1".to_string())
+    );
+    assert_eq!(
+        Fingerprint::new(&transactions[0], Some("id")),
+        Fingerprint::new(&transactions[1], Some("id"))
+    );
+    assert_ne!(
+        Fingerprint::new(&transactions[0], None),
+        Fingerprint::new(&transactions[1], None)
+    );
+    assert_eq!(
+        Fingerprint::new(&transactions[2], Some("id")),
+        Fingerprint::new(&transactions[0], None)
+    );
+}
+
+#[test]
+fn test_partition_new() {
+    let journal = parse_journal(
+        &mut r#"2024-03-01 Coffee
+    expenses:coffee  3.50 EUR
+    assets:bank
+
+2024-03-02 Lunch
+    expenses:food  12 EUR
+    assets:bank
+"#,
+        None,
+    )
+    .unwrap();
+    let imported = transactions(
+        r#"2024-03-01 coffee
+    expenses:coffee  3.50 EUR
+    assets:bank
+
+2024-03-01 coffee
+    expenses:coffee  3.50 EUR
+    assets:bank
+
+2024-03-02 Lunch
+    expenses:food  12 EUR
+    assets:bank
+
+2024-03-03 Dinner
+    expenses:food  20 EUR
+    assets:bank
+"#,
+    );
+
+    let (new, present) = journal.partition_new(imported.clone(), None);
+    assert_eq!(new, vec![imported[1].clone(), imported[3].clone()]);
+    assert_eq!(present, vec![imported[0].clone(), imported[2].clone()]);
+}
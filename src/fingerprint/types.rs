@@ -0,0 +1,123 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+
+use crate::transaction::types::Transaction;
+
+/// A content-based identity of a transaction, used to recognise already imported transactions
+///
+/// Transactions with a value for the id tag, e.g. `id:` holding the bank reference, are
+/// identified by that value only. Other transactions are identified by their date, their
+/// normalised description and the amounts of their accounts, ignoring statuses, codes and the
+/// order of the postings.
+///
+/// # Example
+///
+/// ```
+/// use hledger_parse::{parse_journal, Fingerprint};
+///
+/// let mut input = r#"2024-03-01 * Coffee  Bar
+///     expenses:coffee  3.50 EUR
+///     assets:bank
+///
+/// 2024-03-01 coffee bar
+///     assets:bank  -3.5 EUR
+///     expenses:coffee
+/// "#;
+/// let journal = parse_journal(&mut input, None).unwrap();
+/// let transactions = journal.transactions();
+/// assert_eq!(
+///     Fingerprint::new(&transactions[0], None),
+///     Fingerprint::new(&transactions[1], None)
+/// );
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Fingerprint {
+    /// The value of the id tag of the transaction
+    Id(String),
+    /// The content of the transaction
+    Content {
+        /// The primary date
+        date: NaiveDate,
+        /// The description, lowercased and with whitespace collapsed
+        description: String,
+        /// The account, commodity and normalised quantity of each posting amount, sorted
+        postings: Vec<(String, String, Decimal)>,
+    },
+}
+
+impl Fingerprint {
+    /// Computes the fingerprint of a transaction, identified by the value of `id_tag` if it has one
+    pub fn new(transaction: &Transaction, id_tag: Option<&str>) -> Self {
+        let id = id_tag.and_then(|name| {
+            transaction
+                .tags
+                .iter()
+                .find(|tag| tag.name == name)
+                .and_then(|tag| tag.value.as_deref())
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+        });
+        if let Some(id) = id {
+            return Fingerprint::Id(id.to_string());
+        }
+
+        let mut postings: Vec<(String, String, Decimal)> = transaction
+            .postings
+            .iter()
+            .zip(transaction.posting_amounts())
+            .flat_map(|(posting, amount)| {
+                amount.amounts().into_iter().map(|amount| {
+                    (
+                        posting.account.to_string(),
+                        amount.currency,
+                        amount.value.normalize(),
+                    )
+                })
+            })
+            .collect();
+        postings.sort();
+
+        Fingerprint::Content {
+            date: transaction.primary_date,
+            description: transaction
+                .description
+                .to_string()
+                .to_lowercase()
+                .split_whitespace()
+                .collect::<Vec<&str>>()
+                .join(" "),
+            postings,
+        }
+    }
+}
+
+/// Splits transactions into the new ones and the ones already among the existing transactions
+///
+/// Each existing transaction matches at most one transaction, so repeated identical
+/// transactions are only considered present as many times as they exist.
+pub(crate) fn partition_new<'a>(
+    existing: impl IntoIterator<Item = &'a Transaction>,
+    transactions: Vec<Transaction>,
+    id_tag: Option<&str>,
+) -> (Vec<Transaction>, Vec<Transaction>) {
+    let mut counts: HashMap<Fingerprint, usize> = HashMap::new();
+    for transaction in existing {
+        *counts
+            .entry(Fingerprint::new(transaction, id_tag))
+            .or_default() += 1;
+    }
+
+    transactions.into_iter().partition(|transaction| {
+        match counts.get_mut(&Fingerprint::new(transaction, id_tag)) {
+            Some(count) if *count > 0 => {
+                *count -= 1;
+                false
+            }
+            _ => true,
+        }
+    })
+}
@@ -1,27 +1,125 @@
-use std::{path::PathBuf, str::FromStr};
+use std::path::{Path, PathBuf};
 
 use winnow::{
     ascii::{line_ending, space0, space1, till_line_ending},
-    combinator::{alt, eof, preceded, repeat_till, terminated},
-    error::{ContextError, ErrMode},
+    combinator::{alt, eof, opt, terminated},
+    stream::AsChar,
+    token::take_while,
     PResult, Parser,
 };
 
 use crate::{
-    account::{parsers::parse_account_directive, types::Account},
-    comment::parsers::parse_line_comment,
-    commodity::{parsers::parse_commodity_directive, types::Commodity},
-    price::{parsers::parse_price, types::Price},
-    transaction::{parsers::parse_transaction, types::Transaction},
+    account::{
+        parsers::{
+            parse_account_directive, parse_apply_account_directive,
+            parse_end_apply_account_directive,
+        },
+        types::Account,
+    },
+    alias::parsers::{parse_alias_directive, parse_end_aliases_directive},
+    amount::parsers::parse_decimal_mark_directive,
+    beancount::parsers::read_beancount_from_path,
+    comment::parsers::{parse_block_comment, parse_line_comment},
+    commodity::{
+        parsers::{parse_commodity_directive, parse_default_commodity_directive},
+        types::Commodity,
+    },
+    csv::parsers::read_csv_from_path,
+    date::parsers::parse_year_directive,
+    description::parsers::parse_payee_directive,
+    ledger::{
+        parsers::{parse_ledger_entry, skip_directive_prefix, LedgerEntry},
+        types::AutomatedTransaction,
+    },
+    price::{parsers::parse_price_with_state, types::Price},
+    tag::parsers::parse_tag_directive,
+    timeclock::parsers::read_timeclock_from_path,
+    timedot::parsers::read_timedot_from_path,
+    transaction::{parsers::parse_transaction_with_state, types::Transaction},
+    utils::{expand_glob, expand_home, is_glob_pattern, LineCounter},
     HLParserError,
 };
 
-use super::types::{Journal, Value};
+use super::types::{
+    IncludedFile, InputFormat, Journal, Location, ParseOptions, ParseState, Syntax, Value,
+};
+
+/// Parses an include statement, returning the optional format prefix and the path or glob pattern
+pub(super) fn parse_include_statement<'s>(
+    input: &mut &'s str,
+) -> PResult<(Option<InputFormat>, &'s str)> {
+    let _ = ("include", space1).parse_next(input)?;
+    let format = opt(terminated(
+        take_while(2.., AsChar::is_alpha).parse_to::<InputFormat>(),
+        ':',
+    ))
+    .parse_next(input)?;
+    let path = alt((till_line_ending, eof))
+        .map(str::trim_end)
+        .verify(|p: &str| !p.is_empty())
+        .parse_next(input)?;
+    Ok((format, path))
+}
 
-fn parse_include_statement(input: &mut &str) -> PResult<PathBuf> {
-    let path = preceded(("include", space1), alt((till_line_ending, eof))).parse_next(input)?;
-    let path = PathBuf::from_str(path).map_err(|_| ErrMode::Backtrack(ContextError::new()))?; // TODO: better error
-    Ok(path)
+/// Returns the files of an include statement's path or glob pattern, relative to `base_path`
+pub(crate) fn resolve_include(
+    base_path: &Path,
+    pattern: &str,
+) -> Result<Vec<PathBuf>, HLParserError> {
+    let path = base_path.join(expand_home(pattern).ok_or_else(|| {
+        HLParserError::IncludePath(format!("Cannot find the home directory for {}", pattern))
+    })?);
+    if !is_glob_pattern(pattern) {
+        return Ok(vec![path]);
+    }
+    let paths = expand_glob(&path).map_err(|e| HLParserError::IO(e.to_string()))?;
+    match paths.is_empty() {
+        true => Err(HLParserError::IncludePath(format!(
+            "No files match {}",
+            path.display()
+        ))),
+        false => Ok(paths),
+    }
+}
+
+/// Reads an included file with the reader of its format
+///
+/// `chain` holds the canonical paths of the files being read, from the root file to the including
+/// file, and is used to detect include cycles. The files included by the file are added to
+/// `includes`.
+pub(crate) fn read_included_file(
+    path: PathBuf,
+    format: Option<InputFormat>,
+    state: ParseState,
+    chain: &[PathBuf],
+    locations: &mut Vec<Location>,
+    includes: &mut Vec<IncludedFile>,
+) -> Result<Vec<Value>, HLParserError> {
+    let canonical_path = path
+        .canonicalize()
+        .map_err(|e| HLParserError::IO(format!("{}: {}", path.display(), e)))?;
+    if chain.contains(&canonical_path) {
+        return Err(HLParserError::IncludePath(format!(
+            "Include cycle: {}",
+            chain
+                .iter()
+                .chain([&canonical_path])
+                .map(|p| p.display().to_string())
+                .collect::<Vec<String>>()
+                .join(" -> ")
+        )));
+    }
+    let chain = [chain, &[canonical_path]].concat();
+
+    match format.unwrap_or_else(|| InputFormat::from_path(&path)) {
+        InputFormat::Journal => read_journal_from_path(path, state, &chain, locations, includes),
+        InputFormat::Timeclock => read_timeclock_from_path(path, &state, locations),
+        InputFormat::Timedot => read_timedot_from_path(path, &state, locations),
+        InputFormat::Csv => read_csv_from_path(path, &state, locations),
+        InputFormat::Beancount => {
+            read_beancount_from_path(path, &state, &chain, locations, includes)
+        }
+    }
 }
 
 pub(super) fn parse_comment_value(input: &mut &str) -> PResult<Value> {
@@ -30,44 +128,171 @@ pub(super) fn parse_comment_value(input: &mut &str) -> PResult<Value> {
         .parse_next(input)
 }
 
+pub(super) fn parse_block_comment_value(input: &mut &str) -> PResult<Value> {
+    parse_block_comment.value(Value::Ignore).parse_next(input)
+}
+
 pub(super) fn parse_empty_line(input: &mut &str) -> PResult<Value> {
     terminated(space0, alt((line_ending, eof)))
         .value(Value::Ignore)
         .parse_next(input)
 }
 
-pub fn read_journal_from_path<'s>(path: PathBuf) -> Result<Vec<Value>, HLParserError> {
+pub fn read_journal_from_path(
+    path: PathBuf,
+    state: ParseState,
+    chain: &[PathBuf],
+    locations: &mut Vec<Location>,
+    includes: &mut Vec<IncludedFile>,
+) -> Result<Vec<Value>, HLParserError> {
     let contents = std::fs::read_to_string(&path).map_err(|e| HLParserError::IO(e.to_string()))?;
     let mut input = &contents[..];
-    let values = parse_journal_contents(&mut input, path)?;
+    let base_path = path.parent().map(Path::to_path_buf).unwrap_or_default();
+    let values = parse_journal_contents(
+        &mut input,
+        base_path,
+        Some(path),
+        state,
+        chain,
+        locations,
+        includes,
+    )?;
     Ok(values)
 }
 
-fn parse_journal_contents<'a>(input: &mut &'a str, base_path: PathBuf) -> Result<Vec<Value>, HLParserError> {
-    let res = repeat_till(
-        0..,
-        alt((
-            parse_transaction.map(Value::Transaction),
+/// Parses the contents of a journal file
+///
+/// Include statements are resolved relative to `base_path`, the directory of the file. The
+/// locations of the parsed transactions, including the ones of included files, are added to
+/// `locations` in the same order as the transactions of the flattened values. The included files
+/// are added to `includes`.
+fn parse_journal_contents(
+    input: &mut &str,
+    base_path: PathBuf,
+    path: Option<PathBuf>,
+    mut state: ParseState,
+    chain: &[PathBuf],
+    locations: &mut Vec<Location>,
+    includes: &mut Vec<IncludedFile>,
+) -> Result<Vec<Value>, HLParserError> {
+    let mut lines = LineCounter::new(input);
+    let mut values = vec![];
+
+    while !input.is_empty() {
+        let line = lines.line(input);
+        if state.syntax == Syntax::Ledger {
+            skip_directive_prefix(input);
+        }
+        let include = opt(parse_include_statement)
+            .parse_next(input)
+            .map_err(|e| HLParserError::Parse(e.to_string()))?;
+        if let Some((format, pattern)) = include {
+            for path in resolve_include(&base_path, pattern)? {
+                let mut nested_includes = vec![];
+                let included = read_included_file(
+                    path.clone(),
+                    format,
+                    state.clone(),
+                    chain,
+                    locations,
+                    &mut nested_includes,
+                )?;
+                // Like in ledger-cli, automated transactions of included files apply to the
+                // transactions after the include
+                if state.syntax == Syntax::Ledger {
+                    state
+                        .automated_transactions
+                        .extend(automated_transactions(&included).into_iter().cloned());
+                }
+                values.push(Value::Included(included));
+                includes.push(IncludedFile {
+                    path,
+                    line,
+                    includes: nested_includes,
+                });
+            }
+            continue;
+        }
+
+        if state.syntax == Syntax::Ledger {
+            match parse_ledger_entry(input, &state, line)? {
+                Some(LedgerEntry::Transaction(transaction)) => {
+                    locations.push(Location {
+                        path: path.clone(),
+                        line,
+                    });
+                    values.push(Value::Transaction(state.apply_to_transaction(transaction)));
+                    continue;
+                }
+                Some(LedgerEntry::AutomatedTransaction(automated)) => {
+                    state.automated_transactions.push(automated.clone());
+                    values.push(Value::AutomatedTransaction(automated));
+                    continue;
+                }
+                Some(LedgerEntry::SubDirective) => continue,
+                None => {}
+            }
+        }
+
+        let value = alt((
+            parse_transaction_with_state(&state)
+                .map(|t| Value::Transaction(state.apply_to_transaction(t))),
             parse_comment_value,
+            parse_block_comment_value,
             parse_empty_line,
-            parse_price.map(Value::Price),
-            parse_account_directive.map(|v| Value::Account(v.into())),
-            parse_commodity_directive.map(Value::Commodity),
-            parse_include_statement.try_map(|v| {
-                let values = match read_journal_from_path(base_path.join(v)) {
-                    Ok(values) => values,
-                    Err(e) => return Err(e),
-                };
-                Ok(Value::Included(values))
+            parse_price_with_state(&state).map(Value::Price),
+            parse_account_directive.map(|(name, account_type)| {
+                Value::Account(state.apply_to_account(&name.into()), account_type)
             }),
-        )),
-        eof,
-    )
-    .map(|(v, _)| v)
-    .parse(input)
-    .map_err(|e| HLParserError::Parse(e.to_string()))?;
-
-    Ok(res)
+            parse_commodity_directive.map(Value::Commodity),
+            parse_alias_directive.map(Value::Alias),
+            parse_end_aliases_directive.value(Value::EndAliases),
+            parse_apply_account_directive.map(|a| Value::ApplyAccount(a.into())),
+            parse_end_apply_account_directive.value(Value::EndApplyAccount),
+            parse_year_directive.map(Value::Year),
+            parse_default_commodity_directive.map(Value::DefaultCommodity),
+            parse_decimal_mark_directive.map(Value::DecimalMark),
+            parse_payee_directive.map(|p| Value::PayeeDeclaration(p.to_string())),
+            parse_tag_directive.map(|t| Value::TagDeclaration(t.to_string())),
+        ))
+        .parse_next(input)
+        .map_err(|e| HLParserError::Parse(format!("line {}: {}", lines.line(input), e)))?;
+
+        match value {
+            Value::Alias(alias) => state.aliases.push(alias),
+            Value::EndAliases => state.aliases.clear(),
+            Value::ApplyAccount(account) => state.parent_accounts.push(account),
+            Value::Year(year) => state.default_year = Some(year),
+            Value::DecimalMark(mark) => state.decimal_mark = Some(mark),
+            Value::DefaultCommodity(commodity) => {
+                state.default_commodity = Some(commodity.name.clone());
+                state.declare_commodity(&commodity);
+                values.push(Value::Commodity(commodity));
+            }
+            Value::Commodity(ref commodity) => {
+                state.declare_commodity(commodity);
+                values.push(value);
+            }
+            Value::EndApplyAccount => {
+                if state.parent_accounts.pop().is_none() {
+                    return Err(HLParserError::Parse(format!(
+                        "line {}: end apply account without a matching apply account",
+                        lines.line(input)
+                    )));
+                }
+            }
+            Value::Transaction(_) => {
+                locations.push(Location {
+                    path: path.clone(),
+                    line,
+                });
+                values.push(value);
+            }
+            _ => values.push(value),
+        }
+    }
+
+    Ok(values)
 }
 
 pub(super) fn flatten_values(values: Vec<Value>) -> Vec<Value> {
@@ -80,18 +305,70 @@ pub(super) fn flatten_values(values: Vec<Value>) -> Vec<Value> {
         .collect()
 }
 
+/// Returns the automated transactions of values, including the ones of included files
+fn automated_transactions(values: &[Value]) -> Vec<&AutomatedTransaction> {
+    values
+        .iter()
+        .flat_map(|v| match v {
+            Value::AutomatedTransaction(automated) => vec![automated],
+            Value::Included(contents) => automated_transactions(contents),
+            _ => vec![],
+        })
+        .collect()
+}
+
 pub fn parse_journal<'s>(
     input: &mut &'s str,
     base_path: Option<PathBuf>,
 ) -> Result<Journal, HLParserError> {
+    parse_journal_with_path(input, base_path, None, &ParseOptions::default())
+}
+
+/// Parses a journal with parser options, e.g. to read ledger-cli journals
+///
+/// See [`ParseOptions`] for an example.
+pub fn parse_journal_with_options(
+    input: &mut &str,
+    base_path: Option<PathBuf>,
+    options: &ParseOptions,
+) -> Result<Journal, HLParserError> {
+    parse_journal_with_path(input, base_path, None, options)
+}
+
+/// Parses a journal whose contents were read from `path`, which is used in locations
+pub(super) fn parse_journal_with_path(
+    input: &mut &str,
+    base_path: Option<PathBuf>,
+    path: Option<PathBuf>,
+    options: &ParseOptions,
+) -> Result<Journal, HLParserError> {
+    let mut locations = vec![];
+    let mut includes = vec![];
+    let chain: Vec<PathBuf> = path.iter().filter_map(|p| p.canonicalize().ok()).collect();
     let values = parse_journal_contents(
         input,
         base_path.unwrap_or(std::env::current_dir().map_err(|e| HLParserError::IO(e.to_string()))?),
+        path,
+        ParseState {
+            syntax: options.syntax,
+            ..ParseState::default()
+        },
+        &chain,
+        &mut locations,
+        &mut includes,
     )?;
-    // .map_err(|e| HLParserError::Parse(e))?;
+    Ok(journal_from_values(values, locations, includes))
+}
+
+/// Builds a journal from parsed values, with the locations of its transactions and its includes
+pub(crate) fn journal_from_values(
+    values: Vec<Value>,
+    locations: Vec<Location>,
+    includes: Vec<IncludedFile>,
+) -> Journal {
     let values = flatten_values(values);
 
-    Ok(Journal::new(
+    let mut journal = Journal::new(
         values
             .iter()
             .cloned()
@@ -112,5 +389,19 @@ pub fn parse_journal<'s>(
             .cloned()
             .filter_map(|v| v.try_into().ok())
             .collect::<Vec<Commodity>>(),
-    ))
+    );
+    journal.set_transaction_locations(locations);
+    journal.set_includes(includes);
+    for value in values {
+        match value {
+            Value::Account(account, Some(account_type)) => {
+                journal.set_account_type(account, account_type)
+            }
+            Value::PayeeDeclaration(payee) => journal.declare_payee(payee),
+            Value::TagDeclaration(tag) => journal.declare_tag(tag),
+            _ => {}
+        }
+    }
+
+    journal
 }
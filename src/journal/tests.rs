@@ -1,16 +1,26 @@
+use std::path::PathBuf;
+
 use chrono::NaiveDate;
+use rstest::rstest;
 use rust_decimal_macros::dec;
 
 use crate::{
+    account::types::Account,
     amount::types::Amount,
     description::types::Description,
     journal::{
-        parsers::{parse_comment_value, parse_empty_line, parse_journal},
+        parsers::{
+            parse_block_comment_value, parse_comment_value, parse_empty_line,
+            parse_include_statement, parse_journal,
+        },
         types::Journal,
+        types::Location,
+        types::{IncludedFile, InputFormat},
     },
-    posting::types::Posting,
+    posting::types::{Posting, PostingKind},
     status::types::Status,
     transaction::types::Transaction,
+    HLParserError, ValidationError,
 };
 
 use super::{parsers::flatten_values, types::Value};
@@ -53,6 +63,7 @@ fn test_flatten_values() {
             postings: vec![
                 Posting {
                     account: "assets:bank:checking".into(),
+                    kind: PostingKind::Regular,
                     amount: Some(Amount {
                         currency: "$".into(),
                         value: dec!(1),
@@ -61,14 +72,17 @@ fn test_flatten_values() {
                     unit_price: None,
                     total_price: None,
                     balance_assertion: None,
+                    inclusive_assertion: false,
                 },
                 Posting {
                     account: "income:salary".into(),
+                    kind: PostingKind::Regular,
                     amount: None,
                     status: Status::Unmarked,
                     unit_price: None,
                     total_price: None,
                     balance_assertion: None,
+                    inclusive_assertion: false,
                 },
             ],
             tags: vec![],
@@ -86,6 +100,7 @@ fn test_flatten_values() {
                 postings: vec![
                     Posting {
                         account: "assets:bank:checking".into(),
+                        kind: PostingKind::Regular,
                         amount: Some(Amount {
                             currency: "$".into(),
                             value: dec!(1),
@@ -94,14 +109,17 @@ fn test_flatten_values() {
                         unit_price: None,
                         total_price: None,
                         balance_assertion: None,
+                        inclusive_assertion: false,
                     },
                     Posting {
                         account: "income:salary".into(),
+                        kind: PostingKind::Regular,
                         amount: None,
                         status: Status::Unmarked,
                         unit_price: None,
                         total_price: None,
                         balance_assertion: None,
+                        inclusive_assertion: false,
                     },
                 ],
                 tags: vec![],
@@ -118,6 +136,7 @@ fn test_flatten_values() {
                 postings: vec![
                     Posting {
                         account: "assets:bank:checking".into(),
+                        kind: PostingKind::Regular,
                         amount: Some(Amount {
                             currency: "$".into(),
                             value: dec!(1),
@@ -126,14 +145,17 @@ fn test_flatten_values() {
                         unit_price: None,
                         total_price: None,
                         balance_assertion: None,
+                        inclusive_assertion: false,
                     },
                     Posting {
                         account: "income:salary".into(),
+                        kind: PostingKind::Regular,
                         amount: None,
                         status: Status::Unmarked,
                         unit_price: None,
                         total_price: None,
                         balance_assertion: None,
+                        inclusive_assertion: false,
                     },
                 ],
                 tags: vec![],
@@ -155,6 +177,7 @@ fn test_flatten_values() {
                 postings: vec![
                     Posting {
                         account: "assets:bank:checking".into(),
+                        kind: PostingKind::Regular,
                         amount: Some(Amount {
                             currency: "$".into(),
                             value: dec!(1),
@@ -163,14 +186,17 @@ fn test_flatten_values() {
                         unit_price: None,
                         total_price: None,
                         balance_assertion: None,
+                        inclusive_assertion: false,
                     },
                     Posting {
                         account: "income:salary".into(),
+                        kind: PostingKind::Regular,
                         amount: None,
                         status: Status::Unmarked,
                         unit_price: None,
                         total_price: None,
                         balance_assertion: None,
+                        inclusive_assertion: false,
                     },
                 ],
                 tags: vec![],
@@ -187,6 +213,7 @@ fn test_flatten_values() {
                 postings: vec![
                     Posting {
                         account: "assets:bank:checking".into(),
+                        kind: PostingKind::Regular,
                         amount: Some(Amount {
                             currency: "$".into(),
                             value: dec!(1),
@@ -195,14 +222,17 @@ fn test_flatten_values() {
                         unit_price: None,
                         total_price: None,
                         balance_assertion: None,
+                        inclusive_assertion: false,
                     },
                     Posting {
                         account: "income:salary".into(),
+                        kind: PostingKind::Regular,
                         amount: None,
                         status: Status::Unmarked,
                         unit_price: None,
                         total_price: None,
                         balance_assertion: None,
+                        inclusive_assertion: false,
                     },
                 ],
                 tags: vec![],
@@ -219,6 +249,7 @@ fn test_flatten_values() {
                 postings: vec![
                     Posting {
                         account: "assets:bank:checking".into(),
+                        kind: PostingKind::Regular,
                         amount: Some(Amount {
                             currency: "$".into(),
                             value: dec!(1),
@@ -227,14 +258,17 @@ fn test_flatten_values() {
                         unit_price: None,
                         total_price: None,
                         balance_assertion: None,
+                        inclusive_assertion: false,
                     },
                     Posting {
                         account: "income:salary".into(),
+                        kind: PostingKind::Regular,
                         amount: None,
                         status: Status::Unmarked,
                         unit_price: None,
                         total_price: None,
                         balance_assertion: None,
+                        inclusive_assertion: false,
                     },
                 ],
                 tags: vec![],
@@ -316,6 +350,7 @@ fn test_parse_journal_simple() {
                     postings: vec![
                         Posting {
                             account: "assets:bank:checking".into(),
+                            kind: PostingKind::Regular,
                             amount: Some(Amount {
                                 currency: "$".into(),
                                 value: dec!(1),
@@ -324,14 +359,17 @@ fn test_parse_journal_simple() {
                             unit_price: None,
                             total_price: None,
                             balance_assertion: None,
+                            inclusive_assertion: false,
                         },
                         Posting {
                             account: "income:salary".into(),
+                            kind: PostingKind::Regular,
                             amount: None,
                             status: Status::Unmarked,
                             unit_price: None,
                             total_price: None,
                             balance_assertion: None,
+                            inclusive_assertion: false,
                         },
                     ],
                     tags: vec![],
@@ -348,6 +386,7 @@ fn test_parse_journal_simple() {
                     postings: vec![
                         Posting {
                             account: "assets:bank:checking".into(),
+                            kind: PostingKind::Regular,
                             amount: Some(Amount {
                                 currency: "$".into(),
                                 value: dec!(1),
@@ -356,14 +395,17 @@ fn test_parse_journal_simple() {
                             unit_price: None,
                             total_price: None,
                             balance_assertion: None,
+                            inclusive_assertion: false,
                         },
                         Posting {
                             account: "income:gifts".into(),
+                            kind: PostingKind::Regular,
                             amount: None,
                             status: Status::Unmarked,
                             unit_price: None,
                             total_price: None,
                             balance_assertion: None,
+                            inclusive_assertion: false,
                         },
                     ],
                     tags: vec![],
@@ -380,6 +422,7 @@ fn test_parse_journal_simple() {
                     postings: vec![
                         Posting {
                             account: "assets:bank:saving".into(),
+                            kind: PostingKind::Regular,
                             amount: Some(Amount {
                                 currency: "$".into(),
                                 value: dec!(1),
@@ -388,14 +431,17 @@ fn test_parse_journal_simple() {
                             unit_price: None,
                             total_price: None,
                             balance_assertion: None,
+                            inclusive_assertion: false,
                         },
                         Posting {
                             account: "assets:bank:checking".into(),
+                            kind: PostingKind::Regular,
                             amount: None,
                             status: Status::Unmarked,
                             unit_price: None,
                             total_price: None,
                             balance_assertion: None,
+                            inclusive_assertion: false,
                         },
                     ],
                     tags: vec![],
@@ -412,6 +458,7 @@ fn test_parse_journal_simple() {
                     postings: vec![
                         Posting {
                             account: "expenses:food".into(),
+                            kind: PostingKind::Regular,
                             amount: Some(Amount {
                                 currency: "$".into(),
                                 value: dec!(1),
@@ -420,9 +467,11 @@ fn test_parse_journal_simple() {
                             unit_price: None,
                             total_price: None,
                             balance_assertion: None,
+                            inclusive_assertion: false,
                         },
                         Posting {
                             account: "expenses:supplies".into(),
+                            kind: PostingKind::Regular,
                             amount: Some(Amount {
                                 currency: "$".into(),
                                 value: dec!(1),
@@ -431,14 +480,17 @@ fn test_parse_journal_simple() {
                             unit_price: None,
                             total_price: None,
                             balance_assertion: None,
+                            inclusive_assertion: false,
                         },
                         Posting {
                             account: "assets:cash".into(),
+                            kind: PostingKind::Regular,
                             amount: None,
                             status: Status::Unmarked,
                             unit_price: None,
                             total_price: None,
                             balance_assertion: None,
+                            inclusive_assertion: false,
                         },
                     ],
                     tags: vec![],
@@ -455,6 +507,7 @@ fn test_parse_journal_simple() {
                     postings: vec![
                         Posting {
                             account: "liabilities:debts".into(),
+                            kind: PostingKind::Regular,
                             amount: Some(Amount {
                                 currency: "$".into(),
                                 value: dec!(1),
@@ -463,14 +516,17 @@ fn test_parse_journal_simple() {
                             unit_price: None,
                             total_price: None,
                             balance_assertion: None,
+                            inclusive_assertion: false,
                         },
                         Posting {
                             account: "assets:bank:checking".into(),
+                            kind: PostingKind::Regular,
                             amount: None,
                             status: Status::Unmarked,
                             unit_price: None,
                             total_price: None,
                             balance_assertion: None,
+                            inclusive_assertion: false,
                         },
                     ],
                     tags: vec![],
@@ -482,3 +538,811 @@ fn test_parse_journal_simple() {
         )
     );
 }
+
+/// Writes the files to a new temporary directory and returns the directory
+fn write_journal_files(name: &str, files: &[(&str, &str)]) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("hledger-parse-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    for (path, contents) in files {
+        let path = dir.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+    }
+    dir
+}
+
+fn posting_accounts(journal: &Journal) -> Vec<String> {
+    journal
+        .transactions()
+        .iter()
+        .flat_map(|t| t.postings.iter().map(|p| p.account.to_string()))
+        .collect()
+}
+
+#[test]
+fn test_parse_journal_aliases() {
+    let mut input = r#"alias checking = assets:bank:checking
+alias /^expenses:(.*)/ = exp:\1
+account checking
+
+2024-01-01 groceries
+    expenses:food  $10
+    checking
+
+end aliases
+
+2024-01-02 groceries
+    expenses:food  $10
+    checking
+"#;
+    let journal = parse_journal(&mut input, None).unwrap();
+    assert_eq!(
+        journal.accounts(),
+        vec![Account::from("assets:bank:checking")]
+    );
+    assert_eq!(
+        posting_accounts(&journal),
+        vec![
+            "exp:food",
+            "assets:bank:checking",
+            "expenses:food",
+            "checking"
+        ]
+    );
+}
+
+#[test]
+fn test_parse_journal_aliases_applied_most_recent_first() {
+    let mut input = r#"alias b = c
+alias a = b
+
+2024-01-01 test
+    a  $1
+    x
+"#;
+    let journal = parse_journal(&mut input, None).unwrap();
+    assert_eq!(posting_accounts(&journal), vec!["c", "x"]);
+}
+
+#[test]
+fn test_parse_journal_aliases_include_scope() {
+    let dir = write_journal_files(
+        "aliases",
+        &[(
+            "included.journal",
+            r#"2024-01-01 included
+    checking  $1
+    savings
+
+alias savings = assets:bank:savings
+"#,
+        )],
+    );
+    let mut input = r#"alias checking = assets:bank:checking
+include included.journal
+
+2024-01-02 main
+    checking  $1
+    savings
+"#;
+    let journal = parse_journal(&mut input, Some(dir.clone())).unwrap();
+    std::fs::remove_dir_all(dir).unwrap();
+    assert_eq!(
+        posting_accounts(&journal),
+        vec![
+            "assets:bank:checking",
+            "savings",
+            "assets:bank:checking",
+            "savings"
+        ]
+    );
+}
+
+#[test]
+fn test_parse_journal_apply_account() {
+    let mut input = r#"apply account business
+account assets:bank
+
+2024-01-01 invoice
+    assets:bank  $100
+    revenues:consulting
+
+apply account acme
+alias business:acme:x = business:acme:expenses
+
+2024-01-02 supplies
+    x  $10
+    assets:bank
+end apply account
+
+end apply account
+
+2024-01-03 personal
+    expenses:food  $10
+    assets:cash
+"#;
+    let journal = parse_journal(&mut input, None).unwrap();
+    assert_eq!(
+        journal.accounts(),
+        vec![Account::from("business:assets:bank")]
+    );
+    assert_eq!(
+        posting_accounts(&journal),
+        vec![
+            "business:assets:bank",
+            "business:revenues:consulting",
+            "business:acme:expenses",
+            "business:acme:assets:bank",
+            "expenses:food",
+            "assets:cash",
+        ]
+    );
+}
+
+#[test]
+fn test_parse_journal_unmatched_end_apply_account() {
+    let mut input = "\nend apply account\n";
+    assert_eq!(
+        parse_journal(&mut input, None).unwrap_err().to_string(),
+        "Parse error: line 2: end apply account without a matching apply account"
+    );
+}
+
+#[test]
+fn test_parse_journal_apply_account_include() {
+    let dir = write_journal_files(
+        "apply-account",
+        &[(
+            "personal.journal",
+            r#"apply account inner
+end apply account
+
+2024-01-01 groceries
+    expenses:food  $10
+    assets:cash
+"#,
+        )],
+    );
+    let mut input = r#"apply account personal
+include personal.journal
+end apply account
+include personal.journal
+"#;
+    let journal = parse_journal(&mut input, Some(dir.clone())).unwrap();
+    std::fs::remove_dir_all(dir).unwrap();
+    assert_eq!(
+        posting_accounts(&journal),
+        vec![
+            "personal:expenses:food",
+            "personal:assets:cash",
+            "expenses:food",
+            "assets:cash"
+        ]
+    );
+}
+
+#[test]
+fn test_parse_journal_default_year() {
+    let mut input = r#"Y 2023
+
+03/15 groceries
+    expenses:food  $10
+    assets:cash
+
+P 03-16 EUR $1.10
+
+year 2024
+
+01-02=01-05 rent
+    expenses:rent  $500
+    assets:bank
+"#;
+    let journal = parse_journal(&mut input, None).unwrap();
+    assert_eq!(
+        journal
+            .transactions()
+            .iter()
+            .map(|t| (t.primary_date, t.secondary_date))
+            .collect::<Vec<_>>(),
+        vec![
+            (NaiveDate::from_ymd_opt(2023, 3, 15).unwrap(), None),
+            (
+                NaiveDate::from_ymd_opt(2024, 1, 2).unwrap(),
+                Some(NaiveDate::from_ymd_opt(2024, 1, 5).unwrap())
+            ),
+        ]
+    );
+    assert_eq!(
+        journal.prices()[0].date,
+        NaiveDate::from_ymd_opt(2023, 3, 16).unwrap()
+    );
+}
+
+#[test]
+fn test_parse_journal_partial_date_without_default_year() {
+    let mut input = r#"03/15 groceries
+    expenses:food  $10
+    assets:cash
+"#;
+    assert!(parse_journal(&mut input, None).is_err());
+}
+
+#[test]
+fn test_parse_block_comment_value() {
+    let mut input = "comment\nsome text\nend comment\n";
+    assert_eq!(
+        parse_block_comment_value(&mut input).unwrap(),
+        Value::Ignore
+    );
+    assert_eq!(input, "");
+}
+
+#[test]
+fn test_parse_journal_block_comments() {
+    let mut input = r#"2024-01-01 kept
+    expenses:food  $10
+    assets:cash
+
+comment
+2023-01-01 commented out
+    expenses:food  $10
+    assets:cash
+this would not parse
+end comment
+
+2024-01-02 kept
+    expenses:food  $10
+    assets:cash
+
+comment
+2024-01-03 commented out until the end of the file
+    expenses:food  $10
+    assets:cash
+"#;
+    let journal = parse_journal(&mut input, None).unwrap();
+    assert_eq!(
+        journal
+            .transactions()
+            .iter()
+            .map(|t| t.description.to_string())
+            .collect::<Vec<String>>(),
+        vec!["kept", "kept"]
+    );
+}
+
+#[test]
+fn test_parse_journal_payee_and_tag_declarations() {
+    let mut input = r#"payee Acme
+tag id
+
+2024-01-01 Acme | invoice  ; id:1
+    assets:bank  $100
+    revenues:consulting
+
+2024-01-02 Cheers
+    expenses:bars  $10
+    assets:cash
+"#;
+    let journal = parse_journal(&mut input, None).unwrap();
+    assert_eq!(journal.declared_payees(), vec!["Acme"]);
+    assert_eq!(journal.declared_tags(), vec!["id"]);
+    assert_eq!(journal.payees(), vec!["Acme"]);
+    assert_eq!(
+        format!("{}", journal)
+            .lines()
+            .take(2)
+            .collect::<Vec<&str>>(),
+        vec!["payee Acme", "tag id"]
+    );
+}
+
+#[test]
+fn test_validate_payees() {
+    let mut input = r#"payee Acme
+
+2024-01-01 Acme | invoice
+    assets:bank  $100
+    revenues:consulting
+
+2024-01-02 Cheers
+    expenses:bars  $10
+    assets:cash
+
+2024-01-03 Cheers
+    expenses:bars  $10
+    assets:cash
+
+2024-01-04 Shop | groceries
+    expenses:food  $10
+    assets:cash
+"#;
+    let journal = parse_journal(&mut input, None).unwrap();
+    match journal.validate_payees().unwrap_err() {
+        HLParserError::Validation(ValidationError::UndeclaredPayees(payees)) => assert_eq!(
+            payees,
+            vec![
+                (
+                    "Cheers".to_string(),
+                    Some(Location {
+                        path: None,
+                        line: 7
+                    })
+                ),
+                (
+                    "Shop".to_string(),
+                    Some(Location {
+                        path: None,
+                        line: 15
+                    })
+                ),
+            ]
+        ),
+        e => panic!("Unexpected error {:?}", e),
+    }
+    journal.validate_tags().unwrap();
+}
+
+#[test]
+fn test_validate_tags() {
+    let dir = write_journal_files(
+        "validate-tags",
+        &[(
+            "included.journal",
+            r#"
+2024-01-02 groceries  ; receipt:, id:2
+    expenses:food  $10
+    assets:cash
+"#,
+        )],
+    );
+    let mut input = r#"tag id
+
+2024-01-01 invoice  ; id:1
+    assets:bank  $100
+    revenues:consulting
+include included.journal
+"#;
+    let journal = parse_journal(&mut input, Some(dir.clone())).unwrap();
+    let error = journal.validate_tags().unwrap_err();
+    std::fs::remove_dir_all(&dir).unwrap();
+    match &error {
+        HLParserError::Validation(ValidationError::UndeclaredTags(tags)) => assert_eq!(
+            tags,
+            &vec![(
+                "receipt".to_string(),
+                Some(Location {
+                    path: Some(dir.join("included.journal")),
+                    line: 2
+                })
+            )]
+        ),
+        e => panic!("Unexpected error {:?}", e),
+    }
+    assert_eq!(
+        error.to_string(),
+        format!(
+            "Validation error: These tags are not declared:\nreceipt ({}:2)",
+            dir.join("included.journal").display()
+        )
+    );
+}
+
+#[test]
+fn test_parse_journal_default_commodity() {
+    let mut input = r#"2024-01-01 before
+    expenses:food  5
+    assets:cash
+
+D $1,000.00
+
+P 2024-01-02 EUR 1.10
+
+2024-01-02 after
+    expenses:food  5 = 5
+    assets:cash  -5 EUR @@ 5
+"#;
+    let journal = parse_journal(&mut input, None).unwrap();
+    let transactions = journal.transactions();
+    assert_eq!(
+        transactions[0].postings[0].amount,
+        Some(Amount {
+            currency: "".into(),
+            value: dec!(5)
+        })
+    );
+    let posting = &transactions[1].postings[0];
+    assert_eq!(posting.amount.as_ref().unwrap().currency, "$");
+    assert_eq!(posting.balance_assertion.as_ref().unwrap().currency, "$");
+    assert_eq!(
+        transactions[1].postings[1]
+            .total_price
+            .as_ref()
+            .unwrap()
+            .currency,
+        "$"
+    );
+    assert_eq!(journal.prices()[0].amount.currency, "$");
+    assert_eq!(journal.commodities()[0].name, "$");
+}
+
+#[test]
+fn test_validate_commodities() {
+    let mut input = r#"commodity EUR
+D $1.00
+
+P 2024-01-01 BTC 40000 EUR
+
+2024-01-02 exchange
+    assets:cash  10 EUR @@ 12 CHF
+    assets:bank  -12
+
+2024-01-03 exchange
+    assets:cash  10 EUR @@ 12 CHF
+    assets:bank
+"#;
+    let journal = parse_journal(&mut input, None).unwrap();
+    let error = journal.validate_commodities().unwrap_err();
+    match &error {
+        HLParserError::Validation(ValidationError::UndeclaredCommodities(commodities)) => {
+            assert_eq!(
+                commodities,
+                &vec![
+                    (
+                        "CHF".to_string(),
+                        Some(Location {
+                            path: None,
+                            line: 6
+                        })
+                    ),
+                    ("BTC".to_string(), None),
+                ]
+            )
+        }
+        e => panic!("Unexpected error {:?}", e),
+    }
+    assert_eq!(
+        error.to_string(),
+        "Validation error: These commodities are not declared:\nCHF (line 6)\nBTC"
+    );
+}
+
+#[test]
+fn test_parse_journal_decimal_marks() {
+    let mut input = r#"commodity 1.000,00 EUR
+decimal-mark .
+
+2024-01-01 salary
+    assets:bank  1.234,56 EUR
+    assets:cash  $1,234.56
+    revenues:salary  -1.234,56 EUR
+    revenues:salary  $-1,234.56
+
+P 2024-01-02 BTC $40,000.5
+"#;
+    let journal = parse_journal(&mut input, None).unwrap();
+    let transactions = journal.transactions();
+    assert_eq!(
+        transactions[0].postings[0].amount,
+        Some(Amount {
+            currency: "EUR".into(),
+            value: dec!(1234.56)
+        })
+    );
+    assert_eq!(
+        transactions[0].postings[1].amount,
+        Some(Amount {
+            currency: "$".into(),
+            value: dec!(1234.56)
+        })
+    );
+    assert_eq!(journal.prices()[0].amount.value, dec!(40000.5));
+
+    let mut input = r#"commodity 1.000,00 EUR
+
+P 2024-01-02 BTC 40,000.5 EUR
+"#;
+    assert!(parse_journal(&mut input, None).is_err());
+}
+
+#[rstest]
+#[case::path("include 2024.journal", None, "2024.journal")]
+#[case::glob("include 2024/*.journal  ", None, "2024/*.journal")]
+#[case::format_prefix(
+    "include timeclock:work.log\n",
+    Some(InputFormat::Timeclock),
+    "work.log"
+)]
+#[case::home("include ~/finance/main.journal", None, "~/finance/main.journal")]
+#[case::windows_drive("include C:/finance.journal", None, "C:/finance.journal")]
+fn test_parse_include_statement(
+    #[case] input: &str,
+    #[case] expected_format: Option<InputFormat>,
+    #[case] expected_path: &str,
+) {
+    let mut input = input;
+    assert_eq!(
+        parse_include_statement(&mut input).unwrap(),
+        (expected_format, expected_path)
+    );
+}
+
+#[test]
+fn test_parse_journal_include_glob() {
+    let transaction = |description: &str| {
+        format!(
+            "2024-01-01 {}\n    expenses:food  $1\n    assets:cash\n",
+            description
+        )
+    };
+    let dir = write_journal_files(
+        "include-glob",
+        &[
+            ("2024/02.journal", &transaction("february")),
+            ("2024/01.journal", &transaction("january")),
+            ("2024/notes.txt", "not a journal"),
+            ("prices/2024/eur.prices", "P 2024-01-01 EUR $1.10\n"),
+            ("prices/usd.prices", "P 2024-01-01 USD 0.90 EUR\n"),
+        ],
+    );
+    let mut input = "include 2024/*.journal\ninclude **/*.prices\n";
+    let journal = parse_journal(&mut input, Some(dir.clone())).unwrap();
+    let mut input = "include 2025/*.journal\n";
+    let missing = parse_journal(&mut input, Some(dir.clone()));
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(
+        journal
+            .transactions()
+            .iter()
+            .map(|t| t.description.note.clone().unwrap())
+            .collect::<Vec<String>>(),
+        vec!["january", "february"]
+    );
+    assert_eq!(
+        journal
+            .prices()
+            .iter()
+            .map(|p| p.commodity.clone())
+            .collect::<Vec<String>>(),
+        vec!["EUR", "USD"]
+    );
+    assert!(matches!(missing, Err(HLParserError::IncludePath(_))));
+}
+
+#[test]
+fn test_parse_journal_include_format_prefix() {
+    let dir = write_journal_files(
+        "include-format",
+        &[(
+            "work.log",
+            "2024-01-01 included\n    expenses:food  $1\n    assets:cash\n",
+        )],
+    );
+    let mut input = "include journal:work.log\n";
+    let journal = parse_journal(&mut input, Some(dir.clone())).unwrap();
+    let mut input = "include timeclock:work.log\n";
+    let timeclock = parse_journal(&mut input, Some(dir.clone()));
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(journal.transactions().len(), 1);
+    assert!(matches!(timeclock, Err(HLParserError::Parse(_))));
+}
+
+#[test]
+fn test_parse_journal_include_cycle() {
+    let dir = write_journal_files("include-cycle", &[]);
+    let a = dir.join("a.journal");
+    let b = dir.join("b.journal");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(&a, format!("include {}\n", b.display())).unwrap();
+    std::fs::write(&b, format!("; comment\ninclude {}\n", a.display())).unwrap();
+    std::fs::write(
+        dir.join("self.journal"),
+        format!("include {}\n", dir.join("self.journal").display()),
+    )
+    .unwrap();
+
+    let indirect = Journal::try_from(a.clone());
+    let direct = Journal::try_from(dir.join("self.journal"));
+    let input = format!("include {}\n", a.display());
+    let from_string = parse_journal(&mut input.as_str(), None);
+    let canonical_a = a.canonicalize().unwrap();
+    let canonical_b = b.canonicalize().unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    match indirect {
+        Err(HLParserError::IncludePath(message)) => assert_eq!(
+            message,
+            format!(
+                "Include cycle: {} -> {} -> {}",
+                canonical_a.display(),
+                canonical_b.display(),
+                canonical_a.display()
+            )
+        ),
+        r => panic!("Unexpected result {:?}", r),
+    }
+    assert!(matches!(direct, Err(HLParserError::IncludePath(_))));
+    assert!(matches!(from_string, Err(HLParserError::IncludePath(_))));
+}
+
+#[test]
+fn test_parse_journal_include_tree() {
+    let dir = write_journal_files(
+        "include-tree",
+        &[
+            ("accounts.journal", "account assets:cash\n"),
+            ("2024/01.journal", "P 2024-01-01 EUR $1.10\n"),
+            ("2024/02.journal", "P 2024-02-01 EUR $1.20\n"),
+        ],
+    );
+    std::fs::write(
+        dir.join("2024.journal"),
+        format!(
+            "; prices\ninclude {}\n",
+            dir.join("2024/*.journal").display()
+        ),
+    )
+    .unwrap();
+    let mut input = r#"include accounts.journal
+
+include 2024.journal
+"#;
+    let journal = parse_journal(&mut input, Some(dir.clone())).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(
+        journal.includes(),
+        &[
+            IncludedFile {
+                path: dir.join("accounts.journal"),
+                line: 1,
+                includes: vec![],
+            },
+            IncludedFile {
+                path: dir.join("2024.journal"),
+                line: 3,
+                includes: vec![
+                    IncludedFile {
+                        path: dir.join("2024/01.journal"),
+                        line: 2,
+                        includes: vec![],
+                    },
+                    IncludedFile {
+                        path: dir.join("2024/02.journal"),
+                        line: 2,
+                        includes: vec![],
+                    },
+                ],
+            },
+        ]
+    );
+    assert_eq!(
+        journal.included_paths(),
+        vec![
+            dir.join("accounts.journal"),
+            dir.join("2024.journal"),
+            dir.join("2024/01.journal"),
+            dir.join("2024/02.journal"),
+        ]
+    );
+}
+
+fn fixture(path: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(path)
+}
+
+#[test]
+fn test_parse_journal_nested_includes() {
+    let journal = Journal::try_from(fixture("includes/main.journal")).unwrap();
+
+    assert_eq!(
+        journal
+            .transactions()
+            .iter()
+            .map(|t| t.payee().unwrap().to_string())
+            .collect::<Vec<String>>(),
+        vec!["Landlord", "Grocer"]
+    );
+    assert_eq!(journal.accounts().len(), 3);
+    assert_eq!(journal.declared_payees(), vec!["Grocer", "Landlord"]);
+    assert!(journal.validate_accounts().is_ok());
+    assert!(journal.validate_payees().is_ok());
+    assert!(journal.validate_commodities().is_ok());
+    assert_eq!(
+        journal.included_paths(),
+        vec![
+            fixture("includes/accounts/accounts.journal"),
+            fixture("includes/2024/2024.journal"),
+            fixture("includes/2024/q1/01.journal"),
+            fixture("includes/2024/q1/02.journal"),
+            fixture("includes/2024/q1/../../common/payees.journal"),
+        ]
+    );
+    assert_eq!(
+        journal.transaction_location(1),
+        Some(&Location {
+            path: Some(fixture("includes/2024/q1/02.journal")),
+            line: 3
+        })
+    );
+}
+
+#[test]
+fn test_parse_journal_nested_includes_from_string() {
+    let mut input = "include 2024/2024.journal\n";
+    let journal = parse_journal(&mut input, Some(fixture("includes"))).unwrap();
+    assert_eq!(journal.transactions().len(), 2);
+    assert_eq!(journal.declared_payees().len(), 2);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_journal_serde_round_trip() {
+    let journal = Journal::try_from(fixture("includes/main.journal")).unwrap();
+    let json = serde_json::to_string(&journal).unwrap();
+    let deserialized: Journal = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(deserialized, journal);
+    assert_eq!(deserialized.included_paths(), journal.included_paths());
+    assert_eq!(
+        deserialized.transaction_location(1),
+        journal.transaction_location(1)
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_transaction_serde_field_names() {
+    let mut input = "2024-01-05=2024-01-07 * (42) Grocer | Food ; trip:\n    expenses:food  $10.50\n    assets:bank\n";
+    let journal = parse_journal(&mut input, None).unwrap();
+    let json = serde_json::to_value(&journal.transactions()[0]).unwrap();
+
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "primary_date": "2024-01-05",
+            "secondary_date": "2024-01-07",
+            "status": "cleared",
+            "code": "42",
+            "description": {"payee": "Grocer", "note": "Food"},
+            "postings": [
+                {
+                    "status": "unmarked",
+                    "account": "expenses:food",
+                    "kind": "regular",
+                    "amount": {"currency": "$", "value": "10.50"},
+                    "unit_price": null,
+                    "total_price": null,
+                    "balance_assertion": null,
+                    "inclusive_assertion": false
+                },
+                {
+                    "status": "unmarked",
+                    "account": "assets:bank",
+                    "kind": "regular",
+                    "amount": null,
+                    "unit_price": null,
+                    "total_price": null,
+                    "balance_assertion": null,
+                    "inclusive_assertion": false
+                }
+            ],
+            "tags": [{"name": "trip", "value": null}]
+        })
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_journal_serde_without_locations() {
+    let journal: Journal = serde_json::from_str(
+        r#"{"transactions": [], "accounts": ["assets:bank"], "account_types": {"assets:bank": "cash"},
+            "prices": [], "commodities": [], "declared_payees": [], "declared_tags": []}"#,
+    )
+    .unwrap();
+    assert_eq!(journal.accounts(), vec![Account::from("assets:bank")]);
+    assert_eq!(journal.transaction_location(0), None);
+}
@@ -1,27 +1,334 @@
-use std::{fmt::Display, path::PathBuf};
+use std::{
+    collections::BTreeMap,
+    fmt::Display,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
 
 use crate::{
-    account::types::Account, commodity::types::Commodity, parse_journal, price::types::Price,
-    transaction::types::Transaction, HLParserError,
+    account::types::{Account, AccountTree, AccountType},
+    alias::types::AccountAlias,
+    amount::types::{Amount, MixedAmount},
+    beancount::parsers::parse_beancount_with_path,
+    check::types::Check,
+    commodity::types::Commodity,
+    fingerprint::types::partition_new,
+    ledger::types::AutomatedTransaction,
+    price::types::Price,
+    transaction::types::Transaction,
+    CheckError, HLParserError, ValidationError,
 };
 
+use super::parsers::parse_journal_with_path;
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Value {
     Ignore,
     Transaction(Transaction),
     Included(Vec<Value>),
     Price(Price),
-    Account(Account),
+    Account(Account, Option<AccountType>),
     Commodity(Commodity),
+    Alias(AccountAlias),
+    EndAliases,
+    ApplyAccount(Account),
+    EndApplyAccount,
+    Year(i32),
+    DefaultCommodity(Commodity),
+    DecimalMark(char),
+    PayeeDeclaration(String),
+    TagDeclaration(String),
+    AutomatedTransaction(AutomatedTransaction),
+}
+
+/// Format of an input file, chosen with an include prefix like `timeclock:` or from the file
+/// extension
+///
+/// # Example
+///
+/// ```
+/// use std::path::Path;
+/// use hledger_parse::InputFormat;
+///
+/// assert_eq!(InputFormat::Timeclock, "timeclock".parse().unwrap());
+/// assert_eq!(InputFormat::Csv, InputFormat::from_path(Path::new("bank/2024.csv")));
+/// assert_eq!(InputFormat::Journal, InputFormat::from_path(Path::new("2024.prices")));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum InputFormat {
+    Journal,
+    Timeclock,
+    Timedot,
+    Csv,
+    Beancount,
+}
+
+impl InputFormat {
+    /// Returns the format of a file from its extension, defaulting to the journal format
+    pub fn from_path(path: &Path) -> InputFormat {
+        match path
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .as_deref()
+        {
+            Some("timeclock") => InputFormat::Timeclock,
+            Some("timedot") => InputFormat::Timedot,
+            Some("csv") => InputFormat::Csv,
+            Some("beancount" | "bean") => InputFormat::Beancount,
+            _ => InputFormat::Journal,
+        }
+    }
+}
+
+impl FromStr for InputFormat {
+    type Err = HLParserError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "journal" => Ok(InputFormat::Journal),
+            "timeclock" => Ok(InputFormat::Timeclock),
+            "timedot" => Ok(InputFormat::Timedot),
+            "csv" => Ok(InputFormat::Csv),
+            "beancount" => Ok(InputFormat::Beancount),
+            _ => Err(HLParserError::Parse(format!("Unknown file format: {}", s))),
+        }
+    }
+}
+
+impl Display for InputFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InputFormat::Journal => write!(f, "journal"),
+            InputFormat::Timeclock => write!(f, "timeclock"),
+            InputFormat::Timedot => write!(f, "timedot"),
+            InputFormat::Csv => write!(f, "csv"),
+            InputFormat::Beancount => write!(f, "beancount"),
+        }
+    }
+}
+
+/// Syntax of journal files
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Syntax {
+    /// The hledger journal syntax
+    #[default]
+    Hledger,
+    /// The ledger-cli journal syntax
+    ///
+    /// Comments may follow a single space, comments may set tags with `:tag:` or `key: value`
+    /// and the effective date with `[=DATE]`, postings may have lot annotations like `{PRICE}`,
+    /// directives may start with `!` or `@`, and automated transactions start with `=`. The
+    /// indented sub-directives of `account`, `commodity`, `payee` and `tag` blocks, like `note`,
+    /// are skipped. Constructs with no equivalent, like periodic transactions, amount expressions
+    /// or posting dates, are errors.
+    Ledger,
+}
+
+/// Options of the journal parser
+///
+/// # Example
+///
+/// ```
+/// use hledger_parse::{parse_journal_with_options, ParseOptions, Syntax};
+///
+/// let mut input = r#"2024/01/03 * (42) Shop ; :food: [=2024/01/05]
+///     Expenses:Food  10 AAPL {$1.50} [2024/01/01] ; bought on sale
+///     Assets:Bank
+/// "#;
+/// let options = ParseOptions { syntax: Syntax::Ledger };
+/// let journal = parse_journal_with_options(&mut input, None, &options).unwrap();
+/// assert_eq!(
+///     "2024-01-03=2024-01-05* Shop ; food:\n   Expenses:Food  10 AAPL @ 1.50 $\n   Assets:Bank\n",
+///     format!("{}", journal.transactions()[0])
+/// );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ParseOptions {
+    /// The syntax of the journal and of the journals it includes
+    pub syntax: Syntax,
+}
+
+/// Location of an item in the journal files
+///
+/// # Example
+///
+/// ```
+/// use std::path::PathBuf;
+/// use hledger_parse::Location;
+///
+/// let location = Location { path: Some(PathBuf::from("2024.journal")), line: 12 };
+/// assert_eq!("2024.journal:12", format!("{}", location));
+/// let location = Location { path: None, line: 12 };
+/// assert_eq!("line 12", format!("{}", location));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Location {
+    /// The file of the item, `None` if the journal wasn't read from a file
+    pub path: Option<PathBuf>,
+    /// The line number where the item starts, starting at 1
+    pub line: usize,
+}
+
+impl Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.path {
+            Some(path) => write!(f, "{}:{}", path.display(), self.line),
+            None => write!(f, "line {}", self.line),
+        }
+    }
+}
+
+/// A file included by a journal, with the files it includes in turn
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IncludedFile {
+    /// The path of the included file
+    pub path: PathBuf,
+    /// The line of the include statement in the including file
+    pub line: usize,
+    /// The files included by this file, in include order
+    pub includes: Vec<IncludedFile>,
+}
+
+/// State of the journal parser, changed by directives such as `alias`, `apply account` or `Y`
+///
+/// The state of a file is passed on to the files it includes, but changes made in an included
+/// file don't affect the including file. The exception is the automated transactions of the
+/// ledger-cli syntax, which apply after the include like in ledger-cli.
+#[derive(Debug, Clone, Default)]
+pub struct ParseState {
+    /// The active account aliases, in order of declaration
+    pub aliases: Vec<AccountAlias>,
+    /// The active `apply account` parent accounts, outermost first
+    pub parent_accounts: Vec<Account>,
+    /// The year of dates written without a year, set with the `Y` or `year` directive
+    pub default_year: Option<i32>,
+    /// The commodity of amounts written without a commodity, set with the `D` directive
+    pub default_commodity: Option<String>,
+    /// The decimal mark of all numbers, set with the `decimal-mark` directive
+    pub decimal_mark: Option<char>,
+    /// The decimal marks of the commodities declared with a format
+    pub commodity_decimal_marks: BTreeMap<String, char>,
+    /// The syntax of the journal files, chosen with [`ParseOptions`]
+    pub syntax: Syntax,
+    /// The ledger-cli automated transactions applied to the following transactions
+    pub automated_transactions: Vec<AutomatedTransaction>,
+}
+
+impl ParseState {
+    /// Returns the decimal mark of the numbers of a commodity, if one was declared
+    ///
+    /// The decimal mark of the commodity's format takes precedence over the `decimal-mark`
+    /// directive.
+    pub fn decimal_mark(&self, commodity: &str) -> Option<char> {
+        self.commodity_decimal_marks
+            .get(commodity)
+            .copied()
+            .or(self.decimal_mark)
+    }
+
+    /// Records the decimal mark of a declared commodity's format
+    pub fn declare_commodity(&mut self, commodity: &Commodity) {
+        if let Some(mark) = commodity.decimal_mark() {
+            self.commodity_decimal_marks
+                .insert(commodity.name.clone(), mark);
+        }
+    }
+
+    /// Returns the account with the active aliases applied, the most recent alias first
+    pub fn apply_aliases(&self, account: &Account) -> Account {
+        self.aliases
+            .iter()
+            .rev()
+            .fold(account.clone(), |account, alias| alias.apply(&account))
+    }
+
+    /// Returns the account prefixed with the parent accounts and with the aliases applied
+    pub fn apply_to_account(&self, account: &Account) -> Account {
+        let account = match self.parent_accounts.is_empty() {
+            true => account.clone(),
+            false => format!(
+                "{}:{}",
+                self.parent_accounts
+                    .iter()
+                    .map(Account::as_str)
+                    .collect::<Vec<&str>>()
+                    .join(":"),
+                account
+            )
+            .into(),
+        };
+        self.apply_aliases(&account)
+    }
+
+    /// Sets the default commodity on an amount written without a commodity
+    pub fn apply_default_commodity(&self, amount: &mut Amount) {
+        if let (true, Some(commodity)) = (amount.currency.is_empty(), &self.default_commodity) {
+            amount.currency = commodity.clone();
+        }
+    }
+
+    /// Returns the transaction with the state applied to its postings, followed by the postings of
+    /// the matching automated transactions
+    pub fn apply_to_transaction(&self, mut transaction: Transaction) -> Transaction {
+        for posting in transaction.postings.iter_mut() {
+            posting.account = self.apply_to_account(&posting.account);
+            for amount in [
+                &mut posting.amount,
+                &mut posting.unit_price,
+                &mut posting.total_price,
+                &mut posting.balance_assertion,
+            ]
+            .into_iter()
+            .flatten()
+            {
+                self.apply_default_commodity(amount);
+            }
+        }
+        self.automated_transactions
+            .iter()
+            .fold(transaction, |transaction, automated| {
+                automated.apply(transaction)
+            })
+    }
 }
 
 /// A journal is a collection of transactions, accounts, prices, and commodities
-#[derive(PartialEq, Eq, Debug)]
+///
+/// Journals are compared by their contents, ignoring where the contents were parsed from and the
+/// included files.
+#[derive(Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Journal {
     transactions: Vec<Transaction>,
     accounts: Vec<Account>,
+    account_types: BTreeMap<Account, AccountType>,
     prices: Vec<Price>,
     commodities: Vec<Commodity>,
+    declared_payees: Vec<String>,
+    declared_tags: Vec<String>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    transaction_locations: Vec<Location>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    includes: Vec<IncludedFile>,
+}
+
+impl PartialEq for Journal {
+    fn eq(&self, other: &Self) -> bool {
+        self.transactions == other.transactions
+            && self.accounts == other.accounts
+            && self.account_types == other.account_types
+            && self.prices == other.prices
+            && self.commodities == other.commodities
+            && self.declared_payees == other.declared_payees
+            && self.declared_tags == other.declared_tags
+    }
 }
 
 impl TryFrom<PathBuf> for Journal {
@@ -29,16 +336,36 @@ impl TryFrom<PathBuf> for Journal {
 
     fn try_from(value: PathBuf) -> Result<Self, Self::Error> {
         let base_path = value.parent().map(|v| v.to_owned());
-        let journal_file_contents = std::fs::read_to_string(value).map_err(|e| HLParserError::IO(e.to_string()))?;
+        let journal_file_contents =
+            std::fs::read_to_string(&value).map_err(|e| HLParserError::IO(e.to_string()))?;
         let mut journal_str = journal_file_contents.as_str();
-        parse_journal(&mut journal_str, base_path)
+        match InputFormat::from_path(&value) {
+            InputFormat::Beancount => {
+                parse_beancount_with_path(&mut journal_str, base_path, Some(value))
+            }
+            _ => parse_journal_with_path(
+                &mut journal_str,
+                base_path,
+                Some(value),
+                &ParseOptions::default(),
+            ),
+        }
     }
 }
 
 impl Display for Journal {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         for a in &self.accounts {
-            writeln!(f, "account {}", a)?;
+            match self.account_types.get(a) {
+                Some(account_type) => writeln!(f, "account {}  ; type: {}", a, account_type)?,
+                None => writeln!(f, "account {}", a)?,
+            }
+        }
+        for p in &self.declared_payees {
+            writeln!(f, "payee {}", p)?;
+        }
+        for t in &self.declared_tags {
+            writeln!(f, "tag {}", t)?;
         }
         for c in &self.commodities {
             writeln!(f, "{}", c)?;
@@ -64,8 +391,13 @@ impl Journal {
         Journal {
             transactions,
             accounts,
+            account_types: BTreeMap::new(),
             prices,
             commodities,
+            declared_payees: vec![],
+            declared_tags: vec![],
+            transaction_locations: vec![],
+            includes: vec![],
         }
     }
 
@@ -73,10 +405,97 @@ impl Journal {
         self.transactions.clone()
     }
 
+    pub(crate) fn set_transaction_locations(&mut self, locations: Vec<Location>) {
+        self.transaction_locations = locations;
+    }
+
+    /// Returns the location of the transaction at the given index of [`Journal::transactions`]
+    ///
+    /// Only journals parsed from text have locations.
+    pub fn transaction_location(&self, index: usize) -> Option<&Location> {
+        self.transaction_locations.get(index)
+    }
+
+    pub(crate) fn set_includes(&mut self, includes: Vec<IncludedFile>) {
+        self.includes = includes;
+    }
+
+    /// Returns the files included by the journal, each with the files it includes
+    pub fn includes(&self) -> &[IncludedFile] {
+        &self.includes
+    }
+
+    /// Returns the paths of all the files included directly or indirectly by the journal
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hledger_parse::parse_journal;
+    ///
+    /// let mut input = "2024-01-01 opening\n    assets:cash  $10\n    equity:opening\n";
+    /// let journal = parse_journal(&mut input, None).unwrap();
+    /// assert!(journal.included_paths().is_empty());
+    /// ```
+    pub fn included_paths(&self) -> Vec<PathBuf> {
+        fn collect(includes: &[IncludedFile], paths: &mut Vec<PathBuf>) {
+            for include in includes {
+                if !paths.contains(&include.path) {
+                    paths.push(include.path.clone());
+                }
+                collect(&include.includes, paths);
+            }
+        }
+        let mut paths = vec![];
+        collect(&self.includes, &mut paths);
+        paths
+    }
+
     pub fn accounts(&self) -> Vec<Account> {
         self.accounts.clone()
     }
 
+    /// Declares the type of an account
+    pub fn set_account_type(&mut self, account: Account, account_type: AccountType) {
+        self.account_types.insert(account, account_type);
+    }
+
+    /// Returns the type of an account
+    ///
+    /// The type is the one declared for the account or its closest declared parent account,
+    /// falling back to the type inferred from the account name.
+    pub fn account_type(&self, account: &Account) -> Option<AccountType> {
+        std::iter::once(account.clone())
+            .chain(account.ancestors())
+            .find_map(|a| self.account_types.get(&a).copied())
+            .or_else(|| account.inferred_type())
+    }
+
+    /// Returns the tree of the declared accounts and the accounts used in postings
+    pub fn account_tree(&self) -> AccountTree {
+        AccountTree::new(
+            self.accounts.iter().cloned().chain(
+                self.transactions
+                    .iter()
+                    .flat_map(|t| t.postings.iter().map(|p| p.account.clone())),
+            ),
+        )
+    }
+
+    /// Returns the balance of each account used in postings, without subaccounts
+    pub fn account_balances(&self) -> BTreeMap<Account, MixedAmount> {
+        let mut balances: BTreeMap<Account, MixedAmount> = BTreeMap::new();
+        for transaction in &self.transactions {
+            for (posting, amount) in transaction
+                .postings
+                .iter()
+                .zip(transaction.posting_amounts())
+            {
+                *balances.entry(posting.account.clone()).or_default() += &amount;
+            }
+        }
+        balances
+    }
+
     pub fn prices(&self) -> Vec<Price> {
         self.prices.clone()
     }
@@ -85,11 +504,33 @@ impl Journal {
         self.commodities.clone()
     }
 
+    /// Declares a payee, like the `payee` directive
+    pub fn declare_payee(&mut self, payee: String) {
+        self.declared_payees.push(payee);
+    }
+
+    /// Returns the payees declared with `payee` directives
+    pub fn declared_payees(&self) -> Vec<String> {
+        self.declared_payees.clone()
+    }
+
+    /// Declares a tag name, like the `tag` directive
+    pub fn declare_tag(&mut self, tag: String) {
+        self.declared_tags.push(tag);
+    }
+
+    /// Returns the tag names declared with `tag` directives
+    pub fn declared_tags(&self) -> Vec<String> {
+        self.declared_tags.clone()
+    }
+
+    /// Returns the declared payees and the payees of transactions, sorted and without duplicates
     pub fn payees(&self) -> Vec<String> {
         let mut tx_payees: Vec<String> = self
             .transactions
             .iter()
             .filter_map(|t| t.description.payee.clone())
+            .chain(self.declared_payees.iter().cloned())
             .collect();
         tx_payees.sort();
         let mut unique_payees: Vec<String> = vec![];
@@ -103,24 +544,192 @@ impl Journal {
         unique_payees
     }
 
+    /// Checks that the accounts of all postings are declared with `account` directives
+    ///
+    /// [`Journal::check`] with [`Check::Accounts`] also returns where each account is first used.
     pub fn validate_accounts(&self) -> Result<(), HLParserError> {
-        let undefined_accounts: Vec<Account> = self
-            .transactions
-            .iter()
-            .flat_map(|t| t.postings.clone())
-            .map(|p| p.account)
-            .filter(|a| !self.accounts.contains(a))
-            .collect();
-        if !undefined_accounts.is_empty() {
+        let undefined = self.undeclared_names(
+            |t| t.postings.iter().map(|p| p.account.as_str()),
+            |name| self.accounts.iter().any(|a| a.as_str() == name),
+        );
+        if !undefined.is_empty() {
             return Err(HLParserError::Validation(
-                crate::ValidationError::UndefinedAccounts(
-                    undefined_accounts
-                        .iter()
-                        .map(|v| v.to_string())
-                        .collect::<Vec<String>>(),
+                ValidationError::UndefinedAccounts(
+                    undefined.into_iter().map(|(name, _)| name).collect(),
                 ),
             ));
         }
         Ok(())
     }
+
+    /// Runs the given checks and returns all the violations found
+    ///
+    /// The violations are grouped by check, in the order of `checks`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hledger_parse::{parse_journal, Check, CheckError};
+    ///
+    /// let mut input = r#"account assets:cash
+    ///
+    /// 2024-01-02 groceries
+    ///     expenses:food  10 EUR
+    ///     assets:cash
+    /// "#;
+    /// let journal = parse_journal(&mut input, None).unwrap();
+    /// let violations = journal.check(&[Check::Accounts, Check::OrderedDates]);
+    /// assert_eq!(1, violations.len());
+    /// assert_eq!(
+    ///     "line 3: account expenses:food is not declared",
+    ///     format!("{}", violations[0])
+    /// );
+    /// ```
+    pub fn check(&self, checks: &[Check]) -> Vec<CheckError> {
+        checks.iter().flat_map(|c| c.run(self)).collect()
+    }
+
+    /// Splits imported transactions into the new ones and the ones already in the journal
+    ///
+    /// Transactions are compared by [`Fingerprint`](crate::Fingerprint), using the value of
+    /// `id_tag` when a transaction has one. Each journal transaction matches at most one imported
+    /// transaction.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hledger_parse::parse_journal;
+    ///
+    /// let mut input = r#"2024-03-01 Coffee  ; id:A1
+    ///     expenses:coffee  3.50 EUR
+    ///     assets:bank
+    /// "#;
+    /// let journal = parse_journal(&mut input, None).unwrap();
+    /// let mut input = r#"2024-03-01 COFFEE BAR  ; id:A1
+    ///     expenses:coffee  3.50 EUR
+    ///     assets:bank
+    ///
+    /// 2024-03-02 Lunch  ; id:A2
+    ///     expenses:food  12 EUR
+    ///     assets:bank
+    /// "#;
+    /// let imported = parse_journal(&mut input, None).unwrap();
+    /// let (new, present) = journal.partition_new(imported.transactions(), Some("id"));
+    /// assert_eq!(1, new.len());
+    /// assert_eq!(1, present.len());
+    /// ```
+    pub fn partition_new(
+        &self,
+        transactions: Vec<Transaction>,
+        id_tag: Option<&str>,
+    ) -> (Vec<Transaction>, Vec<Transaction>) {
+        partition_new(&self.transactions, transactions, id_tag)
+    }
+}
+
+impl Journal {
+    /// Returns the transactions with their locations, in journal order
+    pub(crate) fn located_transactions(
+        &self,
+    ) -> impl Iterator<Item = (&Transaction, Option<&Location>)> {
+        self.transactions
+            .iter()
+            .enumerate()
+            .map(|(index, t)| (t, self.transaction_location(index)))
+    }
+
+    /// Returns the first location of each name which is not declared
+    pub(crate) fn undeclared_names<'a, F, I, D>(
+        &'a self,
+        names: F,
+        is_declared: D,
+    ) -> Vec<(String, Option<Location>)>
+    where
+        F: Fn(&'a Transaction) -> I,
+        I: Iterator<Item = &'a str>,
+        D: Fn(&str) -> bool,
+    {
+        let mut undeclared: Vec<(String, Option<Location>)> = vec![];
+        for (index, transaction) in self.transactions.iter().enumerate() {
+            for name in names(transaction) {
+                if !is_declared(name) && !undeclared.iter().any(|(n, _)| n == name) {
+                    undeclared.push((name.to_string(), self.transaction_location(index).cloned()));
+                }
+            }
+        }
+        undeclared
+    }
+
+    /// Returns the first location of each commodity which is not declared
+    ///
+    /// The commodities of postings come first, then the ones of price directives, which have no
+    /// location. Amounts without a commodity are ignored.
+    pub(crate) fn undeclared_commodities(&self) -> Vec<(String, Option<Location>)> {
+        let is_declared =
+            |name: &str| name.is_empty() || self.commodities.iter().any(|c| c.name == name);
+        let mut undeclared = self.undeclared_names(
+            |t| {
+                t.postings
+                    .iter()
+                    .flat_map(|p| {
+                        [
+                            &p.amount,
+                            &p.unit_price,
+                            &p.total_price,
+                            &p.balance_assertion,
+                        ]
+                    })
+                    .flatten()
+                    .map(|a| a.currency.as_str())
+            },
+            is_declared,
+        );
+        for price in &self.prices {
+            for name in [&price.commodity, &price.amount.currency] {
+                if !is_declared(name) && !undeclared.iter().any(|(n, _)| n == name) {
+                    undeclared.push((name.clone(), None));
+                }
+            }
+        }
+        undeclared
+    }
+
+    /// Checks that the commodities of all amounts are declared with `commodity` or `D` directives
+    pub fn validate_commodities(&self) -> Result<(), HLParserError> {
+        let undeclared = self.undeclared_commodities();
+        if !undeclared.is_empty() {
+            return Err(HLParserError::Validation(
+                ValidationError::UndeclaredCommodities(undeclared),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Checks that the payees of all transactions are declared with `payee` directives
+    pub fn validate_payees(&self) -> Result<(), HLParserError> {
+        let undeclared = self.undeclared_names(
+            |t| t.payee().into_iter(),
+            |name| self.declared_payees.iter().any(|p| p == name),
+        );
+        if !undeclared.is_empty() {
+            return Err(HLParserError::Validation(
+                ValidationError::UndeclaredPayees(undeclared),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Checks that the tags of all transactions are declared with `tag` directives
+    pub fn validate_tags(&self) -> Result<(), HLParserError> {
+        let undeclared = self.undeclared_names(
+            |t| t.tags.iter().map(|tag| tag.name.as_str()),
+            |name| self.declared_tags.iter().any(|t| t == name),
+        );
+        if !undeclared.is_empty() {
+            return Err(HLParserError::Validation(ValidationError::UndeclaredTags(
+                undeclared,
+            )));
+        }
+        Ok(())
+    }
 }
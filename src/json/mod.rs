@@ -0,0 +1,4 @@
+pub mod parsers;
+#[cfg(test)]
+mod tests;
+pub mod types;
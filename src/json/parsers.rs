@@ -0,0 +1,83 @@
+use crate::{journal::types::Journal, transaction::types::Transaction, HLParserError};
+
+use super::types::JsonTransaction;
+
+fn from_json<'a, T: serde::Deserialize<'a>>(input: &mut &'a str) -> Result<T, HLParserError> {
+    let value = serde_json::from_str(input)
+        .map_err(|e| HLParserError::Parse(format!("invalid hledger JSON: {}", e)))?;
+    *input = "";
+    Ok(value)
+}
+
+/// Parses the JSON transactions written by `hledger print -O json` or `hledger-web`
+///
+/// Posting dates, posting comments and balance assertions other than `=` are not supported.
+/// Transactions keep the source positions of the JSON as their locations.
+///
+/// # Example
+///
+/// ```
+/// use hledger_parse::parse_hledger_json;
+///
+/// let mut input = r#"[{
+///     "tdate": "2024-01-05",
+///     "tdescription": "Grocer | food",
+///     "tstatus": "Cleared",
+///     "tpostings": [
+///         {
+///             "paccount": "expenses:food",
+///             "pamount": [{
+///                 "acommodity": "$",
+///                 "aquantity": {"decimalMantissa": 1050, "decimalPlaces": 2, "floatingPoint": 10.5}
+///             }]
+///         },
+///         {
+///             "paccount": "assets:bank",
+///             "pamount": [{
+///                 "acommodity": "$",
+///                 "aquantity": {"decimalMantissa": -1050, "decimalPlaces": 2, "floatingPoint": -10.5}
+///             }]
+///         }
+///     ]
+/// }]"#;
+/// let journal = parse_hledger_json(&mut input).unwrap();
+/// assert_eq!(
+///     "2024-01-05 * Grocer | food\n   expenses:food  10.50 $\n   assets:bank  -10.50 $\n",
+///     format!("{}", journal.transactions()[0])
+/// );
+/// ```
+pub fn parse_hledger_json(input: &mut &str) -> Result<Journal, HLParserError> {
+    let parsed: Vec<JsonTransaction> = from_json(input)?;
+    let mut transactions = vec![];
+    let mut locations = vec![];
+    for json in &parsed {
+        let (transaction, location) = json.transaction()?;
+        transactions.push(transaction);
+        locations.extend(location);
+    }
+
+    let has_locations = locations.len() == transactions.len();
+    let mut journal = Journal::new(transactions, vec![], vec![], vec![]);
+    if has_locations {
+        journal.set_transaction_locations(locations);
+    }
+    Ok(journal)
+}
+
+/// Parses a single hledger JSON transaction object
+///
+/// # Example
+///
+/// ```
+/// use hledger_parse::{parse_hledger_json_transaction, write_hledger_json_transaction};
+///
+/// let mut input = "2024-01-05 Grocer\n    expenses:food  $10\n    assets:bank\n";
+/// let journal = hledger_parse::parse_journal(&mut input, None).unwrap();
+/// let json = write_hledger_json_transaction(&journal.transactions()[0]);
+/// let transaction = parse_hledger_json_transaction(&mut json.as_str()).unwrap();
+/// assert_eq!("assets:bank  -10 $", format!("{}", transaction.postings[1]).trim());
+/// ```
+pub fn parse_hledger_json_transaction(input: &mut &str) -> Result<Transaction, HLParserError> {
+    let parsed: JsonTransaction = from_json(input)?;
+    parsed.transaction().map(|(transaction, _)| transaction)
+}
@@ -0,0 +1,283 @@
+use std::path::PathBuf;
+
+use chrono::NaiveDate;
+use rstest::rstest;
+use rust_decimal_macros::dec;
+
+use crate::{
+    amount::types::Amount,
+    description::types::Description,
+    journal::{
+        parsers::parse_journal,
+        types::{Journal, Location},
+    },
+    posting::types::{Posting, PostingKind},
+    status::types::Status,
+    transaction::types::Transaction,
+    HLParserError,
+};
+
+use super::{
+    parsers::{parse_hledger_json, parse_hledger_json_transaction},
+    types::{write_hledger_json, write_hledger_json_transaction},
+};
+
+#[test]
+fn test_write_hledger_json_transaction() {
+    let mut input = "2024-01-05=2024-01-07 * (42) Grocer | food ; trip:, kind:x\n    expenses:food  $10.50\n    assets:bank  = $100\n";
+    let journal = parse_journal(&mut input, None).unwrap();
+    let json: serde_json::Value =
+        serde_json::from_str(&write_hledger_json_transaction(&journal.transactions()[0])).unwrap();
+
+    assert_eq!(json["tdate"], "2024-01-05");
+    assert_eq!(json["tdate2"], "2024-01-07");
+    assert_eq!(json["tstatus"], "Cleared");
+    assert_eq!(json["tcode"], "42");
+    assert_eq!(json["tdescription"], "Grocer | food");
+    assert_eq!(json["tcomment"], "trip:, kind:x\n");
+    assert_eq!(
+        json["ttags"],
+        serde_json::json!([["trip", ""], ["kind", "x"]])
+    );
+    assert_eq!(json["tindex"], 0);
+
+    let posting = &json["tpostings"][1];
+    assert_eq!(posting["paccount"], "assets:bank");
+    assert_eq!(posting["pstatus"], "Unmarked");
+    assert_eq!(posting["ptype"], "RegularPosting");
+    assert_eq!(
+        posting["pamount"][0],
+        serde_json::json!({
+            "acommodity": "$",
+            "aprice": null,
+            "aquantity": {"decimalMantissa": -1050, "decimalPlaces": 2, "floatingPoint": -10.5},
+            "astyle": {
+                "ascommodityside": "L",
+                "ascommodityspaced": false,
+                "asdecimalmark": ".",
+                "asdigitgroups": null,
+                "asprecision": 2,
+                "asrounding": "NoRounding"
+            }
+        })
+    );
+    assert_eq!(
+        posting["pbalanceassertion"]["baamount"]["aquantity"]["decimalMantissa"],
+        100
+    );
+    assert_eq!(posting["pbalanceassertion"]["batotal"], false);
+}
+
+#[rstest]
+#[case::unit_price(Some(dec!(150)), None, "UnitPrice", 150)]
+#[case::total_price(None, Some(dec!(300)), "TotalPrice", 300)]
+fn test_write_hledger_json_prices(
+    #[case] unit_price: Option<rust_decimal::Decimal>,
+    #[case] total_price: Option<rust_decimal::Decimal>,
+    #[case] tag: &str,
+    #[case] mantissa: i64,
+) {
+    let usd = |value| Amount {
+        currency: "USD".to_string(),
+        value,
+    };
+    let transaction = Transaction {
+        primary_date: NaiveDate::from_ymd_opt(2024, 1, 5).unwrap(),
+        secondary_date: None,
+        status: Status::Unmarked,
+        code: None,
+        description: Description {
+            payee: None,
+            note: Some("Buy".to_string()),
+        },
+        postings: vec![Posting {
+            status: Status::Unmarked,
+            account: "assets:stocks".into(),
+            kind: PostingKind::Regular,
+            amount: Some(Amount {
+                currency: "AAPL".to_string(),
+                value: dec!(2),
+            }),
+            unit_price: unit_price.map(usd),
+            total_price: total_price.map(usd),
+            balance_assertion: None,
+            inclusive_assertion: false,
+        }],
+        tags: vec![],
+    };
+    let json: serde_json::Value =
+        serde_json::from_str(&write_hledger_json_transaction(&transaction)).unwrap();
+    let price = &json["tpostings"][0]["pamount"][0]["aprice"];
+    assert_eq!(price["tag"], tag);
+    assert_eq!(price["contents"]["acommodity"], "USD");
+    assert_eq!(price["contents"]["aquantity"]["decimalMantissa"], mantissa);
+    assert_eq!(price["contents"]["astyle"]["ascommodityside"], "R");
+
+    let parsed = parse_hledger_json_transaction(&mut json.to_string().as_str()).unwrap();
+    assert_eq!(parsed, transaction);
+}
+
+#[test]
+fn test_write_hledger_json_key_order() {
+    let mut input = "2024-01-05 Grocer\n    expenses:food  $10\n    assets:bank\n";
+    let journal = parse_journal(&mut input, None).unwrap();
+    let json = write_hledger_json(&journal);
+    let keys: Vec<&str> = json
+        .lines()
+        .filter(|l| l.starts_with("    \""))
+        .filter_map(|l| l.trim().split('"').nth(1))
+        .collect();
+    let mut sorted = keys.clone();
+    sorted.sort();
+    assert_eq!(keys.len(), 11);
+    assert_eq!(keys, sorted);
+    assert!(json.starts_with("[\n  {\n    \"tcode\": \"\",\n"));
+}
+
+#[rstest]
+#[case::simple("2024-01-05 Grocer\n    expenses:food  $10.50\n    assets:bank  $-10.50\n")]
+#[case::full(
+    "2024-01-05=2024-01-07 ! (42) Grocer | food ; trip:, kind:x\n    * expenses:food  10.00 EUR\n    ! assets:bank  -10.00 EUR = 90.00 EUR\n"
+)]
+#[case::inclusive_assertion(
+    "2024-01-05 Opening\n    assets:bank:checking  10 EUR\n    equity:opening  -10 EUR\n\n2024-01-06 Check\n    assets:bank  0 EUR =* 10 EUR\n"
+)]
+#[case::virtual_postings(
+    "2024-01-05 Budget\n    (budget:food)  $-10\n    [assets:savings]  $10\n    [assets:bank]  $-10\n    (budget:left)  $10\n"
+)]
+#[case::total_price(
+    "2024-01-06 Buy\n    assets:stocks  2 AAPL @@ 300 USD\n    assets:bank  -300 USD\n"
+)]
+#[case::precise_amount(
+    "2024-01-06 Interest\n    assets:bank  0.0000000000000000000000000001 EUR\n    revenues:interest  -0.0000000000000000000000000001 EUR\n"
+)]
+fn test_hledger_json_round_trip(#[case] input: &str) {
+    let journal = parse_journal(&mut &input[..], None).unwrap();
+    let json = write_hledger_json(&journal);
+    let parsed = parse_hledger_json(&mut json.as_str()).unwrap();
+    assert_eq!(parsed.transactions(), journal.transactions());
+}
+
+#[test]
+fn test_hledger_json_round_trip_locations() {
+    let path =
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/includes/main.journal");
+    let journal = Journal::try_from(path).unwrap();
+    let json = write_hledger_json(&journal);
+    let parsed = parse_hledger_json(&mut json.as_str()).unwrap();
+    for i in 0..journal.transactions().len() {
+        assert_eq!(
+            parsed.transaction_location(i),
+            journal.transaction_location(i)
+        );
+    }
+}
+
+#[test]
+fn test_parse_hledger_json() {
+    // Output of an older hledger, with `aprice`, and of a newer one, with `acost`
+    let mut input = r#"[
+  {
+    "tcode": "",
+    "tcomment": "",
+    "tdate": "2024-02-01",
+    "tdate2": null,
+    "tdescription": "Exchange",
+    "tindex": 1,
+    "tpostings": [
+      {
+        "paccount": "assets:euro",
+        "pamount": [
+          {
+            "acommodity": "EUR",
+            "aprice": {"contents": {"acommodity": "$", "aquantity": {"decimalMantissa": 11, "decimalPlaces": 1, "floatingPoint": 1.1}, "astyle": null}, "tag": "UnitPrice"},
+            "aquantity": {"decimalMantissa": 100, "decimalPlaces": 0, "floatingPoint": 100},
+            "astyle": {"ascommodityside": "R", "ascommodityspaced": true, "asdecimalmark": null, "asdigitgroups": [",", [3]], "asprecision": 0, "asrounding": "HardRounding"}
+          }
+        ],
+        "pbalanceassertion": null,
+        "pcomment": "rate\n",
+        "pdate": null,
+        "pdate2": null,
+        "poriginal": null,
+        "pstatus": "Cleared",
+        "ptags": [],
+        "ptransaction_": "1",
+        "ptype": "RegularPosting"
+      },
+      {
+        "paccount": "assets:dollar",
+        "pamount": [
+          {"acommodity": "$", "acost": null, "aquantity": {"decimalMantissa": -110, "decimalPlaces": 0, "floatingPoint": -110}},
+          {"acommodity": "CAD", "acost": {"contents": {"acommodity": "$", "aquantity": {"decimalMantissa": 75, "decimalPlaces": 0}}, "tag": "TotalCost"}, "aquantity": {"decimalMantissa": 0, "decimalPlaces": 0}}
+        ],
+        "pbalanceassertion": {"baamount": {"acommodity": "CAD", "aquantity": {"decimalMantissa": 1250, "decimalPlaces": 2}}, "bainclusive": false, "baposition": {"sourceColumn": 45, "sourceLine": 4, "sourceName": "2024.journal"}, "batotal": false},
+        "pstatus": "Unmarked",
+        "ptype": "BalancedVirtualPosting"
+      },
+      {
+        "paccount": "equity:conversion",
+        "pamount": [{"acommodity": "AUTO", "aquantity": {"decimalMantissa": 0, "decimalPlaces": 0}}],
+        "ptype": "VirtualPosting"
+      }
+    ],
+    "tprecedingcomment": "",
+    "tsourcepos": [{"sourceColumn": 1, "sourceLine": 2, "sourceName": "2024.journal"}, {"sourceColumn": 1, "sourceLine": 6, "sourceName": "2024.journal"}],
+    "tstatus": "Pending",
+    "ttags": [["rate", "fixed"]]
+  }
+]"#;
+    let journal = parse_hledger_json(&mut input).unwrap();
+    assert!(input.is_empty());
+    assert_eq!(
+        journal.transactions()[0].to_string(),
+        r#"2024-02-01 ! Exchange ; rate:fixed
+  * assets:euro  100 EUR @ 1.1 $
+   [assets:dollar]  -110 $
+   [assets:dollar]  0 CAD @@ 75 $
+   (equity:conversion)
+"#
+    );
+    assert_eq!(
+        journal.transactions()[0].postings[2].balance_assertion,
+        Some(Amount {
+            currency: "CAD".to_string(),
+            value: dec!(12.50)
+        })
+    );
+    assert_eq!(
+        journal.transaction_location(0),
+        Some(&Location {
+            path: Some(PathBuf::from("2024.journal")),
+            line: 2
+        })
+    );
+}
+
+#[rstest]
+#[case::invalid_json(
+    "[{",
+    "invalid hledger JSON: EOF while parsing an object at line 1 column 2"
+)]
+#[case::missing_field(
+    r#"[{"tdate": "2024-01-01", "tpostings": []}]"#,
+    "invalid hledger JSON: missing field `tdescription` at line 1 column 41"
+)]
+#[case::posting_date(
+    r#"[{"tdate": "2024-01-01", "tdescription": "", "tpostings": [{"paccount": "a", "pamount": [], "pdate": "2024-01-03"}]}]"#,
+    "posting dates are not supported: 2024-01-03 on a"
+)]
+#[case::total_assertion(
+    r#"[{"tdate": "2024-01-01", "tdescription": "", "tpostings": [{"paccount": "a", "pamount": [], "pbalanceassertion": {"baamount": {"acommodity": "", "aquantity": {"decimalMantissa": 0, "decimalPlaces": 0}}, "batotal": true}}]}]"#,
+    "total balance assertions are not supported: a"
+)]
+#[case::quantity_out_of_range(
+    r#"[{"tdate": "2024-01-01", "tdescription": "", "tpostings": [{"paccount": "a", "pamount": [{"acommodity": "", "aquantity": {"decimalMantissa": 1, "decimalPlaces": 40}}]}]}]"#,
+    "invalid quantity 1e-40: Scale exceeds the maximum precision allowed: 40 > 28"
+)]
+fn test_parse_hledger_json_errors(#[case] input: &str, #[case] expected: &str) {
+    match parse_hledger_json(&mut &input[..]) {
+        Err(HLParserError::Parse(message)) => assert_eq!(message, expected),
+        result => panic!("unexpected result {:?}", result),
+    }
+}
@@ -0,0 +1,520 @@
+use chrono::NaiveDate;
+use rust_decimal::{prelude::ToPrimitive, Decimal};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    amount::types::Amount,
+    description::types::Description,
+    journal::types::{Journal, Location},
+    posting::types::{Posting, PostingKind},
+    status::types::Status,
+    tag::types::Tag,
+    transaction::types::Transaction,
+    HLParserError,
+};
+
+// The fields of the hledger JSON objects are declared in alphabetical order, the order in which
+// hledger writes them.
+
+/// A position in a source file, `tsourcepos` and `baposition` in hledger JSON
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct JsonSourcePos {
+    pub source_column: usize,
+    pub source_line: usize,
+    pub source_name: String,
+}
+
+impl Default for JsonSourcePos {
+    fn default() -> Self {
+        JsonSourcePos {
+            source_column: 1,
+            source_line: 1,
+            source_name: String::new(),
+        }
+    }
+}
+
+/// An exact decimal number, `aquantity` in hledger JSON
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct JsonQuantity {
+    pub decimal_mantissa: i128,
+    pub decimal_places: u32,
+    #[serde(default)]
+    pub floating_point: f64,
+}
+
+/// How hledger displays an amount, `astyle` in hledger JSON
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub(crate) struct JsonStyle {
+    pub ascommodityside: String,
+    pub ascommodityspaced: bool,
+    pub asdecimalmark: Option<String>,
+    pub asdigitgroups: Option<serde_json::Value>,
+    pub asprecision: u32,
+    pub asrounding: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) enum JsonPriceTag {
+    #[serde(alias = "UnitCost")]
+    UnitPrice,
+    #[serde(alias = "TotalCost")]
+    TotalPrice,
+}
+
+/// The unit or total price of an amount, `aprice` in hledger JSON
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct JsonPrice {
+    pub contents: Box<JsonAmount>,
+    pub tag: JsonPriceTag,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct JsonAmount {
+    pub acommodity: String,
+    #[serde(default, alias = "acost")]
+    pub aprice: Option<JsonPrice>,
+    pub aquantity: JsonQuantity,
+    #[serde(skip_deserializing)]
+    pub astyle: Option<JsonStyle>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct JsonBalanceAssertion {
+    pub baamount: JsonAmount,
+    #[serde(default)]
+    pub bainclusive: bool,
+    #[serde(default)]
+    pub baposition: JsonSourcePos,
+    #[serde(default)]
+    pub batotal: bool,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub(crate) enum JsonStatus {
+    #[default]
+    Unmarked,
+    Pending,
+    Cleared,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub(crate) enum JsonPostingType {
+    #[default]
+    #[serde(rename = "RegularPosting")]
+    Regular,
+    #[serde(rename = "VirtualPosting")]
+    Virtual,
+    #[serde(rename = "BalancedVirtualPosting")]
+    BalancedVirtual,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct JsonPosting {
+    pub paccount: String,
+    pub pamount: Vec<JsonAmount>,
+    #[serde(default)]
+    pub pbalanceassertion: Option<JsonBalanceAssertion>,
+    #[serde(default)]
+    pub pcomment: String,
+    #[serde(default)]
+    pub pdate: Option<NaiveDate>,
+    #[serde(default)]
+    pub pdate2: Option<NaiveDate>,
+    #[serde(default)]
+    pub poriginal: serde_json::Value,
+    #[serde(default)]
+    pub pstatus: JsonStatus,
+    #[serde(default)]
+    pub ptags: Vec<(String, String)>,
+    #[serde(default)]
+    pub ptransaction_: Option<String>,
+    #[serde(default)]
+    pub ptype: JsonPostingType,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct JsonTransaction {
+    #[serde(default)]
+    pub tcode: String,
+    #[serde(default)]
+    pub tcomment: String,
+    pub tdate: NaiveDate,
+    #[serde(default)]
+    pub tdate2: Option<NaiveDate>,
+    pub tdescription: String,
+    #[serde(default)]
+    pub tindex: usize,
+    pub tpostings: Vec<JsonPosting>,
+    #[serde(default)]
+    pub tprecedingcomment: String,
+    #[serde(default)]
+    pub tsourcepos: Vec<JsonSourcePos>,
+    #[serde(default)]
+    pub tstatus: JsonStatus,
+    #[serde(default)]
+    pub ttags: Vec<(String, String)>,
+}
+
+impl From<&Status> for JsonStatus {
+    fn from(value: &Status) -> Self {
+        match value {
+            Status::Unmarked => JsonStatus::Unmarked,
+            Status::Pending => JsonStatus::Pending,
+            Status::Cleared => JsonStatus::Cleared,
+        }
+    }
+}
+
+impl From<JsonStatus> for Status {
+    fn from(value: JsonStatus) -> Self {
+        match value {
+            JsonStatus::Unmarked => Status::Unmarked,
+            JsonStatus::Pending => Status::Pending,
+            JsonStatus::Cleared => Status::Cleared,
+        }
+    }
+}
+
+impl From<PostingKind> for JsonPostingType {
+    fn from(value: PostingKind) -> Self {
+        match value {
+            PostingKind::Regular => JsonPostingType::Regular,
+            PostingKind::Virtual => JsonPostingType::Virtual,
+            PostingKind::BalancedVirtual => JsonPostingType::BalancedVirtual,
+        }
+    }
+}
+
+impl From<JsonPostingType> for PostingKind {
+    fn from(value: JsonPostingType) -> Self {
+        match value {
+            JsonPostingType::Regular => PostingKind::Regular,
+            JsonPostingType::Virtual => PostingKind::Virtual,
+            JsonPostingType::BalancedVirtual => PostingKind::BalancedVirtual,
+        }
+    }
+}
+
+impl JsonStyle {
+    /// Returns the style of an amount
+    ///
+    /// The journal keeps no display style, so symbols like `$` are put before the quantity and
+    /// other commodities after it, separated by a space.
+    fn of(amount: &Amount) -> JsonStyle {
+        let is_symbol = amount.currency.chars().all(|c| !c.is_alphanumeric());
+        JsonStyle {
+            ascommodityside: if is_symbol { "L" } else { "R" }.to_string(),
+            ascommodityspaced: !is_symbol,
+            asdecimalmark: Some(".".to_string()),
+            asdigitgroups: None,
+            asprecision: amount.precision(),
+            asrounding: "NoRounding".to_string(),
+        }
+    }
+}
+
+impl JsonAmount {
+    fn new(amount: &Amount, aprice: Option<JsonPrice>) -> JsonAmount {
+        JsonAmount {
+            acommodity: amount.currency.clone(),
+            aprice,
+            aquantity: JsonQuantity {
+                decimal_mantissa: amount.value.mantissa(),
+                decimal_places: amount.value.scale(),
+                floating_point: amount.value.to_f64().unwrap_or_default(),
+            },
+            astyle: Some(JsonStyle::of(amount)),
+        }
+    }
+
+    /// Returns the amount, `None` for the `AUTO` commodity which hledger uses for missing amounts
+    pub(crate) fn amount(&self) -> Result<Option<Amount>, HLParserError> {
+        if self.acommodity == "AUTO" {
+            return Ok(None);
+        }
+        let value = Decimal::try_from_i128_with_scale(
+            self.aquantity.decimal_mantissa,
+            self.aquantity.decimal_places,
+        )
+        .map_err(|e| {
+            HLParserError::Parse(format!(
+                "invalid quantity {}e-{}: {}",
+                self.aquantity.decimal_mantissa, self.aquantity.decimal_places, e
+            ))
+        })?;
+        Ok(Some(Amount {
+            currency: self.acommodity.clone(),
+            value,
+        }))
+    }
+}
+
+impl JsonSourcePos {
+    fn new(location: &Location, line: usize) -> JsonSourcePos {
+        JsonSourcePos {
+            source_column: 1,
+            source_line: line,
+            source_name: location
+                .path
+                .as_ref()
+                .map(|p| p.display().to_string())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+impl JsonPosting {
+    fn new(posting: &Posting, amounts: Vec<Amount>, index: usize) -> JsonPosting {
+        let aprice = match (&posting.unit_price, &posting.total_price) {
+            (Some(price), _) => Some(JsonPrice {
+                contents: Box::new(JsonAmount::new(price, None)),
+                tag: JsonPriceTag::UnitPrice,
+            }),
+            (None, Some(price)) => Some(JsonPrice {
+                contents: Box::new(JsonAmount::new(price, None)),
+                tag: JsonPriceTag::TotalPrice,
+            }),
+            (None, None) => None,
+        };
+        JsonPosting {
+            paccount: posting.account.to_string(),
+            pamount: amounts
+                .iter()
+                .map(|a| JsonAmount::new(a, aprice.clone()))
+                .collect(),
+            pbalanceassertion: posting
+                .balance_assertion
+                .as_ref()
+                .map(|a| JsonBalanceAssertion {
+                    baamount: JsonAmount::new(a, None),
+                    bainclusive: posting.inclusive_assertion,
+                    baposition: JsonSourcePos::default(),
+                    batotal: false,
+                }),
+            pcomment: String::new(),
+            pdate: None,
+            pdate2: None,
+            poriginal: serde_json::Value::Null,
+            pstatus: (&posting.status).into(),
+            ptags: vec![],
+            ptransaction_: Some(index.to_string()),
+            ptype: posting.kind.into(),
+        }
+    }
+
+    /// Returns the postings of the posting, one per amount
+    fn postings(&self) -> Result<Vec<Posting>, HLParserError> {
+        if let Some(date) = self.pdate.or(self.pdate2) {
+            return Err(HLParserError::Parse(format!(
+                "posting dates are not supported: {} on {}",
+                date, self.paccount
+            )));
+        }
+        let balance_assertion = match &self.pbalanceassertion {
+            Some(assertion) if assertion.batotal => {
+                return Err(HLParserError::Parse(format!(
+                    "total balance assertions are not supported: {}",
+                    self.paccount
+                )))
+            }
+            Some(assertion) => assertion.baamount.amount()?,
+            None => None,
+        };
+
+        // An empty amount is zero in hledger
+        let zero = [JsonAmount::new(
+            &Amount {
+                currency: String::new(),
+                value: Decimal::ZERO,
+            },
+            None,
+        )];
+        let amounts = match self.pamount.is_empty() {
+            true => &zero[..],
+            false => &self.pamount[..],
+        };
+        let mut postings = vec![];
+        for amount in amounts {
+            let (unit_price, total_price) = match &amount.aprice {
+                Some(JsonPrice {
+                    contents,
+                    tag: JsonPriceTag::UnitPrice,
+                }) => (contents.amount()?, None),
+                Some(JsonPrice {
+                    contents,
+                    tag: JsonPriceTag::TotalPrice,
+                }) => (None, contents.amount()?),
+                None => (None, None),
+            };
+            postings.push(Posting {
+                status: self.pstatus.into(),
+                account: self.paccount.as_str().into(),
+                kind: self.ptype.into(),
+                amount: amount.amount()?,
+                unit_price,
+                total_price,
+                balance_assertion: None,
+                inclusive_assertion: false,
+            });
+        }
+        // The assertion applies to the balance after all the amounts
+        if let Some(last) = postings.last_mut() {
+            last.balance_assertion = balance_assertion;
+            last.inclusive_assertion = self
+                .pbalanceassertion
+                .as_ref()
+                .is_some_and(|assertion| assertion.bainclusive);
+        }
+        Ok(postings)
+    }
+}
+
+impl JsonTransaction {
+    /// Converts a transaction, with missing posting amounts inferred as hledger does
+    pub(crate) fn new(
+        transaction: &Transaction,
+        index: usize,
+        location: Option<&Location>,
+    ) -> JsonTransaction {
+        let tcomment = match transaction.tags.is_empty() {
+            true => String::new(),
+            false => format!(
+                "{}\n",
+                transaction
+                    .tags
+                    .iter()
+                    .map(Tag::to_string)
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+        };
+        // The end position is the line after the transaction, assuming a line per posting
+        let tsourcepos = match location {
+            Some(location) => vec![
+                JsonSourcePos::new(location, location.line),
+                JsonSourcePos::new(location, location.line + transaction.postings.len() + 1),
+            ],
+            None => vec![JsonSourcePos::default(), JsonSourcePos::default()],
+        };
+        JsonTransaction {
+            tcode: transaction.code.clone().unwrap_or_default(),
+            tcomment,
+            tdate: transaction.primary_date,
+            tdate2: transaction.secondary_date,
+            tdescription: transaction.description.to_string(),
+            tindex: index,
+            tpostings: transaction
+                .postings
+                .iter()
+                .zip(transaction.posting_amounts())
+                .map(|(posting, inferred)| {
+                    let amounts = match &posting.amount {
+                        Some(amount) => vec![amount.clone()],
+                        None => inferred.amounts(),
+                    };
+                    JsonPosting::new(posting, amounts, index)
+                })
+                .collect(),
+            tprecedingcomment: String::new(),
+            tsourcepos,
+            tstatus: (&transaction.status).into(),
+            ttags: transaction
+                .tags
+                .iter()
+                .map(|t| (t.name.clone(), t.value.clone().unwrap_or_default()))
+                .collect(),
+        }
+    }
+
+    /// Returns the transaction, and its location if the JSON has a source position
+    pub(crate) fn transaction(&self) -> Result<(Transaction, Option<Location>), HLParserError> {
+        let mut postings = vec![];
+        for posting in &self.tpostings {
+            postings.extend(posting.postings()?);
+        }
+        let (payee, note) = match self.tdescription.split_once('|') {
+            Some((payee, note)) => (non_empty(payee), non_empty(note)),
+            None => (None, non_empty(&self.tdescription)),
+        };
+        let transaction = Transaction {
+            primary_date: self.tdate,
+            secondary_date: self.tdate2,
+            status: self.tstatus.into(),
+            code: non_empty(&self.tcode),
+            description: Description { payee, note },
+            postings,
+            tags: self
+                .ttags
+                .iter()
+                .map(|(name, value)| Tag {
+                    name: name.clone(),
+                    value: non_empty(value),
+                })
+                .collect(),
+        };
+        let location = self.tsourcepos.first().map(|pos| Location {
+            path: non_empty(&pos.source_name).map(Into::into),
+            line: pos.source_line,
+        });
+        Ok((transaction, location))
+    }
+}
+
+fn non_empty(value: &str) -> Option<String> {
+    match value.trim() {
+        "" => None,
+        value => Some(value.to_string()),
+    }
+}
+
+/// Writes the transactions of a journal in the JSON format of `hledger print -O json`
+///
+/// Missing posting amounts are inferred, like hledger does. Amounts keep their exact quantity in
+/// `decimalMantissa` and `decimalPlaces`.
+///
+/// # Example
+///
+/// ```
+/// use hledger_parse::{parse_journal, write_hledger_json};
+///
+/// let mut input = "2024-01-05 * Grocer | food\n    expenses:food  $10.50\n    assets:bank\n";
+/// let journal = parse_journal(&mut input, None).unwrap();
+/// let json = write_hledger_json(&journal);
+/// assert!(json.contains(r#""tdescription": "Grocer | food""#));
+/// assert!(json.contains(r#""decimalMantissa": -1050"#));
+/// ```
+pub fn write_hledger_json(journal: &Journal) -> String {
+    let transactions: Vec<JsonTransaction> = journal
+        .transactions()
+        .iter()
+        .enumerate()
+        .map(|(i, t)| JsonTransaction::new(t, i + 1, journal.transaction_location(i)))
+        .collect();
+    to_json(&transactions)
+}
+
+/// Writes a transaction as a hledger JSON transaction object, as accepted by `hledger-web`
+///
+/// # Example
+///
+/// ```
+/// use hledger_parse::{parse_journal, write_hledger_json_transaction};
+///
+/// let mut input = "2024-01-05 Grocer\n    expenses:food  $10\n    assets:bank\n";
+/// let journal = parse_journal(&mut input, None).unwrap();
+/// let json = write_hledger_json_transaction(&journal.transactions()[0]);
+/// assert!(json.contains(r#""tdate": "2024-01-05""#));
+/// ```
+pub fn write_hledger_json_transaction(transaction: &Transaction) -> String {
+    to_json(&JsonTransaction::new(transaction, 0, None))
+}
+
+fn to_json<T: Serialize>(value: &T) -> String {
+    let mut json =
+        serde_json::to_string_pretty(value).expect("hledger JSON values are always serializable");
+    json.push('\n');
+    json
+}
@@ -0,0 +1,610 @@
+use regex::RegexBuilder;
+use winnow::{
+    ascii::{line_ending, space0, space1, till_line_ending},
+    combinator::{alt, delimited, eof, opt, peek, preceded, repeat, terminated},
+    token::{one_of, take_till, take_until},
+    PResult, Parser,
+};
+
+use crate::{
+    amount::{parsers::parse_amount_with_state, types::MixedAmount},
+    code::parsers::parse_code,
+    date::parsers::parse_date_with_year,
+    description::types::Description,
+    journal::types::ParseState,
+    posting::types::{Posting, PostingKind},
+    status::types::Status,
+    tag::types::Tag,
+    transaction::types::Transaction,
+    Amount, HLParserError, ValidationError,
+};
+
+use super::types::{AutomatedTransaction, PredicateField, PredicateTerm};
+
+/// A transaction, an automated transaction or a skipped line of a ledger-cli journal
+pub(crate) enum LedgerEntry {
+    Transaction(Transaction),
+    AutomatedTransaction(AutomatedTransaction),
+    SubDirective,
+}
+
+/// Indented sub-directives of ledger-cli `account`, `commodity`, `payee` and `tag` blocks, which
+/// are skipped
+const SUB_DIRECTIVES: [&str; 6] = ["note", "alias", "payee", "check", "assert", "default"];
+
+/// Directives and entries of ledger-cli which have no equivalent, by line prefix
+const UNSUPPORTED: [(&str, &str); 19] = [
+    ("~", "periodic transactions"),
+    ("define ", "define directives"),
+    ("assert ", "assert directives"),
+    ("check ", "check directives"),
+    ("expr ", "expr directives"),
+    ("eval ", "eval directives"),
+    ("value ", "value directives"),
+    ("apply tag ", "apply tag directives"),
+    ("apply fixed ", "apply fixed directives"),
+    ("fixed ", "fixed directives"),
+    ("bucket ", "bucket directives"),
+    ("A ", "A directives"),
+    ("C ", "C directives"),
+    ("N ", "N directives"),
+    ("i ", "timeclock entries"),
+    ("I ", "timeclock entries"),
+    ("python", "python blocks"),
+    ("import ", "import directives"),
+    ("--", "command line options"),
+];
+
+/// Skips the `!` or `@` that ledger-cli allows before directives, as in `!include`
+pub(crate) fn skip_directive_prefix(input: &mut &str) {
+    let _: PResult<char> = terminated(
+        one_of(['!', '@']),
+        peek(one_of(|c: char| c.is_ascii_alphabetic())),
+    )
+    .parse_next(input);
+}
+
+/// Parses a ledger-cli transaction or automated transaction starting at `line`, or skips an
+/// indented sub-directive
+///
+/// Returns `None` without consuming the input if the input starts with something else, which is
+/// parsed like in hledger journals.
+pub(crate) fn parse_ledger_entry(
+    input: &mut &str,
+    state: &ParseState,
+    line: usize,
+) -> Result<Option<LedgerEntry>, HLParserError> {
+    let sub_directive: PResult<_> = opt((
+        space1,
+        alt(SUB_DIRECTIVES),
+        alt((space1, peek(line_ending), eof)),
+        till_line_ending,
+        alt((line_ending, eof)),
+    ))
+    .parse_next(input);
+    if let Ok(Some(_)) = sub_directive {
+        return Ok(Some(LedgerEntry::SubDirective));
+    }
+    if let Some((_, construct)) = UNSUPPORTED.iter().find(|(p, _)| input.starts_with(p)) {
+        return Err(HLParserError::Parse(format!(
+            "line {}: ledger-cli {} are not supported",
+            line, construct
+        )));
+    }
+    if input.starts_with('=') {
+        return parse_automated_transaction(input, state, line)
+            .map(|a| Some(LedgerEntry::AutomatedTransaction(a)));
+    }
+    if input.starts_with(|c: char| c.is_ascii_digit()) {
+        return parse_ledger_transaction(input, state, line)
+            .map(|t| Some(LedgerEntry::Transaction(t)));
+    }
+    Ok(None)
+}
+
+fn error(line: usize, message: impl std::fmt::Display) -> HLParserError {
+    HLParserError::Parse(format!("line {}: {}", line, message))
+}
+
+/// Parses a transaction header, `DATE[=DATE] [*|!] [(CODE)] DESCRIPTION [; COMMENT]`, into a
+/// transaction without postings and the comment
+fn parse_header<'s>(
+    state: &ParseState,
+) -> impl FnMut(&mut &'s str) -> PResult<(Transaction, Option<&'s str>)> + '_ {
+    move |input: &mut &'s str| {
+        let ((primary_date, secondary_date), status, code, description, comment) = terminated(
+            (
+                terminated(parse_date_with_year(state.default_year), space0),
+                parse_status,
+                opt(terminated(parse_code, space0)),
+                take_till(0.., [';', '\n', '\r']).map(str::trim),
+                opt(preceded(';', till_line_ending)),
+            ),
+            alt((line_ending, eof)),
+        )
+        .parse_next(input)?;
+        let transaction = Transaction {
+            primary_date,
+            secondary_date,
+            status,
+            code: code.map(str::to_string),
+            description: Description {
+                payee: None,
+                note: Some(description.to_string()).filter(|d| !d.is_empty()),
+            },
+            postings: vec![],
+            tags: vec![],
+        };
+        Ok((transaction, comment))
+    }
+}
+
+fn parse_status(input: &mut &str) -> PResult<Status> {
+    opt(terminated(
+        alt(('*'.value(Status::Cleared), '!'.value(Status::Pending))),
+        space0,
+    ))
+    .map(|status| status.unwrap_or(Status::Unmarked))
+    .parse_next(input)
+}
+
+/// Returns the next indented line of an entry with its line ending, or `None` at the end of it
+fn next_entry_line<'s>(input: &mut &'s str) -> Option<&'s str> {
+    if !input.starts_with([' ', '\t'])
+        || input
+            .trim_start_matches([' ', '\t'])
+            .starts_with(['\n', '\r'])
+    {
+        return None;
+    }
+    let (line, rest) = input.split_once('\n').unwrap_or((input, ""));
+    *input = rest;
+    Some(line.trim_end_matches('\r'))
+}
+
+/// Splits a posting line into its text and its comment, which starts at the first `;` outside
+/// quotes
+fn split_comment(text: &str) -> (&str, Option<&str>) {
+    let mut quoted = false;
+    for (index, c) in text.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            ';' if !quoted => return (&text[..index], Some(&text[index + 1..])),
+            _ => {}
+        }
+    }
+    (text, None)
+}
+
+/// Parses a lot annotation, `{PRICE}`, `{{TOTAL}}`, `[DATE]`, `(NOTE)` or `((EXPRESSION))`
+///
+/// Only lot prices are kept, with a flag which is true for a total price.
+fn parse_lot_annotation<'s>(
+    state: &ParseState,
+) -> impl FnMut(&mut &'s str) -> PResult<Option<(Amount, bool)>> + '_ {
+    move |input: &mut &'s str| {
+        let amount = |input: &mut &'s str| {
+            preceded((space0, opt('='), space0), parse_amount_with_state(state)).parse_next(input)
+        };
+        alt((
+            delimited("{{", terminated(amount, space0), "}}").map(|a| Some((a, true))),
+            delimited('{', terminated(amount, space0), '}').map(|a| Some((a, false))),
+            delimited("((", take_until(0.., "))"), "))").value(None),
+            delimited('[', take_till(0.., ']'), ']').value(None),
+            delimited('(', take_till(0.., ')'), ')').value(None),
+        ))
+        .parse_next(input)
+    }
+}
+
+/// Parses the amounts of a posting, `[AMOUNT] [LOT] [@ PRICE | @@ TOTAL] [= ASSERTION]`
+fn parse_posting_amounts<'s>(
+    state: &ParseState,
+) -> impl FnMut(&mut &'s str) -> PResult<Posting> + '_ {
+    move |input: &mut &'s str| {
+        let amount = opt(parse_amount_with_state(state)).parse_next(input)?;
+        let lots: Vec<Option<(Amount, bool)>> =
+            repeat(0.., preceded(space0, parse_lot_annotation(state))).parse_next(input)?;
+        let price = opt(preceded(
+            space0,
+            (
+                alt(("@@".value(true), "@".value(false))),
+                preceded(space0, parse_amount_with_state(state)),
+            ),
+        ))
+        .parse_next(input)?;
+        let balance_assertion = opt(preceded(
+            (space0, '=', space0),
+            parse_amount_with_state(state),
+        ))
+        .parse_next(input)?;
+        (space0, eof).parse_next(input)?;
+
+        let lot = lots.into_iter().flatten().next();
+        let (unit_price, total_price) = match price.map(|(total, amount)| (amount, total)).or(lot) {
+            Some((amount, true)) => (None, Some(amount)),
+            Some((amount, false)) => (Some(amount), None),
+            None => (None, None),
+        };
+        Ok(Posting {
+            status: Status::Unmarked,
+            account: "".into(),
+            kind: PostingKind::Regular,
+            amount,
+            unit_price,
+            total_price,
+            balance_assertion,
+            inclusive_assertion: false,
+        })
+    }
+}
+
+/// Parses a posting line into a posting and its comment
+///
+/// The account ends at two spaces, a tab or a comment, which may follow a single space.
+fn parse_posting<'s>(
+    text: &'s str,
+    state: &ParseState,
+    line: usize,
+) -> Result<(Posting, Option<&'s str>), HLParserError> {
+    let (text, comment) = split_comment(text.trim());
+    let mut text = text.trim_end();
+    let status = parse_status(&mut text).map_err(|e| error(line, e))?;
+    let end = [text.find("  "), text.find('\t')]
+        .into_iter()
+        .flatten()
+        .min()
+        .unwrap_or(text.len());
+    let (account, mut amounts) = (text[..end].trim(), text[end..].trim());
+    let (kind, account) = PostingKind::split(account);
+    if account.is_empty() {
+        return Err(error(line, "posting without an account"));
+    }
+    if amounts.starts_with('(') {
+        return Err(error(
+            line,
+            format!(
+                "ledger-cli amount expressions are not supported: {}",
+                amounts
+            ),
+        ));
+    }
+    let posting = parse_posting_amounts(state)
+        .parse_next(&mut amounts)
+        .map_err(|_| {
+            error(
+                line,
+                format!("cannot parse the amount of posting: {}", text),
+            )
+        })?;
+    Ok((
+        Posting {
+            status,
+            account: account.into(),
+            kind,
+            ..posting
+        },
+        comment,
+    ))
+}
+
+/// Removes the `[DATE]`, `[=DATE]` and `[DATE=DATE]` dates of a comment, returning them with the
+/// rest of the comment
+fn take_comment_dates(comment: &str) -> (Vec<&str>, String) {
+    let mut dates = vec![];
+    let mut rest = String::new();
+    let mut remaining = comment;
+    while let Some(start) = remaining.find('[') {
+        let inner = &remaining[start + 1..];
+        match inner.find(']') {
+            Some(end) if inner.starts_with(|c: char| c.is_ascii_digit() || c == '=') => {
+                dates.push(&inner[..end]);
+                rest.push_str(&remaining[..start]);
+                remaining = &inner[end + 1..];
+            }
+            _ => {
+                rest.push_str(&remaining[..start + 1]);
+                remaining = inner;
+            }
+        }
+    }
+    rest.push_str(remaining);
+    (dates, rest)
+}
+
+/// Returns the tags of a comment, `:tag1:tag2:` tags and a `key: value` pair
+fn comment_tags(comment: &str) -> Vec<Tag> {
+    let comment = comment.trim();
+    let mut tags = vec![];
+    for word in comment.split_whitespace() {
+        if word.len() > 2 && word.starts_with(':') && word.ends_with(':') {
+            tags.extend(word.split(':').filter(|t| !t.is_empty()).map(|name| Tag {
+                name: name.to_string(),
+                value: None,
+            }));
+        }
+    }
+    if tags.is_empty() {
+        if let Some((key, value)) = comment.split_once(':') {
+            let value = value.strip_prefix(':').unwrap_or(value);
+            if !key.is_empty()
+                && !key.contains(char::is_whitespace)
+                && (value.is_empty() || value.starts_with(char::is_whitespace))
+            {
+                tags.push(Tag {
+                    name: key.to_string(),
+                    value: Some(value.trim().to_string()).filter(|v| !v.is_empty()),
+                });
+            }
+        }
+    }
+    tags
+}
+
+/// Adds the tags and the `[=DATE]` effective date of a transaction comment to a transaction
+fn apply_transaction_comment(
+    transaction: &mut Transaction,
+    comment: &str,
+    state: &ParseState,
+    line: usize,
+) -> Result<(), HLParserError> {
+    let (dates, comment) = take_comment_dates(comment);
+    for date in dates {
+        let mut effective = date.strip_prefix('=').ok_or_else(|| {
+            error(
+                line,
+                format!(
+                    "ledger-cli actual dates in comments are not supported: [{}]",
+                    date
+                ),
+            )
+        })?;
+        let (effective, _) = parse_date_with_year(state.default_year)
+            .parse_next(&mut effective)
+            .map_err(|e| error(line, e))?;
+        transaction.secondary_date = Some(effective);
+    }
+    transaction.tags.extend(comment_tags(&comment));
+    Ok(())
+}
+
+/// Checks that a posting comment has no date, since postings have no dates of their own
+fn check_posting_comment(comment: &str, line: usize) -> Result<(), HLParserError> {
+    match take_comment_dates(comment).0.first() {
+        Some(date) => Err(error(
+            line,
+            format!("ledger-cli posting dates are not supported: [{}]", date),
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Checks that a transaction balances, like ledger-cli
+///
+/// Virtual postings don't need to balance, and balanced virtual postings balance on their own. A
+/// transaction with two commodities and no prices balances with an implied conversion.
+fn check_balanced(transaction: &Transaction) -> Result<(), ValidationError> {
+    for kind in PostingKind::BALANCED {
+        let postings: Vec<&Posting> = transaction
+            .postings
+            .iter()
+            .filter(|p| p.kind == kind)
+            .collect();
+        match postings.iter().filter(|p| p.amount.is_none()).count() {
+            0 => {}
+            1 => continue,
+            _ => {
+                return Err(ValidationError::TransactionWithMissingAmountPostings(
+                    transaction.clone(),
+                ))
+            }
+        }
+        let mut residual = MixedAmount::default();
+        for cost in postings.iter().filter_map(|p| p.cost()) {
+            residual.add_amount(&cost);
+        }
+        let amounts = residual.amounts();
+        let converted = amounts.len() == 2 && amounts[0].is_negative() != amounts[1].is_negative();
+        if !residual.is_zero() && !converted {
+            return Err(ValidationError::NonZeroSumTransactionPostings(
+                transaction.clone(),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Parses a transaction, its header followed by its indented postings and comments
+fn parse_ledger_transaction(
+    input: &mut &str,
+    state: &ParseState,
+    line: usize,
+) -> Result<Transaction, HLParserError> {
+    let (mut transaction, comment) = parse_header(state)
+        .parse_next(input)
+        .map_err(|e| error(line, e))?;
+    if let Some(comment) = comment {
+        apply_transaction_comment(&mut transaction, comment, state, line)?;
+    }
+
+    let mut current = line;
+    while let Some(text) = next_entry_line(input) {
+        current += 1;
+        match text.trim_start().strip_prefix(';') {
+            Some(comment) if transaction.postings.is_empty() => {
+                apply_transaction_comment(&mut transaction, comment, state, current)?
+            }
+            Some(comment) => check_posting_comment(comment, current)?,
+            None => {
+                let (posting, comment) = parse_posting(text, state, current)?;
+                if let Some(comment) = comment {
+                    check_posting_comment(comment, current)?;
+                }
+                transaction.postings.push(posting);
+            }
+        }
+    }
+
+    check_balanced(&transaction).map_err(|e| error(line, e))?;
+    Ok(transaction)
+}
+
+/// Splits an automated transaction predicate into tokens: words, operators and `/regex/`
+fn predicate_tokens(predicate: &str) -> Result<Vec<String>, String> {
+    let mut tokens = vec![];
+    let mut chars = predicate.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {}
+            '/' => {
+                let mut regex = String::from("/");
+                loop {
+                    match chars.next() {
+                        Some('\\') if chars.peek() == Some(&'/') => {
+                            regex.push('/');
+                            chars.next();
+                        }
+                        Some('/') => break,
+                        Some(c) => regex.push(c),
+                        None => return Err(format!("unterminated regular expression {}", regex)),
+                    }
+                }
+                tokens.push(regex);
+            }
+            '&' | '|' | '!' | '@' => {
+                if chars.peek() == Some(&c) {
+                    chars.next();
+                }
+                tokens.push(c.to_string());
+            }
+            '=' if chars.peek() == Some(&'~') => {
+                chars.next();
+                tokens.push("=~".to_string());
+            }
+            '(' | ')' | '<' | '>' | '=' | '+' | '*' => {
+                return Err(format!("unsupported operator {}", c));
+            }
+            c => {
+                let mut word = c.to_string();
+                while let Some(c) =
+                    chars.next_if(|c| !c.is_whitespace() && !"/&|()=<>".contains(*c))
+                {
+                    word.push(c);
+                }
+                tokens.push(word);
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+/// Parses the predicate of an automated transaction into groups of terms
+///
+/// A predicate is either a ledger-cli query, whose terms are account patterns, `@payee` or
+/// `payee PATTERN`, or an `expr` with `account =~ /regex/` and `payee =~ /regex/` comparisons.
+/// Terms are negated with `not` or `!` and combined with `and`, `&`, `or` and `|`; adjacent
+/// terms are combined with `or`.
+pub(super) fn parse_predicate(predicate: &str) -> Result<Vec<Vec<PredicateTerm>>, String> {
+    let (expression, text) = match predicate.trim().strip_prefix("expr ") {
+        Some(text) => (true, text.trim().trim_matches(['\'', '"'])),
+        None => (false, predicate.trim()),
+    };
+    let tokens = predicate_tokens(text)?;
+    let mut groups: Vec<Vec<PredicateTerm>> = vec![];
+    let mut tokens = tokens.iter().map(String::as_str).peekable();
+    let mut operator = None;
+    let mut negated = false;
+    while let Some(token) = tokens.next() {
+        let (field, pattern) = match token {
+            "and" | "&" | "or" | "|" => {
+                operator = Some(matches!(token, "and" | "&"));
+                continue;
+            }
+            "not" | "!" => {
+                negated = !negated;
+                continue;
+            }
+            "account" | "payee" if tokens.peek() == Some(&"=~") => {
+                tokens.next();
+                let field = match token {
+                    "account" => PredicateField::Account,
+                    _ => PredicateField::Payee,
+                };
+                match tokens.next() {
+                    Some(regex) if regex.starts_with('/') => (field, &regex[1..]),
+                    _ => return Err(format!("expected a regular expression after {} =~", token)),
+                }
+            }
+            "payee" | "@" if !expression => match tokens.next() {
+                Some(pattern) => (
+                    PredicateField::Payee,
+                    pattern.strip_prefix('/').unwrap_or(pattern),
+                ),
+                None => return Err(format!("expected a pattern after {}", token)),
+            },
+            regex if regex.starts_with('/') => (PredicateField::Account, &regex[1..]),
+            pattern if !expression => (PredicateField::Account, pattern),
+            other => return Err(format!("unsupported expression {}", other)),
+        };
+        let pattern = RegexBuilder::new(pattern)
+            .case_insensitive(true)
+            .build()
+            .map_err(|e| e.to_string())?;
+        let term = PredicateTerm {
+            field,
+            pattern,
+            negated,
+        };
+        match (groups.last_mut(), operator.take()) {
+            (Some(group), Some(true)) => group.push(term),
+            (Some(_), None) if expression => {
+                return Err(format!("expected and or or before {}", token))
+            }
+            _ => groups.push(vec![term]),
+        }
+        negated = false;
+    }
+    match groups.is_empty() || operator.is_some() || negated {
+        true => Err(format!("invalid predicate {}", predicate.trim())),
+        false => Ok(groups),
+    }
+}
+
+/// Parses an automated transaction, `= PREDICATE` followed by indented postings
+fn parse_automated_transaction(
+    input: &mut &str,
+    state: &ParseState,
+    line: usize,
+) -> Result<AutomatedTransaction, HLParserError> {
+    let predicate: PResult<&str> =
+        terminated(preceded('=', till_line_ending), alt((line_ending, eof))).parse_next(input);
+    let predicate = predicate.map_err(|e| error(line, e))?;
+    let (predicate, _) = split_comment(predicate);
+    let predicate = parse_predicate(predicate).map_err(|e| {
+        error(
+            line,
+            format!("unsupported automated transaction predicate: {}", e),
+        )
+    })?;
+
+    let mut postings = vec![];
+    let mut current = line;
+    while let Some(text) = next_entry_line(input) {
+        current += 1;
+        if text.trim_start().starts_with(';') {
+            continue;
+        }
+        let (posting, _) = parse_posting(text, state, current)?;
+        if posting.amount.is_none() {
+            return Err(error(
+                current,
+                "automated transaction postings need an amount",
+            ));
+        }
+        postings.push(posting);
+    }
+    Ok(AutomatedTransaction {
+        predicate,
+        postings,
+    })
+}
@@ -0,0 +1,384 @@
+use std::path::PathBuf;
+
+use chrono::NaiveDate;
+use rstest::rstest;
+
+use crate::{
+    check::types::Check,
+    journal::{
+        parsers::{parse_journal, parse_journal_with_options},
+        types::{Journal, ParseOptions, Syntax},
+    },
+    posting::types::PostingKind,
+    tag::types::Tag,
+    HLParserError,
+};
+
+use super::parsers::parse_predicate;
+
+fn parse_ledger(mut input: &str) -> Result<Journal, HLParserError> {
+    parse_journal_with_options(
+        &mut input,
+        None,
+        &ParseOptions {
+            syntax: Syntax::Ledger,
+        },
+    )
+}
+
+fn transactions(input: &str) -> String {
+    parse_ledger(input)
+        .unwrap()
+        .transactions()
+        .iter()
+        .map(|t| t.to_string())
+        .collect()
+}
+
+#[rstest]
+#[case::single_space_comment("2024/01/05 Shop ; note", "2024-01-05 Shop\n")]
+#[case::code_without_space("2024/01/05 ! (42)Shop", "2024-01-05 ! Shop\n")]
+#[case::status_without_space("2024/01/05 *Shop", "2024-01-05 * Shop\n")]
+#[case::pipe_in_payee("2024/01/05 Shop | Market", "2024-01-05 Shop | Market\n")]
+#[case::no_description("2024/01/05", "2024-01-05 \n")]
+fn test_parse_ledger_headers(#[case] header: &str, #[case] expected: &str) {
+    assert_eq!(transactions(&format!("{}\n", header)), expected);
+}
+
+#[rstest]
+#[case::single_space_comment("Assets:Bank  $-10 ; note", "Assets:Bank  -10 $")]
+#[case::account_with_spaces("Assets:Credit Card  $-10", "Assets:Credit Card  -10 $")]
+#[case::tab("Assets:Bank\t$-10", "Assets:Bank  -10 $")]
+#[case::flag("* Assets:Bank  $-10", "* Assets:Bank  -10 $")]
+#[case::unit_price("Assets:Bank  10 AAPL @ $1", "Assets:Bank  10 AAPL @ 1 $")]
+#[case::total_price("Assets:Bank  10 AAPL @@ $10", "Assets:Bank  10 AAPL @@ 10 $")]
+#[case::lot_price("Assets:Bank  10 AAPL {$1}", "Assets:Bank  10 AAPL @ 1 $")]
+#[case::fixated_lot_price("Assets:Bank  10 AAPL {=$1}", "Assets:Bank  10 AAPL @ 1 $")]
+#[case::lot_total("Assets:Bank  10 AAPL {{$10}}", "Assets:Bank  10 AAPL @@ 10 $")]
+#[case::lot_date_note(
+    "Assets:Bank  10 AAPL {$1} [2024/01/01] (gift)",
+    "Assets:Bank  10 AAPL @ 1 $"
+)]
+#[case::price_over_lot("Assets:Bank  -10 AAPL {$1} @ $2", "Assets:Bank  -10 AAPL @ 2 $")]
+#[case::quoted_commodity("Assets:Bank  10 \"M;M\"", "Assets:Bank  10 M;M")]
+#[case::virtual_account("(Budget:Food)  $-10", "(Budget:Food)  -10 $")]
+fn test_parse_ledger_postings(#[case] posting: &str, #[case] expected: &str) {
+    let journal = parse_ledger(&format!("2024/01/05 Shop\n    {}\n    Equity\n", posting)).unwrap();
+    let text = journal.transactions()[0].to_string();
+    assert_eq!(text.lines().nth(1).unwrap().trim(), expected);
+}
+
+#[test]
+fn test_parse_ledger_comments() {
+    let journal = parse_ledger(
+        "2024/01/05 Shop ; [=2024/01/07]\n    Expenses  $10 ; note\n    Assets:Bank ; paid\n    ; settled: yes\n",
+    )
+    .unwrap();
+    let transaction = &journal.transactions()[0];
+    assert_eq!(
+        transaction.secondary_date,
+        NaiveDate::from_ymd_opt(2024, 1, 7)
+    );
+    assert_eq!(transaction.postings[1].account, "Assets:Bank".into());
+    assert!(transaction.tags.is_empty());
+}
+
+#[test]
+fn test_parse_ledger_balance_assertion() {
+    let journal =
+        parse_ledger("2024/01/05 Shop\n    Assets:Bank  $-10 = $90\n    Expenses\n").unwrap();
+    assert_eq!(
+        journal.transactions()[0].postings[0]
+            .balance_assertion
+            .as_ref()
+            .map(|a| a.to_string()),
+        Some("90 $".to_string())
+    );
+}
+
+#[rstest]
+#[case::tags("; :food:travel:", vec![("food", None), ("travel", None)])]
+#[case::metadata("; Receipt: 42", vec![("Receipt", Some("42"))])]
+#[case::typed_metadata("; Count:: 3", vec![("Count", Some("3"))])]
+#[case::empty_metadata("; reviewed:", vec![("reviewed", None)])]
+#[case::plain_comment("; bought at 10:30", vec![])]
+fn test_parse_ledger_comment_tags(
+    #[case] comment: &str,
+    #[case] expected: Vec<(&str, Option<&str>)>,
+) {
+    let journal = parse_ledger(&format!(
+        "2024/01/05 Shop\n    {}\n    Assets:Bank  $-10\n    Expenses\n",
+        comment
+    ))
+    .unwrap();
+    assert_eq!(
+        journal.transactions()[0].tags,
+        expected
+            .into_iter()
+            .map(|(name, value)| Tag {
+                name: name.to_string(),
+                value: value.map(str::to_string)
+            })
+            .collect::<Vec<Tag>>()
+    );
+}
+
+#[test]
+fn test_parse_ledger_balancing() {
+    let text = transactions(
+        r#"2024/01/05 Exchange
+    Assets:Euro  10 EUR
+    Assets:Dollar  -11 USD
+
+2024/01/06 Budget
+    Expenses:Food  $10
+    (Budget:Food)  $-10
+    Assets:Bank  $-10
+"#,
+    );
+    assert_eq!(text.matches("\n2024").count(), 1);
+
+    match parse_ledger("2024/01/05 Shop\n    Expenses  $10\n    Assets  $-9\n") {
+        Err(HLParserError::Parse(message)) => {
+            assert!(message.starts_with("line 1: Transaction 2024-01-05 Shop"))
+        }
+        result => panic!("unexpected result {:?}", result),
+    }
+}
+
+#[test]
+fn test_parse_ledger_virtual_postings() {
+    let journal = parse_ledger(
+        r#"= /^Expenses:Food/
+    (Budget:Food)  -1
+
+2024/01/05 Shop
+    Expenses:Food  $10
+    Assets:Bank
+    (Tracking)  $5
+    [Savings]  $5
+    [Assets:Bank]
+"#,
+    )
+    .unwrap();
+    let transaction = &journal.transactions()[0];
+    assert_eq!(
+        transaction.to_string(),
+        r#"2024-01-05 Shop
+   Expenses:Food  10 $
+   Assets:Bank
+   (Tracking)  5 $
+   [Savings]  5 $
+   [Assets:Bank]
+   (Budget:Food)  -10 $
+"#
+    );
+    let kinds: Vec<PostingKind> = transaction.postings.iter().map(|p| p.kind).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            PostingKind::Regular,
+            PostingKind::Regular,
+            PostingKind::Virtual,
+            PostingKind::BalancedVirtual,
+            PostingKind::BalancedVirtual,
+            PostingKind::Virtual
+        ]
+    );
+    let amounts: Vec<String> = transaction
+        .posting_amounts()
+        .iter()
+        .map(|a| a.to_string())
+        .collect();
+    assert_eq!(
+        amounts,
+        vec!["10 $", "-10 $", "5 $", "5 $", "-5 $", "-10 $"]
+    );
+    assert!(journal
+        .check(&[Check::BalancedNoAutoConversion, Check::Assertions])
+        .is_empty());
+}
+
+#[rstest]
+#[case::account_query("/food/", "[[account =~ /food/]]")]
+#[case::bare_pattern("food", "[[account =~ /food/]]")]
+#[case::adjacent_terms("food dining", "[[account =~ /food/], [account =~ /dining/]]")]
+#[case::payee_query("@shop and not /tax/", "[[payee =~ /shop/, not account =~ /tax/]]")]
+#[case::payee_keyword("payee /^shop/", "[[payee =~ /^shop/]]")]
+#[case::expression(
+    "expr 'account =~ /^expenses:/ & payee =~ /shop/'",
+    "[[account =~ /^expenses:/, payee =~ /shop/]]"
+)]
+#[case::expression_or(
+    "expr account =~ /food/ or !account =~ /tax/",
+    "[[account =~ /food/], [not account =~ /tax/]]"
+)]
+fn test_parse_predicate(#[case] predicate: &str, #[case] expected: &str) {
+    let groups: Vec<Vec<String>> = parse_predicate(predicate)
+        .unwrap()
+        .iter()
+        .map(|g| g.iter().map(|t| t.to_string()).collect())
+        .collect();
+    assert_eq!(format!("{:?}", groups).replace('"', ""), expected);
+}
+
+#[rstest]
+#[case::amount("expr amount > 100")]
+#[case::parentheses("(food)")]
+#[case::missing_operator("expr account =~ /a/ account =~ /b/")]
+#[case::dangling_operator("/food/ and")]
+#[case::invalid_regex("/(food/")]
+fn test_parse_predicate_errors(#[case] predicate: &str) {
+    assert!(parse_predicate(predicate).is_err());
+}
+
+#[test]
+fn test_parse_ledger_automated_transactions() {
+    let text = transactions(
+        r#"2024/01/04 Before
+    Expenses:Food  $5
+    Assets:Bank
+
+= /^expenses:food/ and not @market
+    $account:Tax  0.1
+    Liabilities:Tax  $-1
+
+2024/01/05 Shop
+    Expenses:Food  $10
+    Assets:Bank
+
+2024/01/06 Market
+    Expenses:Food  $10
+    Assets:Bank
+"#,
+    );
+    assert_eq!(
+        text,
+        r#"2024-01-04 Before
+   Expenses:Food  5 $
+   Assets:Bank
+2024-01-05 Shop
+   Expenses:Food  10 $
+   Assets:Bank
+   Expenses:Food:Tax  1.0 $
+   Liabilities:Tax  -1 $
+2024-01-06 Market
+   Expenses:Food  10 $
+   Assets:Bank
+"#
+    );
+}
+
+#[test]
+fn test_parse_ledger_includes() {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/ledger");
+    let contents = std::fs::read_to_string(path.join("main.ledger")).unwrap();
+    let journal = parse_journal_with_options(
+        &mut &contents[..],
+        Some(path.clone()),
+        &ParseOptions {
+            syntax: Syntax::Ledger,
+        },
+    )
+    .unwrap();
+
+    let transactions = journal.transactions();
+    assert_eq!(transactions.len(), 2);
+    assert_eq!(
+        transactions[0].to_string(),
+        "2024-01-03 * Shop ; groceries:\n   Expenses:Food  10.00 $\n   Assets:Bank\n   (Budget:Food)  -10.00 $\n"
+    );
+    assert_eq!(
+        transactions[1].tags,
+        vec![Tag {
+            name: "Payslip".to_string(),
+            value: Some("2024-01".to_string())
+        }]
+    );
+    assert_eq!(
+        transactions[1].primary_date,
+        NaiveDate::from_ymd_opt(2024, 1, 31).unwrap()
+    );
+    assert_eq!(
+        journal
+            .includes()
+            .iter()
+            .map(|i| (i.path.clone(), i.line))
+            .collect::<Vec<(PathBuf, usize)>>(),
+        vec![
+            (path.join("automated.ledger"), 2),
+            (path.join("salary.ledger"), 8)
+        ]
+    );
+}
+
+#[test]
+fn test_parse_ledger_sub_directives() {
+    let journal = parse_ledger(
+        r#"account Expenses:Food
+    note Groceries and restaurants
+    alias food
+    check commodity == "$"
+    assert commodity == "$"
+    default
+commodity $
+    note US dollar
+payee Shop
+    alias Shop Inc
+
+2024/01/05 Shop
+    Expenses:Food  $10
+    Assets
+"#,
+    )
+    .unwrap();
+
+    assert!(journal.accounts().contains(&"Expenses:Food".into()));
+    assert_eq!(journal.transactions().len(), 1);
+}
+
+#[rstest]
+#[case::periodic(
+    "~ monthly\n    Expenses  $10\n    Assets\n",
+    "line 1: ledger-cli periodic transactions are not supported"
+)]
+#[case::define(
+    "\ndefine rate=0.1\n",
+    "line 2: ledger-cli define directives are not supported"
+)]
+#[case::amount_expression(
+    "2024/01/05 Shop\n    Expenses  ($10 * 2)\n    Assets\n",
+    "line 2: ledger-cli amount expressions are not supported: ($10 * 2)"
+)]
+#[case::posting_date(
+    "2024/01/05 Shop\n    Expenses  $10 ; [=2024/01/09]\n    Assets\n",
+    "line 2: ledger-cli posting dates are not supported: [=2024/01/09]"
+)]
+#[case::actual_date(
+    "2024/01/05 Shop ; [2024/01/04]\n    Expenses  $10\n    Assets\n",
+    "line 1: ledger-cli actual dates in comments are not supported: [2024/01/04]"
+)]
+#[case::automated_expression(
+    "= expr amount > 100\n    Expenses  0.1\n",
+    "line 1: unsupported automated transaction predicate: unsupported operator >"
+)]
+#[case::automated_without_amount(
+    "= /food/\n    (Budget:Food)\n",
+    "line 2: automated transaction postings need an amount"
+)]
+#[case::invalid_amount(
+    "2024/01/05 Shop\n    Expenses  $10 EUR\n    Assets\n",
+    "line 2: cannot parse the amount of posting: Expenses  $10 EUR"
+)]
+fn test_parse_ledger_errors(#[case] input: &str, #[case] expected: &str) {
+    match parse_ledger(input) {
+        Err(HLParserError::Parse(message)) => assert_eq!(message, expected),
+        result => panic!("unexpected result {:?}", result),
+    }
+}
+
+#[test]
+fn test_parse_journal_ignores_ledger_syntax() {
+    let mut input = "= /food/\n    (Budget:Food)  -1\n";
+    assert!(parse_journal(&mut input, None).is_err());
+}
@@ -0,0 +1,143 @@
+use std::fmt::Display;
+
+use regex::Regex;
+
+use crate::{amount::types::Amount, posting::types::Posting, transaction::types::Transaction};
+
+/// What a term of an automated transaction predicate matches
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum PredicateField {
+    /// The account of a posting, the default of ledger-cli queries
+    Account,
+    /// The payee of the posting's transaction, written `payee` or `@`
+    Payee,
+}
+
+/// A term of an automated transaction predicate, like `account =~ /food/` or `not @shop`
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PredicateTerm {
+    /// What the term matches
+    pub field: PredicateField,
+    /// The case-insensitive pattern to search for
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::serde_regex"))]
+    pub pattern: Regex,
+    /// Whether the term matches when the pattern is not found
+    pub negated: bool,
+}
+
+impl PredicateTerm {
+    fn matches(&self, transaction: &Transaction, posting: &Posting) -> bool {
+        let value = match self.field {
+            PredicateField::Account => posting.account.as_str(),
+            PredicateField::Payee => transaction.payee().unwrap_or_default(),
+        };
+        self.pattern.is_match(value) != self.negated
+    }
+}
+
+impl PartialEq for PredicateTerm {
+    fn eq(&self, other: &Self) -> bool {
+        self.field == other.field
+            && self.pattern.as_str() == other.pattern.as_str()
+            && self.negated == other.negated
+    }
+}
+
+impl Eq for PredicateTerm {}
+
+impl Display for PredicateTerm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.negated {
+            write!(f, "not ")?;
+        }
+        match self.field {
+            PredicateField::Account => write!(f, "account =~ /{}/", self.pattern.as_str()),
+            PredicateField::Payee => write!(f, "payee =~ /{}/", self.pattern.as_str()),
+        }
+    }
+}
+
+/// A ledger-cli automated transaction, adding postings to the postings it matches
+///
+/// The postings of an automated transaction are added to the transaction of each matching
+/// posting. An amount without a commodity multiplies the amount of the matching posting, and
+/// `$account` in an account name is replaced by the account of the matching posting.
+///
+/// # Example
+///
+/// ```
+/// use hledger_parse::{parse_journal_with_options, ParseOptions, Syntax};
+///
+/// let mut input = r#"= expr account =~ /^expenses:food/
+///     (budget:food)  -1
+///
+/// 2024/01/05 Shop
+///     expenses:food  $10
+///     assets:bank
+/// "#;
+/// let options = ParseOptions { syntax: Syntax::Ledger };
+/// let journal = parse_journal_with_options(&mut input, None, &options).unwrap();
+/// assert_eq!(
+///     "2024-01-05 Shop\n   expenses:food  10 $\n   assets:bank\n   (budget:food)  -10 $\n",
+///     format!("{}", journal.transactions()[0])
+/// );
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AutomatedTransaction {
+    /// Groups of terms which must all match; a posting matches if any group matches
+    pub predicate: Vec<Vec<PredicateTerm>>,
+    /// The postings to add for each matching posting
+    pub postings: Vec<Posting>,
+}
+
+impl AutomatedTransaction {
+    /// Returns true if the predicate matches a posting of a transaction
+    pub fn matches(&self, transaction: &Transaction, posting: &Posting) -> bool {
+        self.predicate
+            .iter()
+            .any(|terms| terms.iter().all(|t| t.matches(transaction, posting)))
+    }
+
+    /// Returns the transaction with the postings added for each of its matching postings
+    pub fn apply(&self, mut transaction: Transaction) -> Transaction {
+        let amounts = transaction.posting_amounts();
+        let mut generated = vec![];
+        for (posting, amount) in transaction.postings.iter().zip(amounts) {
+            if !self.matches(&transaction, posting) {
+                continue;
+            }
+            for automated in &self.postings {
+                let account = automated
+                    .account
+                    .as_str()
+                    .replace("$account", posting.account.as_str());
+                let amounts = match &automated.amount {
+                    Some(multiplier) if multiplier.currency.is_empty() => amount
+                        .amounts()
+                        .into_iter()
+                        .map(|a| {
+                            Some(Amount {
+                                currency: a.currency,
+                                value: a.value * multiplier.value,
+                            })
+                        })
+                        .collect(),
+                    fixed => vec![fixed.clone()],
+                };
+                for amount in amounts {
+                    generated.push(Posting {
+                        account: account.as_str().into(),
+                        amount,
+                        ..automated.clone()
+                    });
+                }
+            }
+        }
+        transaction.postings.extend(generated);
+        transaction
+    }
+}
@@ -1,31 +1,66 @@
 mod account;
+mod alias;
 mod amount;
+mod beancount;
+mod check;
 mod code;
 mod comment;
 mod commodity;
+mod csv;
 mod date;
 mod description;
+mod fingerprint;
 mod journal;
+#[cfg(feature = "json")]
+mod json;
+mod ledger;
+mod ofx;
 mod posting;
 mod price;
+mod qif;
+mod report;
 mod status;
 mod tag;
+mod timeclock;
+mod timedot;
 mod transaction;
 mod utils;
 
-pub use account::types::Account;
-pub use amount::types::Amount;
+pub use account::types::{Account, AccountNode, AccountTree, AccountType};
+pub use alias::types::AccountAlias;
+pub use amount::types::{Amount, MixedAmount};
+pub use beancount::parsers::parse_beancount;
+pub use beancount::types::{write_beancount, BeancountExport, BeancountMapping};
+pub use check::types::Check;
+use chrono::NaiveDate;
 pub use commodity::types::Commodity;
+pub use csv::types::{ConditionalBlock, CsvRules, Matcher};
 pub use description::types::Description;
-pub use journal::types::Journal;
-pub use posting::types::Posting;
+pub use fingerprint::types::Fingerprint;
+pub use journal::types::{IncludedFile, InputFormat, Journal, Location, ParseOptions, Syntax};
+pub use ledger::types::{AutomatedTransaction, PredicateField, PredicateTerm};
+pub use ofx::types::{OfxElement, OfxMapping};
+pub use posting::types::{Posting, PostingKind};
 pub use price::types::Price;
+pub use qif::types::{write_qif, QifMapping, QifRecord, QifType};
+pub use report::types::{Interval, Period, Statement, StatementRow, StatementSection};
 pub use status::types::Status;
 pub use tag::types::Tag;
 use thiserror::Error;
+pub use timeclock::types::{TimeclockCode, TimeclockEntry};
+pub use timedot::types::TimedotEntry;
 pub use transaction::types::Transaction;
 
-pub use journal::parsers::parse_journal;
+pub use csv::parsers::{parse_csv, parse_csv_rules};
+pub use journal::parsers::{parse_journal, parse_journal_with_options};
+#[cfg(feature = "json")]
+pub use json::parsers::{parse_hledger_json, parse_hledger_json_transaction};
+#[cfg(feature = "json")]
+pub use json::types::{write_hledger_json, write_hledger_json_transaction};
+pub use ofx::parsers::{parse_ofx, parse_ofx_document};
+pub use qif::parsers::parse_qif;
+pub use timeclock::parsers::parse_timeclock;
+pub use timedot::parsers::{parse_timedot, parse_timedot_quantity};
 
 use journal::types::Value;
 
@@ -34,7 +69,7 @@ pub enum HLParserError {
     #[error("IO error: {0}")]
     IO(String),
     #[error("Parse error: {0}")]
-    Parse(String), 
+    Parse(String),
     #[error("Validation error: {0}")]
     Validation(ValidationError),
     #[error("Included journal error: {0}")]
@@ -44,6 +79,8 @@ pub enum HLParserError {
 }
 
 #[derive(Debug, Error)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum ValidationError {
     #[error("Invalid date components: {}-{}-{}", .0.unwrap_or(0), .1, .2)]
     InvalidDateComponents(Option<i32>, u32, u32),
@@ -55,4 +92,189 @@ pub enum ValidationError {
     UndefinedAccounts(Vec<String>),
     #[error("Invalid amount: {0}")]
     InvalidAmount(String),
+    #[error("Clock-in at {0} has no matching clock-out")]
+    UnmatchedClockIn(Location),
+    #[error("Clock-out at {0} has no matching clock-in")]
+    UnmatchedClockOut(Location),
+    #[error("Clock-out at {0} is before its clock-in")]
+    ClockOutBeforeClockIn(Location),
+    #[error("These commodities are not declared:\n{}", format_located(.0))]
+    UndeclaredCommodities(Vec<(String, Option<Location>)>),
+    #[error("These payees are not declared:\n{}", format_located(.0))]
+    UndeclaredPayees(Vec<(String, Option<Location>)>),
+    #[error("These tags are not declared:\n{}", format_located(.0))]
+    UndeclaredTags(Vec<(String, Option<Location>)>),
+}
+
+/// A violation found by [`Journal::check`]
+#[derive(Debug, Error, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum CheckError {
+    #[error("{}account {account} is not declared", prefix(.location))]
+    UndeclaredAccount {
+        account: Account,
+        location: Option<Location>,
+    },
+    #[error("{}commodity {commodity} is not declared", prefix(.location))]
+    UndeclaredCommodity {
+        commodity: String,
+        location: Option<Location>,
+    },
+    #[error("{}payee {payee} is not declared", prefix(.location))]
+    UndeclaredPayee {
+        payee: String,
+        location: Option<Location>,
+    },
+    #[error("{}tag {tag} is not declared", prefix(.location))]
+    UndeclaredTag {
+        tag: String,
+        location: Option<Location>,
+    },
+    #[error("{}date {date} is before the previous date {previous}", prefix(.location))]
+    UnorderedDate {
+        date: NaiveDate,
+        previous: NaiveDate,
+        location: Option<Location>,
+    },
+    #[error(
+        "{}accounts {} have the same leaf name {leaf}",
+        prefix(.location),
+        .accounts.iter().map(Account::as_str).collect::<Vec<&str>>().join(", ")
+    )]
+    DuplicateLeafName {
+        leaf: String,
+        accounts: Vec<Account>,
+        location: Option<Location>,
+    },
+    #[error("{}transaction is unbalanced by {residual}", prefix(.location))]
+    UnbalancedTransaction {
+        residual: MixedAmount,
+        location: Option<Location>,
+    },
+    #[error(
+        "{}balance assertion failed for {account}: expected {expected}, found {actual}",
+        prefix(.location)
+    )]
+    FailedAssertion {
+        account: Account,
+        expected: Amount,
+        actual: Amount,
+        location: Option<Location>,
+    },
+    #[error("{}transaction duplicates the one at {}", prefix(.location), display_location(.original))]
+    DuplicateTransaction {
+        original: Option<Location>,
+        location: Option<Location>,
+    },
+}
+
+/// Something of a journal which Beancount cannot express as is, found by
+/// [`write_beancount`](crate::write_beancount)
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum BeancountIssue {
+    #[error("account {account} has no type, exported as {renamed}")]
+    UntypedAccount { account: Account, renamed: String },
+    #[error(
+        "accounts {} are all exported as {renamed}",
+        .accounts.iter().map(Account::as_str).collect::<Vec<&str>>().join(", ")
+    )]
+    AccountCollision {
+        accounts: Vec<Account>,
+        renamed: String,
+    },
+    #[error("commodity {commodity:?} is not a valid Beancount commodity, exported as {renamed}")]
+    RenamedCommodity { commodity: String, renamed: String },
+    #[error("{}transaction is unbalanced by {residual}, which Beancount rejects", prefix(.location))]
+    UnbalancedTransaction {
+        residual: MixedAmount,
+        location: Option<Location>,
+    },
+    #[error("{directive} has no Beancount equivalent and is not exported")]
+    UnsupportedDirective { directive: String },
+    #[error(
+        "{}virtual posting to {account} has no Beancount equivalent and is not exported",
+        prefix(.location)
+    )]
+    VirtualPosting {
+        account: Account,
+        location: Option<Location>,
+    },
+    #[error(
+        "{}balance assertion on {account} excludes its subaccounts, unlike Beancount balances, and is not exported",
+        prefix(.location)
+    )]
+    ExclusiveAssertion {
+        account: Account,
+        location: Option<Location>,
+    },
+    #[error(
+        "{}balance assertion on {account} is followed by other postings on the same day, unlike Beancount balances, and is not exported",
+        prefix(.location)
+    )]
+    ShiftedAssertion {
+        account: Account,
+        location: Option<Location>,
+    },
+    #[error("{directive} cannot be dated without transactions or prices and is not exported")]
+    UndatedDirective { directive: String },
+}
+
+impl CheckError {
+    /// Returns the check which found the violation
+    pub fn check(&self) -> Check {
+        match self {
+            CheckError::UndeclaredAccount { .. } => Check::Accounts,
+            CheckError::UndeclaredCommodity { .. } => Check::Commodities,
+            CheckError::UndeclaredPayee { .. } => Check::Payees,
+            CheckError::UndeclaredTag { .. } => Check::Tags,
+            CheckError::UnorderedDate { .. } => Check::OrderedDates,
+            CheckError::DuplicateLeafName { .. } => Check::UniqueLeafNames,
+            CheckError::UnbalancedTransaction { .. } => Check::BalancedNoAutoConversion,
+            CheckError::FailedAssertion { .. } => Check::Assertions,
+            CheckError::DuplicateTransaction { .. } => Check::Duplicates,
+        }
+    }
+
+    /// Returns the location of the violation, if the journal was parsed from text
+    pub fn location(&self) -> Option<&Location> {
+        match self {
+            CheckError::UndeclaredAccount { location, .. }
+            | CheckError::UndeclaredCommodity { location, .. }
+            | CheckError::UndeclaredPayee { location, .. }
+            | CheckError::UndeclaredTag { location, .. }
+            | CheckError::UnorderedDate { location, .. }
+            | CheckError::DuplicateLeafName { location, .. }
+            | CheckError::UnbalancedTransaction { location, .. }
+            | CheckError::FailedAssertion { location, .. }
+            | CheckError::DuplicateTransaction { location, .. } => location.as_ref(),
+        }
+    }
+}
+
+fn prefix(location: &Option<Location>) -> String {
+    match location {
+        Some(location) => format!("{}: ", location),
+        None => String::new(),
+    }
+}
+
+fn display_location(location: &Option<Location>) -> String {
+    match location {
+        Some(location) => location.to_string(),
+        None => "an unknown location".to_string(),
+    }
+}
+
+fn format_located(values: &[(String, Option<Location>)]) -> String {
+    values
+        .iter()
+        .map(|(value, location)| match location {
+            Some(location) => format!("{} ({})", value, location),
+            None => value.to_string(),
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
 }
@@ -0,0 +1,129 @@
+use winnow::{
+    ascii::multispace0,
+    combinator::{delimited, opt, repeat_till, terminated},
+    token::take_till,
+    PResult, Parser,
+};
+
+use crate::{journal::types::Journal, HLParserError};
+
+use super::types::{OfxElement, OfxMapping};
+
+fn parse_tag_name<'s>(input: &mut &'s str) -> PResult<&'s str> {
+    take_till(1.., |c: char| c == '>' || c == '/' || c.is_whitespace()).parse_next(input)
+}
+
+fn parse_closing_tag<'s>(name: &'s str) -> impl FnMut(&mut &'s str) -> PResult<&'s str> {
+    move |input: &mut &'s str| delimited((multispace0, "</"), name, '>').parse_next(input)
+}
+
+fn decode_entities(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+}
+
+/// Parses an OFX element, in SGML (OFX 1.x) or XML (OFX 2.x) syntax
+///
+/// Leaf elements have a value and may omit their closing tag, like in SGML. Aggregates contain
+/// other elements and always have a closing tag.
+pub fn parse_ofx_element(input: &mut &str) -> PResult<OfxElement> {
+    let name = delimited((multispace0, '<'), parse_tag_name, '>').parse_next(input)?;
+    let value = take_till(0.., '<').map(str::trim).parse_next(input)?;
+
+    if !value.is_empty() {
+        opt(parse_closing_tag(name)).parse_next(input)?;
+        return Ok(OfxElement {
+            name: name.to_string(),
+            value: Some(decode_entities(value)),
+            children: vec![],
+        });
+    }
+    if opt(parse_closing_tag(name)).parse_next(input)?.is_some() {
+        return Ok(OfxElement {
+            name: name.to_string(),
+            value: Some(String::new()),
+            children: vec![],
+        });
+    }
+
+    let (children, _) =
+        repeat_till(0.., parse_ofx_element, parse_closing_tag(name)).parse_next(input)?;
+    Ok(OfxElement {
+        name: name.to_string(),
+        value: None,
+        children,
+    })
+}
+
+/// Parses an OFX document into its `OFX` element, skipping the SGML header or XML declarations
+pub fn parse_ofx_document(input: &mut &str) -> Result<OfxElement, HLParserError> {
+    let start = input
+        .find("<OFX>")
+        .ok_or_else(|| HLParserError::Parse("No OFX element".to_string()))?;
+    *input = &input[start..];
+    terminated(parse_ofx_element, multispace0)
+        .parse_next(input)
+        .map_err(|e| HLParserError::Parse(format!("Invalid OFX: {}", e)))
+}
+
+/// Converts the bank and credit card statements of an OFX 1.x or 2.x document into a journal
+///
+/// Each statement transaction becomes a transaction between the mapped statement account and
+/// the income or expense account, and the last transaction of each statement asserts its
+/// closing balance.
+///
+/// # Example
+///
+/// ```
+/// use hledger_parse::{parse_ofx, OfxMapping};
+///
+/// let mut input = r#"OFXHEADER:100
+/// DATA:OFXSGML
+///
+/// <OFX>
+/// <BANKMSGSRSV1><STMTTRNRS><STMTRS>
+/// <CURDEF>USD
+/// <BANKACCTFROM><ACCTID>123456</BANKACCTFROM>
+/// <BANKTRANLIST>
+/// <STMTTRN>
+/// <TRNTYPE>DEBIT
+/// <DTPOSTED>20240301120000
+/// <TRNAMT>-45.20
+/// <FITID>A1
+/// <NAME>Grocery Store
+/// </STMTTRN>
+/// </BANKTRANLIST>
+/// <LEDGERBAL><BALAMT>954.80<DTASOF>20240301</LEDGERBAL>
+/// </STMTRS></STMTTRNRS></BANKMSGSRSV1>
+/// </OFX>
+/// "#;
+/// let mut mapping = OfxMapping::default();
+/// mapping.accounts.insert("123456".to_string(), "assets:bank:checking".into());
+/// let journal = parse_ofx(&mut input, &mapping).unwrap();
+/// let transaction = &journal.transactions()[0];
+/// assert_eq!(
+///     "2024-03-01 Grocery Store ; id:A1\n   assets:bank:checking  -45.20 USD\n   expenses:unknown  45.20 USD\n",
+///     format!("{}", transaction)
+/// );
+/// assert_eq!(
+///     "954.80 USD",
+///     format!("{}", transaction.postings[0].balance_assertion.as_ref().unwrap())
+/// );
+/// ```
+pub fn parse_ofx(input: &mut &str, mapping: &OfxMapping) -> Result<Journal, HLParserError> {
+    let document = parse_ofx_document(input)?;
+    let mut transactions = vec![];
+    for statement in document
+        .descendants("STMTRS")
+        .into_iter()
+        .chain(document.descendants("CCSTMTRS"))
+    {
+        transactions.extend(mapping.statement_transactions(statement)?);
+    }
+    Ok(Journal::new(transactions, vec![], vec![], vec![]))
+}
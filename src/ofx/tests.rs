@@ -0,0 +1,179 @@
+use std::path::PathBuf;
+
+use rstest::rstest;
+use rust_decimal_macros::dec;
+
+use crate::{amount::types::Amount, HLParserError};
+
+use super::{
+    parsers::{parse_ofx, parse_ofx_document, parse_ofx_element},
+    types::{OfxElement, OfxMapping},
+};
+
+fn fixture(path: &str) -> String {
+    std::fs::read_to_string(
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/ofx")
+            .join(path),
+    )
+    .unwrap()
+}
+
+fn leaf(name: &str, value: &str) -> OfxElement {
+    OfxElement {
+        name: name.to_string(),
+        value: Some(value.to_string()),
+        children: vec![],
+    }
+}
+
+#[rstest]
+#[case::sgml("<STATUS>\n<CODE>0\n<SEVERITY>INFO\n</STATUS>")]
+#[case::xml("<STATUS>\n  <CODE>0</CODE>\n  <SEVERITY>INFO</SEVERITY>\n</STATUS>")]
+#[case::mixed("<STATUS><CODE>0</CODE><SEVERITY>INFO</STATUS>")]
+fn test_parse_ofx_element(#[case] input: &str) {
+    let mut input = input;
+    assert_eq!(
+        parse_ofx_element(&mut input).unwrap(),
+        OfxElement {
+            name: "STATUS".to_string(),
+            value: None,
+            children: vec![leaf("CODE", "0"), leaf("SEVERITY", "INFO")],
+        }
+    );
+}
+
+#[test]
+fn test_parse_ofx_element_entities_and_empty_leaf() {
+    let mut input = "<STMTTRN><NAME>A &amp; B &lt;C&gt;</NAME><MEMO></MEMO></STMTTRN>";
+    assert_eq!(
+        parse_ofx_element(&mut input).unwrap().children,
+        vec![leaf("NAME", "A & B <C>"), leaf("MEMO", "")]
+    );
+}
+
+#[rstest]
+#[case::no_ofx("OFXHEADER:100\n", "No OFX element")]
+#[case::unclosed_aggregate("<OFX><BANKMSGSRSV1><STMTTRNRS></OFX>", "Invalid OFX")]
+fn test_parse_ofx_document_errors(#[case] input: &str, #[case] expected: &str) {
+    let mut input = input;
+    match parse_ofx_document(&mut input) {
+        Err(e @ HLParserError::Parse(_)) => assert!(e.to_string().contains(expected), "{}", e),
+        r => panic!("Unexpected result {:?}", r),
+    }
+}
+
+#[test]
+fn test_parse_ofx_sgml() {
+    let mut mapping = OfxMapping::default();
+    mapping
+        .accounts
+        .insert("123456".to_string(), "assets:bank:checking".into());
+    let contents = fixture("checking.ofx");
+    let journal = parse_ofx(&mut &contents[..], &mapping).unwrap();
+    let transactions = journal.transactions();
+
+    assert_eq!(
+        transactions
+            .iter()
+            .map(|t| t.to_string())
+            .collect::<Vec<String>>()
+            .join("\n"),
+        r#"2024-03-01 Smith & Sons Grocery ; id:A1
+   assets:bank:checking  -45.20 USD
+   expenses:unknown  45.20 USD
+
+2024-03-02 ACME Corp | Salary March ; id:A2
+   assets:bank:checking  2500.00 USD
+   income:unknown  -2500.00 USD
+
+2024-03-05 Landlord ; id:A3
+   assets:bank:checking  -120.00 USD
+   expenses:unknown  120.00 USD
+"#
+    );
+    assert_eq!(transactions[2].code, Some("1001".to_string()));
+    assert_eq!(
+        transactions
+            .iter()
+            .map(|t| t.postings[0].balance_assertion.clone())
+            .collect::<Vec<_>>(),
+        vec![
+            None,
+            None,
+            Some(Amount {
+                currency: "USD".to_string(),
+                value: dec!(2334.80)
+            })
+        ]
+    );
+    assert!(journal.check(&[crate::Check::Assertions]).is_empty());
+}
+
+#[test]
+fn test_parse_ofx_xml() {
+    let mapping = OfxMapping {
+        default_account: "liabilities:card".into(),
+        id_tag: None,
+        ..OfxMapping::default()
+    };
+    let contents = fixture("card.qfx");
+    let journal = parse_ofx(&mut &contents[..], &mapping).unwrap();
+    let transactions = journal.transactions();
+
+    assert_eq!(
+        transactions[0].to_string(),
+        "2024-03-10 Online Shop\n   liabilities:card  -12.99 EUR\n   expenses:unknown  12.99 EUR\n"
+    );
+    assert_eq!(
+        transactions[0].postings[0].balance_assertion,
+        Some(Amount {
+            currency: "EUR".to_string(),
+            value: dec!(-12.99)
+        })
+    );
+}
+
+#[test]
+fn test_parse_ofx_without_transactions() {
+    let mut input = "<OFX><STMTRS><CURDEF>USD<BANKTRANLIST></BANKTRANLIST><LEDGERBAL><BALAMT>100.50<DTASOF>20240331</LEDGERBAL></STMTRS></OFX>";
+    let journal = parse_ofx(&mut input, &OfxMapping::default()).unwrap();
+    let transactions = journal.transactions();
+
+    assert_eq!(
+        transactions[0].to_string(),
+        "2024-03-31 balance\n   assets:bank  0 USD\n"
+    );
+    assert_eq!(
+        transactions[0].postings[0].balance_assertion,
+        Some(Amount {
+            currency: "USD".to_string(),
+            value: dec!(100.50)
+        })
+    );
+}
+
+#[rstest]
+#[case::missing_amount(
+    "<OFX><STMTRS><BANKTRANLIST><STMTTRN><DTPOSTED>20240301</STMTTRN></BANKTRANLIST></STMTRS></OFX>",
+    "without TRNAMT"
+)]
+#[case::invalid_date(
+    "<OFX><STMTRS><BANKTRANLIST><STMTTRN><DTPOSTED>2024<TRNAMT>1</STMTTRN></BANKTRANLIST></STMTRS></OFX>",
+    "Invalid OFX date: 2024"
+)]
+#[case::invalid_amount(
+    "<OFX><STMTRS><BANKTRANLIST><STMTTRN><DTPOSTED>20240301<TRNAMT>one</STMTTRN></BANKTRANLIST></STMTRS></OFX>",
+    "Invalid OFX amount: one"
+)]
+#[case::balance_without_date(
+    "<OFX><STMTRS><BANKTRANLIST></BANKTRANLIST><LEDGERBAL><BALAMT>1</LEDGERBAL></STMTRS></OFX>",
+    "OFX LEDGERBAL without DTASOF"
+)]
+fn test_parse_ofx_errors(#[case] input: &str, #[case] expected: &str) {
+    let mut input = input;
+    match parse_ofx(&mut input, &OfxMapping::default()) {
+        Err(e @ HLParserError::Parse(_)) => assert!(e.to_string().contains(expected), "{}", e),
+        r => panic!("Unexpected result {:?}", r),
+    }
+}
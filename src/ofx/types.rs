@@ -0,0 +1,248 @@
+use std::{collections::BTreeMap, str::FromStr};
+
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+
+use crate::{
+    account::types::Account,
+    amount::types::Amount,
+    description::types::Description,
+    posting::types::{Posting, PostingKind},
+    status::types::Status,
+    tag::types::Tag,
+    transaction::types::Transaction,
+    HLParserError,
+};
+
+/// An element of an OFX document, either an aggregate with children or a leaf with a value
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OfxElement {
+    /// The tag name, e.g. `STMTTRN`
+    pub name: String,
+    /// The value of a leaf element
+    pub value: Option<String>,
+    /// The children of an aggregate element
+    pub children: Vec<OfxElement>,
+}
+
+impl OfxElement {
+    /// Returns the first child with the given name
+    pub fn child(&self, name: &str) -> Option<&OfxElement> {
+        self.children.iter().find(|c| c.name == name)
+    }
+
+    /// Returns the value of the first child with the given name
+    pub fn child_value(&self, name: &str) -> Option<&str> {
+        self.child(name).and_then(|c| c.value.as_deref())
+    }
+
+    /// Returns the elements with the given name in the subtree, in document order
+    pub fn descendants(&self, name: &str) -> Vec<&OfxElement> {
+        let mut found = vec![];
+        for child in &self.children {
+            if child.name == name {
+                found.push(child);
+            }
+            found.extend(child.descendants(name));
+        }
+        found
+    }
+}
+
+/// How the statements of an OFX document are mapped to journal accounts
+///
+/// # Example
+///
+/// ```
+/// use hledger_parse::OfxMapping;
+///
+/// let mut mapping = OfxMapping::default();
+/// mapping.accounts.insert("123456".to_string(), "assets:bank:checking".into());
+/// assert_eq!("assets:bank:checking", mapping.account("123456").to_string());
+/// assert_eq!("assets:bank", mapping.account("999").to_string());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OfxMapping {
+    /// The journal account of each statement account id (`ACCTID`)
+    pub accounts: BTreeMap<String, Account>,
+    /// The journal account of statement accounts missing from `accounts`
+    pub default_account: Account,
+    /// The account balancing money coming in, for every transaction
+    pub income_account: Account,
+    /// The account balancing money going out, for every transaction
+    pub expense_account: Account,
+    /// The tag receiving the transaction id (`FITID`), e.g. for
+    /// [`Journal::partition_new`](crate::Journal::partition_new)
+    pub id_tag: Option<String>,
+}
+
+impl Default for OfxMapping {
+    fn default() -> Self {
+        Self {
+            accounts: BTreeMap::new(),
+            default_account: "assets:bank".into(),
+            income_account: "income:unknown".into(),
+            expense_account: "expenses:unknown".into(),
+            id_tag: Some("id".to_string()),
+        }
+    }
+}
+
+impl OfxMapping {
+    /// Returns the journal account of a statement account id
+    pub fn account(&self, account_id: &str) -> Account {
+        self.accounts
+            .get(account_id)
+            .cloned()
+            .unwrap_or_else(|| self.default_account.clone())
+    }
+
+    /// Converts a bank (`STMTRS`) or credit card (`CCSTMTRS`) statement to transactions
+    ///
+    /// The transactions are sorted by date, and the posting of the last one asserts the closing
+    /// balance of the statement (`LEDGERBAL`). A statement without transactions becomes a single
+    /// transaction of zero on the date of its balance, which asserts the balance.
+    pub fn statement_transactions(
+        &self,
+        statement: &OfxElement,
+    ) -> Result<Vec<Transaction>, HLParserError> {
+        let account_id = statement
+            .child("BANKACCTFROM")
+            .or_else(|| statement.child("CCACCTFROM"))
+            .and_then(|a| a.child_value("ACCTID"))
+            .unwrap_or_default();
+        let account = self.account(account_id);
+        let currency = statement.child_value("CURDEF").unwrap_or_default();
+
+        let mut transactions = vec![];
+        for element in statement.descendants("STMTTRN") {
+            transactions.push(self.transaction(element, &account, currency)?);
+        }
+        transactions.sort_by_key(|t| t.primary_date);
+
+        let Some(ledger_balance) = statement.child("LEDGERBAL") else {
+            return Ok(transactions);
+        };
+        let balance = Amount {
+            currency: currency.to_string(),
+            value: parse_ofx_decimal(ledger_balance.child_value("BALAMT").ok_or_else(|| {
+                HLParserError::Parse("OFX LEDGERBAL without BALAMT".to_string())
+            })?)?,
+        };
+        match transactions.last_mut() {
+            Some(last) => last.postings[0].balance_assertion = Some(balance),
+            None => {
+                let date = ledger_balance.child_value("DTASOF").ok_or_else(|| {
+                    HLParserError::Parse("OFX LEDGERBAL without DTASOF".to_string())
+                })?;
+                transactions.push(Transaction {
+                    primary_date: parse_ofx_date(date)?,
+                    secondary_date: None,
+                    status: Status::Unmarked,
+                    code: None,
+                    description: Description {
+                        payee: None,
+                        note: Some("balance".to_string()),
+                    },
+                    postings: vec![Posting {
+                        status: Status::Unmarked,
+                        account,
+                        kind: PostingKind::Regular,
+                        amount: Some(Amount {
+                            currency: currency.to_string(),
+                            value: Decimal::ZERO,
+                        }),
+                        unit_price: None,
+                        total_price: None,
+                        balance_assertion: Some(balance),
+                        inclusive_assertion: false,
+                    }],
+                    tags: vec![],
+                });
+            }
+        }
+        Ok(transactions)
+    }
+
+    fn transaction(
+        &self,
+        element: &OfxElement,
+        account: &Account,
+        currency: &str,
+    ) -> Result<Transaction, HLParserError> {
+        let date = element
+            .child_value("DTPOSTED")
+            .ok_or_else(|| HLParserError::Parse("OFX transaction without DTPOSTED".to_string()))?;
+        let amount = Amount {
+            currency: currency.to_string(),
+            value: parse_ofx_decimal(element.child_value("TRNAMT").ok_or_else(|| {
+                HLParserError::Parse("OFX transaction without TRNAMT".to_string())
+            })?)?,
+        };
+        let name = element
+            .child_value("NAME")
+            .or_else(|| element.child("PAYEE").and_then(|p| p.child_value("NAME")))
+            .filter(|name| !name.is_empty());
+        let memo = element.child_value("MEMO").filter(|memo| !memo.is_empty());
+        let counter_account = match amount.is_negative() {
+            true => self.expense_account.clone(),
+            false => self.income_account.clone(),
+        };
+
+        Ok(Transaction {
+            primary_date: parse_ofx_date(date)?,
+            secondary_date: None,
+            status: Status::Unmarked,
+            code: element.child_value("CHECKNUM").map(str::to_string),
+            description: Description {
+                payee: name.filter(|_| memo.is_some()).map(str::to_string),
+                note: memo.or(name).map(str::to_string),
+            },
+            postings: vec![
+                Posting {
+                    status: Status::Unmarked,
+                    account: account.clone(),
+                    kind: PostingKind::Regular,
+                    amount: Some(amount.clone()),
+                    unit_price: None,
+                    total_price: None,
+                    balance_assertion: None,
+                    inclusive_assertion: false,
+                },
+                Posting {
+                    status: Status::Unmarked,
+                    account: counter_account,
+                    kind: PostingKind::Regular,
+                    amount: Some(amount.negate()),
+                    unit_price: None,
+                    total_price: None,
+                    balance_assertion: None,
+                    inclusive_assertion: false,
+                },
+            ],
+            tags: match (&self.id_tag, element.child_value("FITID")) {
+                (Some(tag), Some(id)) => vec![Tag {
+                    name: tag.clone(),
+                    value: Some(id.to_string()),
+                }],
+                _ => vec![],
+            },
+        })
+    }
+}
+
+/// Parses an OFX date, `YYYYMMDD` optionally followed by a time and a time zone
+fn parse_ofx_date(value: &str) -> Result<NaiveDate, HLParserError> {
+    value
+        .get(..8)
+        .and_then(|date| NaiveDate::parse_from_str(date, "%Y%m%d").ok())
+        .ok_or_else(|| HLParserError::Parse(format!("Invalid OFX date: {}", value)))
+}
+
+/// Parses an OFX amount, whose decimal mark may be a comma
+fn parse_ofx_decimal(value: &str) -> Result<Decimal, HLParserError> {
+    Decimal::from_str(&value.trim().replace(',', "."))
+        .map_err(|_| HLParserError::Parse(format!("Invalid OFX amount: {}", value)))
+}
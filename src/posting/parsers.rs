@@ -1,106 +1,144 @@
 use winnow::{
     ascii::{space0, space1, till_line_ending},
-    combinator::{alt, delimited, empty, opt, rest, separated_pair},
+    combinator::{alt, delimited, empty, opt, rest, separated_pair, terminated},
     token::{literal, take_until},
     PResult, Parser,
 };
 
-use crate::{amount::parsers::parse_amount, status::parsers::parse_status, Amount};
+use crate::{
+    amount::parsers::parse_amount_with_state, journal::types::ParseState,
+    status::parsers::parse_status, Amount,
+};
 
-use super::types::{Posting, PostingComplexAmount};
+use super::types::{Posting, PostingComplexAmount, PostingKind};
 
-fn parse_posting_with_amount<'s>(input: &mut &'s str) -> PResult<PostingComplexAmount> {
-    space0.parse_next(input)?;
-    let complex_amount = alt((
-        // NOTE: order of parsers is important
-        separated_pair(parse_amount, delimited(space0, "@@", space0), parse_amount).map(
-            |(amount, total_price)| -> PostingComplexAmount {
+fn parse_posting_with_amount<'s>(
+    state: &ParseState,
+) -> impl FnMut(&mut &'s str) -> PResult<PostingComplexAmount> + '_ {
+    move |input: &mut &'s str| {
+        space0.parse_next(input)?;
+        let complex_amount = alt((
+            // NOTE: order of parsers is important
+            separated_pair(
+                parse_amount_with_state(state),
+                delimited(space0, "@@", space0),
+                parse_amount_with_state(state),
+            )
+            .map(|(amount, total_price)| -> PostingComplexAmount {
                 PostingComplexAmount {
                     amount: Some(amount),
                     unit_price: None,
                     total_price: Some(total_price),
                 }
-            },
-        ),
-        separated_pair(parse_amount, delimited(space0, "@", space0), parse_amount).map(
-            |(amount, unit_price)| -> PostingComplexAmount {
+            }),
+            separated_pair(
+                parse_amount_with_state(state),
+                delimited(space0, "@", space0),
+                parse_amount_with_state(state),
+            )
+            .map(|(amount, unit_price)| -> PostingComplexAmount {
                 PostingComplexAmount {
                     amount: Some(amount),
                     unit_price: Some(unit_price),
                     total_price: None,
                 }
-            },
-        ),
-        opt(parse_amount).map(|amount| -> PostingComplexAmount {
-            PostingComplexAmount {
-                amount,
-                unit_price: None,
-                total_price: None,
-            }
-        }),
-    ))
-    .parse_next(input)?;
+            }),
+            opt(parse_amount_with_state(state)).map(|amount| -> PostingComplexAmount {
+                PostingComplexAmount {
+                    amount,
+                    unit_price: None,
+                    total_price: None,
+                }
+            }),
+        ))
+        .parse_next(input)?;
 
-    Ok(complex_amount)
+        Ok(complex_amount)
+    }
 }
 
-pub(super) fn parse_balance_assertion(input: &mut &str) -> PResult<Amount> {
-    let balance_assertion = delimited(
-        delimited(space0, literal('='), space0),
-        parse_amount,
-        space0,
-    )
-    .parse_next(input)?;
-
-    Ok(balance_assertion)
+/// Parses a balance assertion, `= amount` or `=* amount`, returning whether it is inclusive
+pub(super) fn parse_balance_assertion<'s>(
+    state: &ParseState,
+) -> impl FnMut(&mut &'s str) -> PResult<(Amount, bool)> + '_ {
+    move |input: &mut &'s str| {
+        let inclusive = delimited(
+            (space0, literal('=')),
+            opt(literal('*')).map(|star| star.is_some()),
+            space0,
+        )
+        .parse_next(input)?;
+        let amount = terminated(parse_amount_with_state(state), space0).parse_next(input)?;
+        Ok((amount, inclusive))
+    }
 }
 
+/// Parses a posting with the default journal parser state
+#[allow(dead_code)]
 pub fn parse_posting(input: &mut &str) -> PResult<Posting> {
-    let status = delimited(space1, parse_status, space0)
-        .context(winnow::error::StrContext::Label(
-            "error parsing posting status",
-        ))
-        .parse_next(input)?;
-
-    let mut rest_of_line = alt((till_line_ending, rest)).parse_next(input)?;
+    parse_posting_with_state(&ParseState::default()).parse_next(input)
+}
 
-    if rest_of_line.contains("  ") {
-        let account_name = take_until(1.., "  ")
+/// Parses a posting, using the journal parser state for e.g. decimal marks
+pub fn parse_posting_with_state<'s>(
+    state: &ParseState,
+) -> impl FnMut(&mut &'s str) -> PResult<Posting> + '_ {
+    move |input: &mut &'s str| {
+        let status = delimited(space1, parse_status, space0)
             .context(winnow::error::StrContext::Label(
-                "error parsing account name in posting with amount",
+                "error parsing posting status",
             ))
-            .parse_next(&mut rest_of_line)?;
+            .parse_next(input)?;
 
-        let complex_amount = alt((
-            parse_posting_with_amount,
-            empty.value(PostingComplexAmount::default()),
-        ))
-        .context(winnow::error::StrContext::Label(
-            "error parsing posting complex amount",
-        ))
-        .parse_next(&mut rest_of_line)?;
-        let balance_assertion = opt(parse_balance_assertion)
+        let mut rest_of_line = alt((till_line_ending, rest)).parse_next(input)?;
+
+        if rest_of_line.contains("  ") {
+            let account_name = take_until(1.., "  ")
+                .context(winnow::error::StrContext::Label(
+                    "error parsing account name in posting with amount",
+                ))
+                .parse_next(&mut rest_of_line)?;
+
+            let complex_amount = alt((
+                parse_posting_with_amount(state),
+                empty.value(PostingComplexAmount::default()),
+            ))
             .context(winnow::error::StrContext::Label(
-                "error parsing posting balance assertion",
+                "error parsing posting complex amount",
             ))
             .parse_next(&mut rest_of_line)?;
+            let (balance_assertion, inclusive_assertion) = opt(parse_balance_assertion(state))
+                .context(winnow::error::StrContext::Label(
+                    "error parsing posting balance assertion",
+                ))
+                .parse_next(&mut rest_of_line)?
+                .map_or((None, false), |(amount, inclusive)| {
+                    (Some(amount), inclusive)
+                });
 
-        Ok(Posting {
-            status,
-            account: account_name.trim().into(),
-            amount: complex_amount.amount,
-            unit_price: complex_amount.unit_price,
-            total_price: complex_amount.total_price,
-            balance_assertion: balance_assertion,
-        })
-    } else {
-        Ok(Posting {
-            status,
-            account: rest_of_line.trim().into(),
-            amount: None,
-            unit_price: None,
-            total_price: None,
-            balance_assertion: None,
-        })
+            let (kind, account) = PostingKind::split(account_name.trim());
+            Ok(Posting {
+                status,
+                account: account.into(),
+                kind,
+                amount: complex_amount.amount,
+                unit_price: complex_amount.unit_price,
+                total_price: complex_amount.total_price,
+                balance_assertion,
+                inclusive_assertion,
+            })
+        } else {
+            let (kind, account) = PostingKind::split(rest_of_line.trim());
+            Ok(Posting {
+                status,
+                account: account.into(),
+                kind,
+                amount: None,
+                unit_price: None,
+                total_price: None,
+                balance_assertion: None,
+                inclusive_assertion: false,
+            })
+        }
     }
 }
@@ -2,9 +2,15 @@ use rstest::rstest;
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
 
-use crate::{amount::types::Amount, status::types::Status};
+use winnow::Parser;
 
-use super::{parsers::parse_balance_assertion, parsers::parse_posting, types::Posting};
+use crate::{amount::types::Amount, journal::types::ParseState, status::types::Status};
+
+use super::{
+    parsers::parse_balance_assertion,
+    parsers::parse_posting,
+    types::{Posting, PostingKind},
+};
 
 #[rstest]
 #[case::simple(" assets:cash  $100", "", Status::Unmarked, "assets:cash", "$", dec!(100))]
@@ -24,6 +30,7 @@ fn test_parse_simple_posting(
         Posting {
             status: expected_status,
             account: expected_account.into(),
+            kind: PostingKind::Regular,
             amount: Some(Amount {
                 currency: expected_currency.into(),
                 value: expected_value,
@@ -31,11 +38,27 @@ fn test_parse_simple_posting(
             unit_price: None,
             total_price: None,
             balance_assertion: None,
+            inclusive_assertion: false,
         }
     );
     assert_eq!(input, expected_rest);
 }
 
+#[rstest]
+#[case::regular(" assets:cash  100 $", PostingKind::Regular, "assets:cash")]
+#[case::virtual_posting(" (budget:food)  100 $", PostingKind::Virtual, "budget:food")]
+#[case::balanced_virtual(" ! [assets:savings]", PostingKind::BalancedVirtual, "assets:savings")]
+fn test_parse_posting_kind(
+    #[case] input: &str,
+    #[case] expected_kind: PostingKind,
+    #[case] expected_account: &str,
+) {
+    let posting = parse_posting(&mut &input[..]).unwrap();
+    assert_eq!(posting.kind, expected_kind);
+    assert_eq!(posting.account.as_str(), expected_account);
+    assert_eq!(posting.to_string().trim(), input.trim());
+}
+
 #[test]
 fn test_correct_termination_parse_posting() {
     let mut input = " assets:cash\n2008/06/01 gift\n  assets:bank:checking  $1";
@@ -44,10 +67,12 @@ fn test_correct_termination_parse_posting() {
         Posting {
             status: Status::Unmarked,
             account: "assets:cash".into(),
+            kind: PostingKind::Regular,
             amount: None,
             unit_price: None,
             total_price: None,
             balance_assertion: None,
+            inclusive_assertion: false,
         }
     );
     assert_eq!(input, "\n2008/06/01 gift\n  assets:bank:checking  $1");
@@ -61,10 +86,12 @@ fn test_parse_posting_without_amount() {
         Posting {
             status: Status::Unmarked,
             account: "assets:cash".into(),
+            kind: PostingKind::Regular,
             amount: None,
             unit_price: None,
             total_price: None,
             balance_assertion: None,
+            inclusive_assertion: false,
         }
     );
     assert_eq!(input, "");
@@ -82,6 +109,7 @@ fn test_parse_posting_with_unit_price() {
         Posting {
             status: Status::Pending,
             account: "assets:cash".into(),
+            kind: PostingKind::Regular,
             amount: Some(Amount {
                 currency: "$".into(),
                 value: dec!(100)
@@ -92,6 +120,7 @@ fn test_parse_posting_with_unit_price() {
             }),
             total_price: None,
             balance_assertion: None,
+            inclusive_assertion: false,
         }
     )
 }
@@ -103,6 +132,7 @@ fn test_parse_posting_with_total_price() {
         Posting {
             status: Status::Pending,
             account: "assets:cash".into(),
+            kind: PostingKind::Regular,
             amount: Some(Amount {
                 currency: "$".into(),
                 value: dec!(100)
@@ -113,18 +143,26 @@ fn test_parse_posting_with_total_price() {
                 value: dec!(93.89),
             }),
             balance_assertion: None,
+            inclusive_assertion: false,
         }
     )
 }
 
-#[test]
-fn test_parse_balance_assertion() {
+#[rstest]
+#[case::exclusive(" = $100", false)]
+#[case::inclusive(" =* $100", true)]
+fn test_parse_balance_assertion(#[case] input: &str, #[case] inclusive: bool) {
     assert_eq!(
-        parse_balance_assertion(&mut " = $100").unwrap(),
-        Amount {
-            currency: "$".into(),
-            value: dec!(100)
-        }
+        parse_balance_assertion(&ParseState::default())
+            .parse_next(&mut &input[..])
+            .unwrap(),
+        (
+            Amount {
+                currency: "$".into(),
+                value: dec!(100)
+            },
+            inclusive
+        )
     )
 }
 
@@ -135,6 +173,7 @@ fn test_parse_posting_with_balance_assertion() {
         Posting {
             status: Status::Pending,
             account: "assets:cash".into(),
+            kind: PostingKind::Regular,
             amount: Some(Amount {
                 currency: "$".into(),
                 value: dec!(100)
@@ -148,6 +187,7 @@ fn test_parse_posting_with_balance_assertion() {
                 currency: "$".into(),
                 value: dec!(100)
             }),
+            inclusive_assertion: false,
         }
     )
 }
@@ -8,11 +8,12 @@ use crate::{amount::types::Amount, status::types::Status, Account};
 ///
 /// ```
 /// use rust_decimal_macros::dec;
-/// use hledger_parse::{Posting, Status, Amount};
+/// use hledger_parse::{Posting, PostingKind, Status, Amount};
 ///
 /// let posting = Posting {
 ///     status: Status::Pending,
 ///     account: "expenses:food".into(),
+///     kind: PostingKind::Regular,
 ///     amount: Some(Amount {
 ///         currency: "EUR".to_string(),
 ///         value: dec!(100)
@@ -20,11 +21,13 @@ use crate::{amount::types::Amount, status::types::Status, Account};
 ///     unit_price: None,
 ///     total_price: None,
 ///     balance_assertion: None,
+///     inclusive_assertion: false,
 /// };
 /// assert_eq!("  ! expenses:food  100 EUR", format!("{}", posting));
 /// let posting = Posting {
 ///     status: Status::Pending,
 ///     account: "expenses:food".into(),
+///     kind: PostingKind::Regular,
 ///     amount: Some(Amount {
 ///         currency: "EUR".to_string(),
 ///         value: dec!(100)
@@ -35,15 +38,20 @@ use crate::{amount::types::Amount, status::types::Status, Account};
 ///     }),
 ///     total_price: None,
 ///     balance_assertion: None,
+///     inclusive_assertion: false,
 /// };
 /// assert_eq!("  ! expenses:food  100 EUR @ 1.05 USD", format!("{}", posting));
 /// ```
-#[derive(PartialEq, Eq, Debug, Clone)]
+#[derive(PartialEq, Eq, Hash, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Posting {
     /// The status of the posting
     pub status: Status,
     /// The account of the posting
     pub account: Account,
+    /// Whether the posting is virtual, written with its account in parentheses or brackets
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub kind: PostingKind,
     /// The amount of the posting
     pub amount: Option<Amount>,
     /// The unit price of the posting
@@ -52,34 +60,106 @@ pub struct Posting {
     pub total_price: Option<Amount>,
     /// Optional balance assertion of the posting
     pub balance_assertion: Option<Amount>,
+    /// Whether the balance assertion includes the balances of the subaccounts, written `=*`
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub inclusive_assertion: bool,
+}
+
+/// The kind of a posting, which decides whether it must balance
+///
+/// # Example
+///
+/// ```
+/// use hledger_parse::PostingKind;
+///
+/// assert_eq!((PostingKind::Virtual, "budget:food"), PostingKind::split("(budget:food)"));
+/// assert_eq!((PostingKind::Regular, "assets:bank"), PostingKind::split("assets:bank"));
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum PostingKind {
+    /// A real posting, which balances with the other real postings of its transaction
+    #[default]
+    Regular,
+    /// A posting to an account in parentheses, which doesn't need to balance
+    Virtual,
+    /// A posting to an account in brackets, which balances with the other balanced virtual
+    /// postings of its transaction
+    BalancedVirtual,
+}
+
+impl PostingKind {
+    /// The kinds of postings which must balance, each on its own
+    pub(crate) const BALANCED: [PostingKind; 2] =
+        [PostingKind::Regular, PostingKind::BalancedVirtual];
+
+    /// Splits an account name as written in a posting into the kind and the account name
+    pub fn split(name: &str) -> (PostingKind, &str) {
+        let inner = |open, close| {
+            name.strip_prefix(open)
+                .and_then(|n: &str| n.strip_suffix(close))
+                .map(str::trim)
+        };
+        match (inner('(', ')'), inner('[', ']')) {
+            (Some(account), _) => (PostingKind::Virtual, account),
+            (None, Some(account)) => (PostingKind::BalancedVirtual, account),
+            (None, None) => (PostingKind::Regular, name),
+        }
+    }
 }
 
 impl Display for Posting {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let account = match self.kind {
+            PostingKind::Regular => self.account.to_string(),
+            PostingKind::Virtual => format!("({})", self.account),
+            PostingKind::BalancedVirtual => format!("[{}]", self.account),
+        };
         match (
             self.amount.as_ref(),
             self.unit_price.as_ref(),
             self.total_price.as_ref(),
         ) {
-            (None, None, None) => write!(f, "  {} {}", self.status, self.account),
+            (None, None, None) => write!(f, "  {} {}", self.status, account),
             (Some(amount), None, None) => {
-                write!(f, "  {} {}  {}", self.status, self.account, amount)
+                write!(f, "  {} {}  {}", self.status, account, amount)
             }
             (Some(amount), Some(unit_price), None) => write!(
                 f,
                 "  {} {}  {} @ {}",
-                self.status, self.account, amount, unit_price
+                self.status, account, amount, unit_price
             ),
             (Some(amount), None, Some(total_price)) => write!(
                 f,
                 "  {} {}  {} @@ {}",
-                self.status, self.account, amount, total_price
+                self.status, account, amount, total_price
             ),
             _ => unreachable!(),
         }
     }
 }
 
+impl Posting {
+    /// Returns the amount used to balance the transaction
+    ///
+    /// This is the amount converted with the unit or total price, if there is one.
+    pub fn cost(&self) -> Option<Amount> {
+        let amount = self.amount.as_ref()?;
+        match (self.unit_price.as_ref(), self.total_price.as_ref()) {
+            (Some(unit_price), _) => Some(Amount {
+                currency: unit_price.currency.clone(),
+                value: amount.value * unit_price.value,
+            }),
+            (None, Some(total_price)) => match amount.is_negative() {
+                false => Some(total_price.clone()),
+                true => Some(total_price.negate()),
+            },
+            (None, None) => Some(amount.clone()),
+        }
+    }
+}
+
 #[derive(Clone, Default)]
 pub struct PostingComplexAmount {
     pub amount: Option<Amount>,
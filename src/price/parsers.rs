@@ -5,22 +5,36 @@ use winnow::{
 };
 
 use crate::{
-    amount::parsers::{parse_amount, parse_currency_string},
-    date::parsers::parse_date,
+    amount::parsers::{parse_amount_with_state, parse_currency_string},
+    date::parsers::parse_date_with_year,
+    journal::types::ParseState,
 };
 
 use super::types::Price;
 
+/// Parses a price directive with the default journal parser state
+#[allow(dead_code)]
 pub fn parse_price(input: &mut &str) -> PResult<Price> {
-    let _ = terminated("P", space1).parse_next(input)?;
-    let (date, _) = terminated(parse_date, space1).parse_next(input)?;
-    let commodity = terminated(parse_currency_string, space1).parse_next(input)?;
-    let amount = parse_amount(input)?;
-    let _ = alt((line_ending, eof)).parse_next(input)?;
+    parse_price_with_state(&ParseState::default()).parse_next(input)
+}
+
+/// Parses a price directive, using the journal parser state for e.g. dates without a year
+pub fn parse_price_with_state<'s>(
+    state: &ParseState,
+) -> impl FnMut(&mut &'s str) -> PResult<Price> + '_ {
+    move |input: &mut &'s str| {
+        let _ = terminated("P", space1).parse_next(input)?;
+        let (date, _) =
+            terminated(parse_date_with_year(state.default_year), space1).parse_next(input)?;
+        let commodity = terminated(parse_currency_string, space1).parse_next(input)?;
+        let mut amount = parse_amount_with_state(state).parse_next(input)?;
+        state.apply_default_commodity(&mut amount);
+        let _ = alt((line_ending, eof)).parse_next(input)?;
 
-    Ok(Price {
-        commodity: commodity.into(),
-        date,
-        amount,
-    })
+        Ok(Price {
+            commodity: commodity.into(),
+            date,
+            amount,
+        })
+    }
 }
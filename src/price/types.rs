@@ -24,6 +24,7 @@ use crate::{amount::types::Amount, journal::types::Value, HLParserError};
 /// assert_eq!("P 2022-06-23 EUR 1.05 USD", format!("{}", price));
 /// ```
 #[derive(PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Price {
     /// The commodity of the price
     pub commodity: String,
@@ -0,0 +1,146 @@
+use std::path::PathBuf;
+
+use winnow::{
+    ascii::{line_ending, space0, till_line_ending},
+    combinator::{alt, eof, preceded, terminated},
+    token::none_of,
+    PResult, Parser,
+};
+
+use crate::{
+    journal::types::{Journal, Location},
+    utils::LineCounter,
+    HLParserError,
+};
+
+use super::types::{QifMapping, QifRecord, QifType};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum QifLine<'s> {
+    Header(&'s str),
+    Field(char, &'s str),
+    End,
+    Empty,
+}
+
+/// The kind of records of the current section of a QIF file
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Section {
+    Transactions(QifType),
+    Account,
+    Other,
+}
+
+fn parse_qif_line<'s>(input: &mut &'s str) -> PResult<QifLine<'s>> {
+    terminated(
+        alt((
+            preceded('!', till_line_ending).map(|h: &str| QifLine::Header(h.trim())),
+            preceded('^', till_line_ending).value(QifLine::End),
+            (none_of([' ', '\t', '\n', '\r']), till_line_ending)
+                .map(|(code, value)| QifLine::Field(code, value)),
+            space0.value(QifLine::Empty),
+        )),
+        alt((line_ending, eof)),
+    )
+    .parse_next(input)
+}
+
+/// Parses a QIF file into a journal
+///
+/// Transactions of `!Type:` sections belong to the account of the last `!Account` record, or
+/// to the account of the mapping. Category and class lists are ignored. `path` is the file the
+/// input was read from, if any, and is used in locations.
+///
+/// # Example
+///
+/// ```
+/// use hledger_parse::{parse_qif, QifMapping};
+///
+/// let mut input = r#"!Type:Bank
+/// D3/ 1'24
+/// T-45.20
+/// PGrocery Store
+/// LFood:Groceries
+/// ^
+/// "#;
+/// let mapping = QifMapping {
+///     currency: "USD".to_string(),
+///     ..QifMapping::default()
+/// };
+/// let journal = parse_qif(&mut input, &mapping, None).unwrap();
+/// assert_eq!(
+///     "2024-03-01 Grocery Store\n   assets:bank  -45.20 USD\n   Food:Groceries  45.20 USD\n",
+///     format!("{}", journal.transactions()[0])
+/// );
+/// ```
+pub fn parse_qif(
+    input: &mut &str,
+    mapping: &QifMapping,
+    path: Option<PathBuf>,
+) -> Result<Journal, HLParserError> {
+    let mut lines = LineCounter::new(input);
+    let mut section = Section::Other;
+    let mut account = mapping.account.clone();
+    let mut record = QifRecord::default();
+    let mut record_line = 1;
+    let mut transactions = vec![];
+    let mut locations = vec![];
+
+    while !input.is_empty() {
+        let line = lines.line(input);
+        let parsed = parse_qif_line
+            .parse_next(input)
+            .map_err(|e| HLParserError::Parse(format!("line {}: {}", line, e)))?;
+        match parsed {
+            QifLine::Header(header) => {
+                section = match header.strip_prefix("Type:") {
+                    Some(qif_type) => qif_type
+                        .parse()
+                        .map(Section::Transactions)
+                        .unwrap_or(Section::Other),
+                    None if header == "Account" => Section::Account,
+                    // Options like `!Option:AutoSwitch` keep the current section
+                    None => section,
+                };
+            }
+            QifLine::Field(code, value) => {
+                if record.fields.is_empty() {
+                    record_line = line;
+                }
+                record.fields.push((code, value.to_string()));
+            }
+            QifLine::End => {
+                match section {
+                    Section::Transactions(qif_type) => {
+                        transactions.push(
+                            mapping.transaction(qif_type, &account, &record).map_err(
+                                |e| match e {
+                                    HLParserError::Parse(e) => {
+                                        HLParserError::Parse(format!("line {}: {}", record_line, e))
+                                    }
+                                    e => e,
+                                },
+                            )?,
+                        );
+                        locations.push(Location {
+                            path: path.clone(),
+                            line: record_line,
+                        });
+                    }
+                    Section::Account => {
+                        if let Some(name) = record.value('N') {
+                            account = mapping.account(name);
+                        }
+                    }
+                    Section::Other => {}
+                }
+                record = QifRecord::default();
+            }
+            QifLine::Empty => {}
+        }
+    }
+
+    let mut journal = Journal::new(transactions, vec![], vec![], vec![]);
+    journal.set_transaction_locations(locations);
+    Ok(journal)
+}
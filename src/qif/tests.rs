@@ -0,0 +1,246 @@
+use std::path::PathBuf;
+
+use rstest::rstest;
+
+use crate::{
+    fingerprint::types::Fingerprint, journal::parsers::parse_journal,
+    transaction::types::Transaction, HLParserError,
+};
+
+use super::{
+    parsers::parse_qif,
+    types::{write_qif, QifMapping, QifType},
+};
+
+fn fixture(path: &str) -> String {
+    std::fs::read_to_string(
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/qif")
+            .join(path),
+    )
+    .unwrap()
+}
+
+fn mapping() -> QifMapping {
+    let mut mapping = QifMapping {
+        currency: "USD".to_string(),
+        ..QifMapping::default()
+    };
+    for (name, account) in [
+        ("Checking", "assets:bank:checking"),
+        ("Visa", "liabilities:visa"),
+        ("Brokerage", "assets:brokerage"),
+    ] {
+        mapping.accounts.insert(name.to_string(), account.into());
+    }
+    mapping
+        .categories
+        .insert("Food:Groceries".to_string(), "expenses:food".into());
+    mapping
+}
+
+fn print(input: &str, mapping: &QifMapping) -> String {
+    parse_qif(&mut &input[..], mapping, None)
+        .unwrap()
+        .transactions()
+        .iter()
+        .map(|t| t.to_string())
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+#[rstest]
+#[case::bank("Bank", QifType::Bank)]
+#[case::cash("Cash", QifType::Cash)]
+#[case::credit_card("CCard", QifType::CreditCard)]
+#[case::investment("Invst", QifType::Investment)]
+#[case::other_asset("Oth A", QifType::OtherAsset)]
+#[case::other_liability("Oth L", QifType::OtherLiability)]
+fn test_qif_type(#[case] name: &str, #[case] qif_type: QifType) {
+    assert_eq!(name.parse::<QifType>().unwrap(), qif_type);
+    assert_eq!(qif_type.to_string(), name);
+}
+
+#[test]
+fn test_parse_qif_accounts() {
+    assert_eq!(
+        print(&fixture("accounts.qif"), &mapping()),
+        r#"2024-03-01 * Landlord | March rent
+   assets:bank:checking  -1045.20 USD
+   Housing:Rent  1045.20 USD
+
+2024-03-02 Supermarket
+   assets:bank:checking  -100.00 USD
+   expenses:food  60.00 USD
+   Household  40.00 USD
+
+2024-03-05 * Card payment
+   assets:bank:checking  -250.00 USD
+   liabilities:visa  250.00 USD
+
+2024-03-04 Online Shop
+   liabilities:visa  -12.99 USD
+   expenses:unknown  12.99 USD
+"#
+    );
+}
+
+#[test]
+fn test_parse_qif_investments() {
+    assert_eq!(
+        print(&fixture("investments.qif"), &mapping()),
+        r#"2024-03-01 
+   assets:brokerage  10 ACME @@ 1500.00 USD
+   assets:bank:checking  -1500.00 USD
+
+2024-03-15 
+   assets:brokerage  12.50 USD
+   Investment Income:Dividends  -12.50 USD
+
+2024-03-20 
+   assets:brokerage  0.1 ACME @@ 12.50 USD
+   income:unknown  -12.50 USD
+
+2024-03-25 
+   assets:brokerage  -5 ACME @@ 800.00 USD
+   assets:brokerage  800.00 USD
+"#
+    );
+}
+
+#[rstest]
+#[case::month_first("D12/31/2024\nT1\n^\n", false, "2024-12-31")]
+#[case::day_first("D31/12/2024\nT1\n^\n", true, "2024-12-31")]
+#[case::apostrophe_year("D1/ 2'05\nT1\n^\n", false, "2005-01-02")]
+#[case::two_digit_year("D01/02/99\nT1\n^\n", false, "1999-01-02")]
+fn test_parse_qif_dates(#[case] record: &str, #[case] day_first: bool, #[case] expected: &str) {
+    let mapping = QifMapping {
+        day_first,
+        ..QifMapping::default()
+    };
+    let input = format!("!Type:Cash\n{}", record);
+    let journal = parse_qif(&mut &input[..], &mapping, None).unwrap();
+    assert_eq!(journal.transactions()[0].primary_date.to_string(), expected);
+}
+
+#[rstest]
+#[case::invalid_date("!Type:Bank\nD13/45/2024\nT1\n^\n", "line 2: Invalid QIF date")]
+#[case::missing_amount(
+    "!Type:Bank\nD03/01/2024\n^\n",
+    "line 2: QIF transaction without amount"
+)]
+#[case::unsupported_action(
+    "!Type:Invst\nD03/01/2024\nNStkSplit\nT0\n^\n",
+    "line 2: Unsupported QIF investment action: StkSplit"
+)]
+fn test_parse_qif_errors(#[case] input: &str, #[case] expected: &str) {
+    match parse_qif(&mut &input[..], &QifMapping::default(), None) {
+        Err(e @ HLParserError::Parse(_)) => assert!(e.to_string().contains(expected), "{}", e),
+        r => panic!("Unexpected result {:?}", r),
+    }
+}
+
+#[test]
+fn test_write_qif() {
+    let journal = parse_journal(
+        &mut r#"2024-03-01 * (1001) Landlord | March rent
+    expenses:housing:rent  1045.20 USD
+    assets:bank:checking
+
+2024-03-02 Supermarket
+    expenses:food  60.00 USD
+    expenses:household  40.00 USD
+    assets:bank:checking
+
+2024-03-03 Unrelated
+    expenses:food  5 USD
+    assets:cash
+
+2024-03-05 Card payment
+    liabilities:visa  250.00 USD
+    assets:bank:checking
+"#,
+        None,
+    )
+    .unwrap();
+    let qif = write_qif(&journal, &"assets:bank:checking".into(), QifType::Bank);
+
+    assert_eq!(
+        qif,
+        r#"!Type:Bank
+D03/01/2024
+T-1045.20
+C*
+N1001
+PLandlord
+MMarch rent
+Lexpenses:housing:rent
+^
+D03/02/2024
+T-100.00
+PSupermarket
+Sexpenses:food
+$-60.00
+Sexpenses:household
+$-40.00
+^
+D03/05/2024
+T-250.00
+PCard payment
+L[liabilities:visa]
+^
+"#
+    );
+
+    let mapping = QifMapping {
+        account: "assets:bank:checking".into(),
+        currency: "USD".to_string(),
+        ..QifMapping::default()
+    };
+    let fingerprints = |transactions: Vec<Transaction>| {
+        transactions
+            .iter()
+            .map(|t| Fingerprint::new(t, None))
+            .collect::<Vec<Fingerprint>>()
+    };
+    let mut expected = journal.transactions();
+    expected.remove(2);
+    assert_eq!(
+        fingerprints(
+            parse_qif(&mut &qif[..], &mapping, None)
+                .unwrap()
+                .transactions()
+        ),
+        fingerprints(expected)
+    );
+}
+
+#[test]
+fn test_write_qif_commodities() {
+    let journal = parse_journal(
+        &mut r#"2024-03-01 ! Exchange office
+    assets:wallet  -100.00 USD
+    assets:wallet  92.00 EUR
+    income:exchange  100.00 USD
+    expenses:exchange  -92.00 EUR
+"#,
+        None,
+    )
+    .unwrap();
+
+    assert_eq!(
+        write_qif(&journal, &"assets:wallet".into(), QifType::Cash),
+        r#"!Type:Cash
+D03/01/2024
+T92.00
+PExchange office
+Lexpenses:exchange
+^
+D03/01/2024
+T-100.00
+PExchange office
+Lincome:exchange
+^
+"#
+    );
+}
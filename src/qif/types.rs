@@ -0,0 +1,471 @@
+use std::{collections::BTreeMap, fmt::Display, str::FromStr};
+
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+
+use crate::{
+    account::types::{Account, AccountType},
+    amount::types::{Amount, MixedAmount},
+    description::types::Description,
+    journal::types::Journal,
+    posting::types::{Posting, PostingKind},
+    status::types::Status,
+    transaction::types::Transaction,
+    HLParserError,
+};
+
+/// The type of the transactions of a QIF section, e.g. `Bank` in `!Type:Bank`
+///
+/// # Example
+///
+/// ```
+/// use hledger_parse::QifType;
+///
+/// assert_eq!(QifType::CreditCard, "CCard".parse().unwrap());
+/// assert_eq!("Oth L", format!("{}", QifType::OtherLiability));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum QifType {
+    Bank,
+    Cash,
+    CreditCard,
+    Investment,
+    OtherAsset,
+    OtherLiability,
+}
+
+impl FromStr for QifType {
+    type Err = HLParserError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "Bank" => Ok(QifType::Bank),
+            "Cash" => Ok(QifType::Cash),
+            "CCard" => Ok(QifType::CreditCard),
+            "Invst" => Ok(QifType::Investment),
+            "Oth A" => Ok(QifType::OtherAsset),
+            "Oth L" => Ok(QifType::OtherLiability),
+            _ => Err(HLParserError::Parse(format!("Unknown QIF type: {}", s))),
+        }
+    }
+}
+
+impl Display for QifType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            QifType::Bank => write!(f, "Bank"),
+            QifType::Cash => write!(f, "Cash"),
+            QifType::CreditCard => write!(f, "CCard"),
+            QifType::Investment => write!(f, "Invst"),
+            QifType::OtherAsset => write!(f, "Oth A"),
+            QifType::OtherLiability => write!(f, "Oth L"),
+        }
+    }
+}
+
+/// A QIF record, the lines of a transaction up to `^`, as codes and values
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct QifRecord {
+    /// The fields of the record, e.g. `('T', "-45.20")`, in order
+    pub fields: Vec<(char, String)>,
+}
+
+/// A split of a QIF transaction, from its `S`, `E` and `$` fields
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct QifSplit {
+    category: Option<String>,
+    amount: Option<String>,
+}
+
+impl QifRecord {
+    /// Returns the first non-empty value of a field
+    pub fn value(&self, code: char) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(c, v)| *c == code && !v.trim().is_empty())
+            .map(|(_, v)| v.trim())
+    }
+
+    fn splits(&self) -> Vec<QifSplit> {
+        let mut splits: Vec<QifSplit> = vec![];
+        for (code, value) in &self.fields {
+            let value = Some(value.trim().to_string()).filter(|v| !v.is_empty());
+            match (code, splits.last_mut()) {
+                ('S', _) => splits.push(QifSplit {
+                    category: value,
+                    amount: None,
+                }),
+                ('$', Some(split)) => split.amount = value,
+                ('$', None) => splits.push(QifSplit {
+                    category: None,
+                    amount: value,
+                }),
+                _ => {}
+            }
+        }
+        splits
+    }
+}
+
+/// How the accounts and categories of QIF files are mapped to journal accounts
+///
+/// Categories and transfer accounts (written `[name]`) missing from the maps are used as account
+/// names as they are, and transactions without a category go to the income or expense account.
+///
+/// # Example
+///
+/// ```
+/// use hledger_parse::QifMapping;
+///
+/// let mut mapping = QifMapping::default();
+/// mapping.categories.insert("Food".to_string(), "expenses:food".into());
+/// assert_eq!("assets:bank", mapping.account.to_string());
+/// assert!(!mapping.day_first);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct QifMapping {
+    /// The journal account of the transactions of a file without `!Account` records
+    pub account: Account,
+    /// The journal account of each QIF account name, from `!Account` records and transfers
+    pub accounts: BTreeMap<String, Account>,
+    /// The journal account of each QIF category
+    pub categories: BTreeMap<String, Account>,
+    /// The account balancing money coming in without a category
+    pub income_account: Account,
+    /// The account balancing money going out without a category
+    pub expense_account: Account,
+    /// The commodity of the amounts
+    pub currency: String,
+    /// Whether dates are written day first, e.g. `31/12/2024`, instead of month first
+    pub day_first: bool,
+}
+
+impl Default for QifMapping {
+    fn default() -> Self {
+        Self {
+            account: "assets:bank".into(),
+            accounts: BTreeMap::new(),
+            categories: BTreeMap::new(),
+            income_account: "income:unknown".into(),
+            expense_account: "expenses:unknown".into(),
+            currency: String::new(),
+            day_first: false,
+        }
+    }
+}
+
+impl QifMapping {
+    /// Returns the journal account of a QIF account name
+    pub fn account(&self, name: &str) -> Account {
+        self.accounts
+            .get(name)
+            .cloned()
+            .unwrap_or_else(|| name.into())
+    }
+
+    /// Returns the journal account of a category or a `[transfer]` account, ignoring `/class`
+    fn category_account(&self, category: Option<&str>, amount: Decimal) -> Account {
+        let category = category
+            .map(|c| c.split('/').next().unwrap_or(c).trim())
+            .filter(|c| !c.is_empty());
+        match category {
+            Some(category) if category.starts_with('[') && category.ends_with(']') => {
+                self.account(&category[1..category.len() - 1])
+            }
+            Some(category) => self
+                .categories
+                .get(category)
+                .cloned()
+                .unwrap_or_else(|| category.into()),
+            None if amount.is_sign_negative() => self.expense_account.clone(),
+            None => self.income_account.clone(),
+        }
+    }
+
+    /// Parses a QIF date, e.g. `3/1/2024`, `03/01/24` or `3/ 1'24`
+    fn date(&self, value: &str) -> Result<NaiveDate, HLParserError> {
+        let error = || HLParserError::Parse(format!("Invalid QIF date: {}", value));
+        let parts: Vec<&str> = value.split(['/', '-', '.', '\'']).map(str::trim).collect();
+        let [first, second, year] = parts[..] else {
+            return Err(error());
+        };
+        let (month, day) = match self.day_first {
+            true => (second, first),
+            false => (first, second),
+        };
+        let year: i32 = year.parse().map_err(|_| error())?;
+        let year = match (year, value.contains('\'')) {
+            (0..=99, true) => 2000 + year,
+            (0..=99, false) => 1900 + year,
+            _ => year,
+        };
+        NaiveDate::from_ymd_opt(
+            year,
+            month.parse().map_err(|_| error())?,
+            day.parse().map_err(|_| error())?,
+        )
+        .ok_or_else(error)
+    }
+
+    fn amount(&self, value: &str) -> Result<Amount, HLParserError> {
+        Ok(Amount {
+            currency: self.currency.clone(),
+            value: Decimal::from_str(&value.replace([',', ' '], ""))
+                .map_err(|_| HLParserError::Parse(format!("Invalid QIF amount: {}", value)))?,
+        })
+    }
+
+    fn record_amount(&self, record: &QifRecord) -> Result<Amount, HLParserError> {
+        let value = record
+            .value('T')
+            .or_else(|| record.value('U'))
+            .ok_or_else(|| HLParserError::Parse("QIF transaction without amount".to_string()))?;
+        self.amount(value)
+    }
+
+    /// Converts a QIF record of a section to a transaction of the given account
+    ///
+    /// Bank-like records get a posting per split, or a single posting to their category.
+    /// Investment records support the buy, sell, reinvest and income actions, whose `X`
+    /// variants move the cash to the transfer account.
+    pub fn transaction(
+        &self,
+        qif_type: QifType,
+        account: &Account,
+        record: &QifRecord,
+    ) -> Result<Transaction, HLParserError> {
+        let date = record
+            .value('D')
+            .ok_or_else(|| HLParserError::Parse("QIF transaction without date".to_string()))?;
+        let payee = record.value('P');
+        let memo = record.value('M');
+        let postings = match qif_type {
+            QifType::Investment => self.investment_postings(account, record)?,
+            _ => self.bank_postings(account, record)?,
+        };
+
+        Ok(Transaction {
+            primary_date: self.date(date)?,
+            secondary_date: None,
+            status: match record.value('C') {
+                Some("*" | "c" | "X" | "R") => Status::Cleared,
+                _ => Status::Unmarked,
+            },
+            code: match qif_type {
+                QifType::Investment => None,
+                _ => record.value('N').map(str::to_string),
+            },
+            description: Description {
+                payee: payee.filter(|_| memo.is_some()).map(str::to_string),
+                note: memo.or(payee).map(str::to_string),
+            },
+            postings,
+            tags: vec![],
+        })
+    }
+
+    fn bank_postings(
+        &self,
+        account: &Account,
+        record: &QifRecord,
+    ) -> Result<Vec<Posting>, HLParserError> {
+        let amount = self.record_amount(record)?;
+        let mut postings = vec![posting(account.clone(), amount.clone(), None)];
+        let splits = record.splits();
+        if splits.is_empty() {
+            postings.push(posting(
+                self.category_account(record.value('L'), amount.value),
+                amount.negate(),
+                None,
+            ));
+        }
+        for split in splits {
+            let split_amount = match &split.amount {
+                Some(value) => self.amount(value)?,
+                None => amount.clone(),
+            };
+            postings.push(posting(
+                self.category_account(split.category.as_deref(), split_amount.value),
+                split_amount.negate(),
+                None,
+            ));
+        }
+        Ok(postings)
+    }
+
+    fn shares(&self, record: &QifRecord) -> Result<Amount, HLParserError> {
+        let security = record
+            .value('Y')
+            .ok_or_else(|| HLParserError::Parse("QIF investment without security".to_string()))?;
+        let quantity = record
+            .value('Q')
+            .ok_or_else(|| HLParserError::Parse("QIF investment without quantity".to_string()))?;
+        Ok(Amount {
+            currency: security.to_string(),
+            value: self.amount(quantity)?.value,
+        })
+    }
+
+    fn investment_postings(
+        &self,
+        account: &Account,
+        record: &QifRecord,
+    ) -> Result<Vec<Posting>, HLParserError> {
+        let action = record
+            .value('N')
+            .ok_or_else(|| HLParserError::Parse("QIF investment without action".to_string()))?;
+        let total = self.record_amount(record)?;
+        let cash_account = match action.ends_with('X') {
+            true => self.category_account(record.value('L'), total.value),
+            false => account.clone(),
+        };
+        Ok(match action {
+            "Buy" | "BuyX" => vec![
+                posting(account.clone(), self.shares(record)?, Some(total.clone())),
+                posting(cash_account, total.negate(), None),
+            ],
+            "Sell" | "SellX" => vec![
+                posting(
+                    account.clone(),
+                    self.shares(record)?.negate(),
+                    Some(total.clone()),
+                ),
+                posting(cash_account, total, None),
+            ],
+            "ReinvDiv" | "ReinvInt" | "ReinvLg" | "ReinvMd" | "ReinvSh" => vec![
+                posting(account.clone(), self.shares(record)?, Some(total.clone())),
+                posting(
+                    self.category_account(record.value('L'), total.value),
+                    total.negate(),
+                    None,
+                ),
+            ],
+            "Div" | "IntInc" | "CGLong" | "CGMid" | "CGShort" | "MiscInc" => vec![
+                posting(cash_account, total.clone(), None),
+                posting(
+                    self.category_account(record.value('L'), total.value),
+                    total.negate(),
+                    None,
+                ),
+            ],
+            "DivX" | "IntIncX" | "CGLongX" | "CGMidX" | "CGShortX" | "MiscIncX" => vec![
+                posting(cash_account, total.clone(), None),
+                posting(self.income_account.clone(), total.negate(), None),
+            ],
+            _ => {
+                return Err(HLParserError::Parse(format!(
+                    "Unsupported QIF investment action: {}",
+                    action
+                )))
+            }
+        })
+    }
+}
+
+fn posting(account: Account, amount: Amount, total_price: Option<Amount>) -> Posting {
+    Posting {
+        status: Status::Unmarked,
+        account,
+        kind: PostingKind::Regular,
+        amount: Some(amount),
+        unit_price: None,
+        total_price,
+        balance_assertion: None,
+        inclusive_assertion: false,
+    }
+}
+
+/// Writes the transactions of an account as a QIF section of the given type
+///
+/// Each transaction posting to the account becomes a record with the amount of the account, or
+/// one record per commodity if the account receives several of them. The other postings in that
+/// commodity become the category, or splits if there are several of them. Asset and liability
+/// accounts are written as `[transfers]`. Only cleared transactions are marked as cleared, since
+/// QIF has no pending status.
+///
+/// # Example
+///
+/// ```
+/// use hledger_parse::{parse_journal, write_qif, QifType};
+///
+/// let mut input = r#"2024-03-01 * Grocery Store | weekly
+///     expenses:food  45.20 USD
+///     assets:bank
+/// "#;
+/// let journal = parse_journal(&mut input, None).unwrap();
+/// assert_eq!(
+///     "!Type:Bank\nD03/01/2024\nT-45.20\nC*\nPGrocery Store\nMweekly\nLexpenses:food\n^\n",
+///     write_qif(&journal, &"assets:bank".into(), QifType::Bank)
+/// );
+/// ```
+pub fn write_qif(journal: &Journal, account: &Account, qif_type: QifType) -> String {
+    let mut output = format!("!Type:{}\n", qif_type);
+    for transaction in journal.transactions() {
+        let amounts = transaction.posting_amounts();
+        let mut total = MixedAmount::default();
+        let mut others = vec![];
+        for (posting, amount) in transaction.postings.iter().zip(amounts) {
+            match posting.account == *account {
+                true => total += &amount,
+                false => others.push((posting.account.clone(), amount)),
+            }
+        }
+        if others.len() == transaction.postings.len() {
+            continue;
+        }
+        let category = |account: &Account| match journal.account_type(account) {
+            Some(t) if t.is(AccountType::Asset) || t.is(AccountType::Liability) => {
+                format!("[{}]", account)
+            }
+            _ => account.to_string(),
+        };
+        for Amount { currency, value } in total.amounts() {
+            output.push_str(&format!(
+                "D{}\nT{}\n",
+                transaction.primary_date.format("%m/%d/%Y"),
+                value
+            ));
+            if transaction.status == Status::Cleared {
+                output.push_str("C*\n");
+            }
+            if let Some(code) = &transaction.code {
+                output.push_str(&format!("N{}\n", code));
+            }
+            let Description { payee, note } = &transaction.description;
+            match (payee, note) {
+                (Some(payee), Some(note)) => output.push_str(&format!("P{}\nM{}\n", payee, note)),
+                (Some(payee), None) | (None, Some(payee)) => {
+                    output.push_str(&format!("P{}\n", payee))
+                }
+                (None, None) => {}
+            }
+            let splits = others
+                .iter()
+                .filter(|(_, amount)| !amount.value(&currency).is_zero())
+                .collect::<Vec<_>>();
+            match &splits[..] {
+                [] => {
+                    if let [(other, _)] = &others[..] {
+                        output.push_str(&format!("L{}\n", category(other)))
+                    }
+                }
+                [(other, _)] => output.push_str(&format!("L{}\n", category(other))),
+                _ => {
+                    for (other, amount) in splits {
+                        output.push_str(&format!(
+                            "S{}\n${}\n",
+                            category(other),
+                            -amount.value(&currency)
+                        ));
+                    }
+                }
+            }
+            output.push_str("^\n");
+        }
+    }
+    output
+}
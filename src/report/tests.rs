@@ -0,0 +1,182 @@
+use chrono::NaiveDate;
+use rstest::rstest;
+use rust_decimal_macros::dec;
+
+use crate::{
+    account::types::AccountType, amount::types::Amount, journal::parsers::parse_journal,
+    journal::types::Journal,
+};
+
+use super::types::{Interval, Period, Statement};
+
+const JOURNAL: &str = r#"account assets:receivable  ; type: A
+account loans  ; type: L
+
+2024-01-01 opening
+    assets:bank:checking  1000 EUR
+    equity:opening
+
+2024-01-05 salary
+    assets:bank:checking  2000 EUR
+    revenues:salary
+
+2024-01-20 groceries
+    expenses:food  150 EUR
+    assets:bank:checking
+
+2024-02-03 loan
+    assets:bank:savings  500 EUR
+    loans:car
+
+2024-02-10 invoice
+    assets:receivable  300 EUR
+    income:consulting
+
+2024-02-15 rent
+    expenses:rent  800 EUR
+    assets:bank:checking
+"#;
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+fn journal() -> Journal {
+    let mut input = JOURNAL;
+    parse_journal(&mut input, None).unwrap()
+}
+
+fn eur(value: rust_decimal::Decimal) -> String {
+    format!(
+        "{}",
+        Amount {
+            currency: "EUR".into(),
+            value
+        }
+    )
+}
+
+#[rstest]
+#[case::monthly(date(2024, 1, 15), date(2024, 4, 1), Interval::Monthly, vec!["2024-01", "2024-02", "2024-03"])]
+#[case::quarterly(date(2024, 2, 1), date(2024, 7, 1), Interval::Quarterly, vec!["2024Q1", "2024Q2"])]
+#[case::yearly(date(2023, 6, 1), date(2024, 6, 1), Interval::Yearly, vec!["2023", "2024"])]
+fn test_period_split(
+    #[case] start: NaiveDate,
+    #[case] end: NaiveDate,
+    #[case] interval: Interval,
+    #[case] expected: Vec<&str>,
+) {
+    assert_eq!(
+        Period::split(start, end, interval)
+            .iter()
+            .map(|p| format!("{}", p))
+            .collect::<Vec<String>>(),
+        expected
+    );
+}
+
+#[test]
+fn test_period_display_custom() {
+    assert_eq!(
+        format!("{}", Period::new(date(2024, 1, 3), date(2024, 1, 10))),
+        "2024-01-03..2024-01-10"
+    );
+}
+
+#[test]
+fn test_journal_account_types() {
+    let journal = journal();
+    assert_eq!(
+        journal.account_type(&"assets:receivable:acme".into()),
+        Some(AccountType::Asset)
+    );
+    assert_eq!(
+        journal.account_type(&"loans:car".into()),
+        Some(AccountType::Liability)
+    );
+    assert_eq!(
+        journal.account_type(&"assets:bank:savings".into()),
+        Some(AccountType::Cash)
+    );
+    assert_eq!(journal.account_type(&"misc".into()), None);
+}
+
+#[test]
+fn test_balance_sheet() {
+    let periods = Period::split(date(2024, 1, 1), date(2024, 3, 1), Interval::Monthly);
+    let statement = Statement::balance_sheet(&journal(), &periods);
+
+    assert_eq!(statement.sections.len(), 2);
+    let assets = &statement.sections[0];
+    assert_eq!(
+        assets
+            .rows
+            .iter()
+            .map(|r| r.account.to_string())
+            .collect::<Vec<String>>(),
+        vec![
+            "assets:bank:checking",
+            "assets:bank:savings",
+            "assets:receivable"
+        ]
+    );
+    assert_eq!(format!("{}", assets.rows[0].amounts[0]), eur(dec!(2850)));
+    assert_eq!(format!("{}", assets.rows[0].amounts[1]), eur(dec!(2050)));
+    assert_eq!(format!("{}", assets.totals[1]), eur(dec!(2850)));
+
+    let liabilities = &statement.sections[1];
+    assert!(liabilities.totals[0].is_zero());
+    assert_eq!(format!("{}", liabilities.totals[1]), eur(dec!(500)));
+
+    assert_eq!(format!("{}", statement.net[0]), eur(dec!(2850)));
+    assert_eq!(format!("{}", statement.net[1]), eur(dec!(2350)));
+}
+
+#[test]
+fn test_income_statement() {
+    let periods = Period::split(date(2024, 1, 1), date(2024, 3, 1), Interval::Monthly);
+    let statement = Statement::income_statement(&journal(), &periods);
+
+    let revenues = &statement.sections[0];
+    assert_eq!(format!("{}", revenues.totals[0]), eur(dec!(2000)));
+    assert_eq!(format!("{}", revenues.totals[1]), eur(dec!(300)));
+
+    let expenses = &statement.sections[1];
+    assert_eq!(format!("{}", expenses.totals[0]), eur(dec!(150)));
+    assert_eq!(format!("{}", expenses.totals[1]), eur(dec!(800)));
+
+    assert_eq!(format!("{}", statement.net[0]), eur(dec!(1850)));
+    assert_eq!(format!("{}", statement.net[1]), eur(dec!(-500)));
+}
+
+#[test]
+fn test_cash_flow() {
+    let periods = Period::split(date(2024, 1, 1), date(2024, 3, 1), Interval::Monthly);
+    let statement = Statement::cash_flow(&journal(), &periods);
+
+    assert_eq!(statement.sections.len(), 1);
+    assert_eq!(format!("{}", statement.net[0]), eur(dec!(2850)));
+    assert_eq!(format!("{}", statement.net[1]), eur(dec!(-300)));
+}
+
+#[test]
+fn test_statement_display() {
+    let periods = Period::split(date(2024, 1, 1), date(2024, 3, 1), Interval::Monthly);
+    let statement = Statement::cash_flow(&journal(), &periods);
+    assert_eq!(
+        format!("{}", statement),
+        r#"Cashflow Statement
+
+                      ||  2024-01   2024-02
+----------------------++--------------------
+ Cash flows           ||
+----------------------++--------------------
+ assets:bank:checking || 2850 EUR  -800 EUR
+ assets:bank:savings  ||        0   500 EUR
+----------------------++--------------------
+                      || 2850 EUR  -300 EUR
+----------------------++--------------------
+ Net:                 || 2850 EUR  -300 EUR
+"#
+    );
+}
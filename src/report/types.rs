@@ -0,0 +1,409 @@
+use std::{collections::BTreeMap, fmt::Display};
+
+use chrono::{Datelike, Months, NaiveDate};
+
+use crate::{
+    account::types::{Account, AccountType},
+    amount::types::MixedAmount,
+    journal::types::Journal,
+};
+
+/// Length of the periods a report is split into
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Interval {
+    Monthly,
+    Quarterly,
+    Yearly,
+}
+
+impl Interval {
+    fn months(&self) -> u32 {
+        match self {
+            Interval::Monthly => 1,
+            Interval::Quarterly => 3,
+            Interval::Yearly => 12,
+        }
+    }
+}
+
+/// A date range, including the start date and excluding the end date
+///
+/// # Example
+///
+/// ```
+/// use chrono::NaiveDate;
+/// use hledger_parse::{Interval, Period};
+///
+/// let periods = Period::split(
+///     NaiveDate::from_ymd_opt(2024, 1, 15).unwrap(),
+///     NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(),
+///     Interval::Monthly,
+/// );
+/// assert_eq!(
+///     vec!["2024-01", "2024-02"],
+///     periods.iter().map(|p| format!("{}", p)).collect::<Vec<String>>()
+/// );
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Period {
+    /// The first date of the period
+    pub start: NaiveDate,
+    /// The first date after the period
+    pub end: NaiveDate,
+}
+
+impl Period {
+    pub fn new(start: NaiveDate, end: NaiveDate) -> Period {
+        Period { start, end }
+    }
+
+    /// Returns true if the date falls inside the period
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        self.start <= date && date < self.end
+    }
+
+    /// Splits the dates from `start` to `end` into calendar periods of the given interval
+    ///
+    /// The first period starts at the beginning of the interval containing `start`.
+    pub fn split(start: NaiveDate, end: NaiveDate, interval: Interval) -> Vec<Period> {
+        let months = interval.months();
+        let first_month = start.month0() / months * months + 1;
+        let mut period_start = NaiveDate::from_ymd_opt(start.year(), first_month, 1)
+            .expect("first day of a month should be a valid date");
+        let mut periods = vec![];
+        while period_start < end {
+            let period_end = period_start + Months::new(months);
+            periods.push(Period::new(period_start, period_end));
+            period_start = period_end;
+        }
+        periods
+    }
+}
+
+impl Display for Period {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let is_month_start = self.start.day() == 1 && self.end.day() == 1;
+        let months = (self.end.year() - self.start.year()) * 12 + self.end.month() as i32
+            - self.start.month() as i32;
+        match (is_month_start, months, self.start.month0()) {
+            (true, 1, _) => write!(f, "{}", self.start.format("%Y-%m")),
+            (true, 3, m) if m % 3 == 0 => write!(f, "{}Q{}", self.start.year(), m / 3 + 1),
+            (true, 12, 0) => write!(f, "{}", self.start.year()),
+            _ => write!(f, "{}..{}", self.start, self.end),
+        }
+    }
+}
+
+/// The amounts of an account in each period of a report
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StatementRow {
+    /// The account of the row
+    pub account: Account,
+    /// The amounts of the account, one per period
+    pub amounts: Vec<MixedAmount>,
+}
+
+/// A group of accounts of a financial statement, e.g. the assets of a balance sheet
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StatementSection {
+    /// The title of the section
+    pub title: String,
+    /// The rows of the section, ordered by account name
+    pub rows: Vec<StatementRow>,
+    /// The totals of the section, one per period
+    pub totals: Vec<MixedAmount>,
+}
+
+/// A financial statement, with one column per period
+///
+/// Amounts of liability, equity and revenue accounts are shown with their sign flipped, so that
+/// they are usually positive, like in hledger.
+///
+/// # Example
+///
+/// ```
+/// use chrono::NaiveDate;
+/// use hledger_parse::{parse_journal, Period, Statement};
+///
+/// let mut input = r#"2024-01-05 salary
+///     assets:bank:checking  1000 EUR
+///     revenues:salary
+///
+/// 2024-01-20 groceries
+///     expenses:food  100 EUR
+///     assets:bank:checking
+/// "#;
+/// let journal = parse_journal(&mut input, None).unwrap();
+/// let periods = vec![Period::new(
+///     NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+///     NaiveDate::from_ymd_opt(2024, 2, 1).unwrap(),
+/// )];
+/// let statement = Statement::income_statement(&journal, &periods);
+/// assert_eq!("900 EUR", format!("{}", statement.net[0]));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Statement {
+    /// The title of the statement
+    pub title: String,
+    /// The periods of the statement, one per column
+    pub periods: Vec<Period>,
+    /// The sections of the statement
+    pub sections: Vec<StatementSection>,
+    /// The net result of all sections, one per period
+    pub net: Vec<MixedAmount>,
+}
+
+/// Which postings are summed in each period of a statement
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Accumulation {
+    /// Postings inside the period, e.g. for income statements
+    Change,
+    /// Postings before the end of the period, e.g. for balance sheets
+    Historical,
+}
+
+impl Statement {
+    /// Builds a balance sheet, the equivalent of `hledger balancesheet`
+    ///
+    /// Shows the asset and liability balances at the end of each period.
+    pub fn balance_sheet(journal: &Journal, periods: &[Period]) -> Statement {
+        let sections = vec![
+            build_section(
+                journal,
+                periods,
+                "Assets",
+                AccountType::Asset,
+                Accumulation::Historical,
+                false,
+            ),
+            build_section(
+                journal,
+                periods,
+                "Liabilities",
+                AccountType::Liability,
+                Accumulation::Historical,
+                true,
+            ),
+        ];
+        let net = net_totals(&sections[0].totals, &sections[1].totals);
+        Statement {
+            title: "Balance Sheet".to_string(),
+            periods: periods.to_vec(),
+            sections,
+            net,
+        }
+    }
+
+    /// Builds an income statement, the equivalent of `hledger incomestatement`
+    ///
+    /// Shows the revenues and expenses of each period.
+    pub fn income_statement(journal: &Journal, periods: &[Period]) -> Statement {
+        let sections = vec![
+            build_section(
+                journal,
+                periods,
+                "Revenues",
+                AccountType::Revenue,
+                Accumulation::Change,
+                true,
+            ),
+            build_section(
+                journal,
+                periods,
+                "Expenses",
+                AccountType::Expense,
+                Accumulation::Change,
+                false,
+            ),
+        ];
+        let net = net_totals(&sections[0].totals, &sections[1].totals);
+        Statement {
+            title: "Income Statement".to_string(),
+            periods: periods.to_vec(),
+            sections,
+            net,
+        }
+    }
+
+    /// Builds a cash flow statement, the equivalent of `hledger cashflow`
+    ///
+    /// Shows the changes of the cash accounts in each period.
+    pub fn cash_flow(journal: &Journal, periods: &[Period]) -> Statement {
+        let section = build_section(
+            journal,
+            periods,
+            "Cash flows",
+            AccountType::Cash,
+            Accumulation::Change,
+            false,
+        );
+        let net = section.totals.clone();
+        Statement {
+            title: "Cashflow Statement".to_string(),
+            periods: periods.to_vec(),
+            sections: vec![section],
+            net,
+        }
+    }
+}
+
+fn build_section(
+    journal: &Journal,
+    periods: &[Period],
+    title: &str,
+    account_type: AccountType,
+    accumulation: Accumulation,
+    flip_sign: bool,
+) -> StatementSection {
+    let mut accounts: BTreeMap<Account, Vec<MixedAmount>> = BTreeMap::new();
+
+    for transaction in journal.transactions() {
+        let date = transaction.primary_date;
+        let in_periods: Vec<bool> = periods
+            .iter()
+            .map(|p| match accumulation {
+                Accumulation::Change => p.contains(date),
+                Accumulation::Historical => date < p.end,
+            })
+            .collect();
+        if !in_periods.contains(&true) {
+            continue;
+        }
+
+        for (posting, amount) in transaction
+            .postings
+            .iter()
+            .zip(transaction.posting_amounts())
+        {
+            if !journal
+                .account_type(&posting.account)
+                .is_some_and(|t| t.is(account_type))
+            {
+                continue;
+            }
+            let amount = match flip_sign {
+                true => amount.negate(),
+                false => amount,
+            };
+            let row = accounts
+                .entry(posting.account.clone())
+                .or_insert_with(|| vec![MixedAmount::default(); periods.len()]);
+            for (column, _) in in_periods.iter().enumerate().filter(|(_, v)| **v) {
+                row[column] += &amount;
+            }
+        }
+    }
+
+    let rows: Vec<StatementRow> = accounts
+        .into_iter()
+        .filter(|(_, amounts)| amounts.iter().any(|a| !a.is_zero()))
+        .map(|(account, amounts)| StatementRow { account, amounts })
+        .collect();
+    let totals = (0..periods.len())
+        .map(|column| rows.iter().map(|r| &r.amounts[column]).sum())
+        .collect();
+
+    StatementSection {
+        title: title.to_string(),
+        rows,
+        totals,
+    }
+}
+
+fn net_totals(first: &[MixedAmount], second: &[MixedAmount]) -> Vec<MixedAmount> {
+    first
+        .iter()
+        .zip(second)
+        .map(|(a, b)| {
+            let mut net = a.clone();
+            net += &b.negate();
+            net
+        })
+        .collect()
+}
+
+impl Display for Statement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut lines: Vec<Option<(String, Vec<String>)>> = vec![];
+        for section in &self.sections {
+            lines.push(None);
+            lines.push(Some((section.title.clone(), vec![])));
+            lines.push(None);
+            for row in &section.rows {
+                lines.push(Some((
+                    row.account.to_string(),
+                    row.amounts.iter().map(|a| format!("{}", a)).collect(),
+                )));
+            }
+            lines.push(None);
+            lines.push(Some((
+                String::new(),
+                section.totals.iter().map(|a| format!("{}", a)).collect(),
+            )));
+        }
+        lines.push(None);
+        lines.push(Some((
+            "Net:".to_string(),
+            self.net.iter().map(|a| format!("{}", a)).collect(),
+        )));
+
+        let header: Vec<String> = self.periods.iter().map(|p| format!("{}", p)).collect();
+        let label_width = lines
+            .iter()
+            .flatten()
+            .map(|(label, _)| label.chars().count())
+            .max()
+            .unwrap_or_default();
+        let column_widths: Vec<usize> = header
+            .iter()
+            .enumerate()
+            .map(|(column, h)| {
+                lines
+                    .iter()
+                    .flatten()
+                    .filter_map(|(_, amounts)| amounts.get(column))
+                    .chain([h])
+                    .map(|v| v.chars().count())
+                    .max()
+                    .unwrap_or_default()
+            })
+            .collect();
+        let table_width = column_widths.iter().map(|w| w + 2).sum::<usize>();
+
+        let write_line = |f: &mut std::fmt::Formatter<'_>, label: &str, values: &[String]| {
+            let line = values
+                .iter()
+                .zip(&column_widths)
+                .map(|(value, width)| format!(" {:>width$} ", value))
+                .collect::<String>();
+            writeln!(
+                f,
+                "{}",
+                format!(" {:<label_width$} ||{}", label, line).trim_end()
+            )
+        };
+
+        writeln!(f, "{}", self.title)?;
+        writeln!(f)?;
+        write_line(f, "", &header)?;
+        for line in lines {
+            match line {
+                Some((label, values)) => write_line(f, &label, &values)?,
+                None => writeln!(
+                    f,
+                    "{}++{}",
+                    "-".repeat(label_width + 2),
+                    "-".repeat(table_width)
+                )?,
+            }
+        }
+
+        Ok(())
+    }
+}
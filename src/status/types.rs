@@ -11,7 +11,9 @@ use std::fmt::Display;
 /// assert_eq!("!", format!("{}", Status::Pending));
 /// assert_eq!("*", format!("{}", Status::Cleared));
 /// ```
-#[derive(PartialEq, Eq, Debug, Clone)]
+#[derive(PartialEq, Eq, Hash, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum Status {
     /// The status has not been specified
     Unmarked,
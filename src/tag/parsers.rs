@@ -1,6 +1,7 @@
 use winnow::{
-    combinator::{alt, terminated},
-    token::take_while,
+    ascii::{space1, till_line_ending},
+    combinator::{alt, preceded, terminated},
+    token::{rest, take_until, take_while},
     PResult, Parser,
 };
 
@@ -26,3 +27,14 @@ pub fn parse_tag(input: &mut &str) -> PResult<Tag> {
         value: value.map(str::trim).map(str::to_string),
     })
 }
+
+pub fn parse_tag_directive<'s>(input: &mut &'s str) -> PResult<&'s str> {
+    preceded(
+        ("tag", space1),
+        till_line_ending.and_then(alt((take_until(1.., "  "), rest))),
+    )
+    .map(str::trim)
+    .verify(|tag: &str| !tag.is_empty() && !tag.contains([' ', '\t', ':']))
+    .context(winnow::error::StrContext::Label("tag directive"))
+    .parse_next(input)
+}
@@ -1,7 +1,10 @@
 use rstest::rstest;
 use winnow::error::{ContextError, ErrMode};
 
-use super::{parsers::parse_tag, types::Tag};
+use super::{
+    parsers::{parse_tag, parse_tag_directive},
+    types::Tag,
+};
 
 #[rstest]
 #[case("not a tag:")]
@@ -53,3 +56,22 @@ fn test_parse_tag_with_value(
         }
     )
 }
+
+#[rstest]
+#[case::simple("tag id", "id")]
+#[case::comment("tag id  ; bank transaction id", "id")]
+#[case::unicode("tag кеш", "кеш")]
+fn test_parse_tag_directive(#[case] input: &str, #[case] expected: &str) {
+    let mut input = input;
+    assert_eq!(parse_tag_directive(&mut input).unwrap(), expected);
+    assert_eq!(input, "");
+}
+
+#[rstest]
+#[case::empty("tag ")]
+#[case::with_space("tag two words")]
+#[case::with_colon("tag id:")]
+fn test_parse_invalid_tag_directive(#[case] input: &str) {
+    let mut input = input;
+    assert!(parse_tag_directive(&mut input).is_err());
+}
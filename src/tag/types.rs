@@ -12,7 +12,8 @@ use std::fmt::Display;
 /// let tag = Tag { name: "tag1".to_string(), value: Some("some value".to_string()) };
 /// assert_eq!("tag1:some value", format!("{}", tag));
 /// ```
-#[derive(PartialEq, Eq, Debug, Clone)]
+#[derive(PartialEq, Eq, Hash, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Tag {
     /// The name of the tag
     pub name: String,
@@ -0,0 +1,166 @@
+use std::path::PathBuf;
+
+use chrono::{NaiveDateTime, NaiveTime};
+use winnow::{
+    ascii::{line_ending, space0, space1, till_line_ending},
+    combinator::{alt, eof, opt, preceded, terminated},
+    error::{ContextError, ErrMode},
+    stream::AsChar,
+    token::take_while,
+    PResult, Parser,
+};
+
+use crate::{
+    comment::parsers::parse_line_comment,
+    date::parsers::parse_date,
+    journal::types::{Journal, Location, ParseState, Value},
+    transaction::types::Transaction,
+    utils::LineCounter,
+    HLParserError,
+};
+
+use super::types::{timeclock_transactions, TimeclockCode, TimeclockEntry};
+
+fn parse_two_digits(input: &mut &str) -> PResult<u32> {
+    take_while(2, AsChar::is_dec_digit)
+        .parse_to()
+        .parse_next(input)
+}
+
+/// Parses a time of the form `HH:MM:SS` or `HH:MM`
+pub fn parse_time(input: &mut &str) -> PResult<NaiveTime> {
+    (
+        parse_two_digits,
+        preceded(':', parse_two_digits),
+        opt(preceded(':', parse_two_digits)),
+    )
+        .verify_map(|(hour, minute, second)| {
+            NaiveTime::from_hms_opt(hour, minute, second.unwrap_or(0))
+        })
+        .parse_next(input)
+}
+
+/// Parses a timeclock line, e.g. `i 2024-03-01 09:00:00 client:acme  task`
+///
+/// Clock-ins need an account, which ends at two spaces and may be followed by a description.
+/// Anything after the time of a clock-out is ignored.
+pub fn parse_timeclock_entry(input: &mut &str) -> PResult<TimeclockEntry> {
+    let code = terminated(
+        alt((
+            'i'.value(TimeclockCode::ClockIn),
+            'o'.value(TimeclockCode::ClockOut),
+            'O'.value(TimeclockCode::FinalClockOut),
+        )),
+        space1,
+    )
+    .parse_next(input)?;
+    let (date, _) = terminated(parse_date, space1).parse_next(input)?;
+    let time = parse_time(input)?;
+    let rest = terminated(till_line_ending, alt((line_ending, eof)))
+        .map(str::trim)
+        .parse_next(input)?;
+
+    let (account, description) = match code {
+        TimeclockCode::ClockIn => {
+            let (account, description) = rest.split_once("  ").unwrap_or((rest, ""));
+            let account = account.trim();
+            if account.is_empty() {
+                return Err(ErrMode::Backtrack(ContextError::new()));
+            }
+            let description = description.trim();
+            (
+                Some(account.into()),
+                (!description.is_empty()).then(|| description.to_string()),
+            )
+        }
+        _ => (None, None),
+    };
+
+    Ok(TimeclockEntry {
+        code,
+        datetime: NaiveDateTime::new(date, time),
+        account,
+        description,
+    })
+}
+
+fn parse_ignored_line(input: &mut &str) -> PResult<()> {
+    alt((
+        terminated(parse_line_comment, alt((line_ending, eof))).void(),
+        terminated(space0, alt((line_ending, eof))).void(),
+    ))
+    .parse_next(input)
+}
+
+/// Parses the contents of a timeclock file into transactions with their locations
+fn parse_timeclock_contents(
+    input: &mut &str,
+    path: Option<PathBuf>,
+) -> Result<Vec<(Transaction, Location)>, HLParserError> {
+    let mut lines = LineCounter::new(input);
+    let mut entries = vec![];
+    while !input.is_empty() {
+        let line = lines.line(input);
+        let entry = alt((
+            parse_timeclock_entry.map(Some),
+            parse_ignored_line.value(None),
+        ))
+        .parse_next(input)
+        .map_err(|e| HLParserError::Parse(format!("line {}: {}", line, e)))?;
+        if let Some(entry) = entry {
+            entries.push((
+                entry,
+                Location {
+                    path: path.clone(),
+                    line,
+                },
+            ));
+        }
+    }
+    timeclock_transactions(&entries)
+}
+
+/// Parses a timeclock file into a journal of its sessions
+///
+/// `path` is the file the input was read from, if any, and is used in locations.
+///
+/// # Example
+///
+/// ```
+/// use hledger_parse::parse_timeclock;
+///
+/// let mut input = r#"i 2024-03-01 09:00:00 client:acme  task
+/// o 2024-03-01 12:30:00
+/// "#;
+/// let journal = parse_timeclock(&mut input, None).unwrap();
+/// assert_eq!(
+///     "2024-03-01 task\n   (client:acme)  3.50 h\n",
+///     format!("{}", journal.transactions()[0])
+/// );
+/// ```
+pub fn parse_timeclock(input: &mut &str, path: Option<PathBuf>) -> Result<Journal, HLParserError> {
+    let (transactions, locations): (Vec<_>, Vec<_>) =
+        parse_timeclock_contents(input, path)?.into_iter().unzip();
+    let mut journal = Journal::new(transactions, vec![], vec![], vec![]);
+    journal.set_transaction_locations(locations);
+    Ok(journal)
+}
+
+/// Reads a timeclock file included by a journal
+///
+/// The journal parser state, e.g. account aliases, is applied to the transactions.
+pub fn read_timeclock_from_path(
+    path: PathBuf,
+    state: &ParseState,
+    locations: &mut Vec<Location>,
+) -> Result<Vec<Value>, HLParserError> {
+    let contents = std::fs::read_to_string(&path).map_err(|e| HLParserError::IO(e.to_string()))?;
+    let mut input = &contents[..];
+    Ok(parse_timeclock_contents(&mut input, Some(path))?
+        .into_iter()
+        .map(|(transaction, location)| {
+            locations.push(location);
+            Value::Transaction(state.apply_to_transaction(transaction))
+        })
+        .collect())
+}
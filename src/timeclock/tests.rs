@@ -0,0 +1,225 @@
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use rstest::rstest;
+use rust_decimal_macros::dec;
+
+use crate::{
+    check::types::Check,
+    journal::{parsers::parse_journal, types::Location},
+    posting::types::PostingKind,
+    status::types::Status,
+    HLParserError, ValidationError,
+};
+
+use super::{
+    parsers::{parse_time, parse_timeclock, parse_timeclock_entry},
+    types::{TimeclockCode, TimeclockEntry},
+};
+
+fn datetime(d: u32, h: u32, m: u32, s: u32) -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(2024, 3, d)
+        .unwrap()
+        .and_hms_opt(h, m, s)
+        .unwrap()
+}
+
+#[rstest]
+#[case::seconds("09:05:30", NaiveTime::from_hms_opt(9, 5, 30))]
+#[case::minutes("17:45", NaiveTime::from_hms_opt(17, 45, 0))]
+#[case::invalid_hour("25:00", None)]
+fn test_parse_time(#[case] input: &str, #[case] expected: Option<NaiveTime>) {
+    let mut input = input;
+    assert_eq!(parse_time(&mut input).ok(), expected);
+}
+
+#[rstest]
+#[case::clock_in(
+    "i 2024-03-01 09:00:00 client:acme  task\nrest",
+    TimeclockCode::ClockIn,
+    datetime(1, 9, 0, 0),
+    Some("client:acme"),
+    Some("task")
+)]
+#[case::clock_in_without_description(
+    "i 2024-03-01 09:00 client:acme",
+    TimeclockCode::ClockIn,
+    datetime(1, 9, 0, 0),
+    Some("client:acme"),
+    None
+)]
+#[case::clock_out(
+    "o 2024-03-01 12:30:00\nrest",
+    TimeclockCode::ClockOut,
+    datetime(1, 12, 30, 0),
+    None,
+    None
+)]
+#[case::final_clock_out(
+    "O 2024/03/01 12:30:00 done",
+    TimeclockCode::FinalClockOut,
+    datetime(1, 12, 30, 0),
+    None,
+    None
+)]
+fn test_parse_timeclock_entry(
+    #[case] input: &str,
+    #[case] code: TimeclockCode,
+    #[case] datetime: NaiveDateTime,
+    #[case] account: Option<&str>,
+    #[case] description: Option<&str>,
+) {
+    let mut input = input;
+    assert_eq!(
+        parse_timeclock_entry(&mut input).unwrap(),
+        TimeclockEntry {
+            code,
+            datetime,
+            account: account.map(|a| a.into()),
+            description: description.map(str::to_string),
+        }
+    );
+    assert!(input.is_empty() || input == "rest");
+}
+
+#[test]
+fn test_parse_timeclock_entry_clock_in_without_account() {
+    let mut input = "i 2024-03-01 09:00:00\n";
+    assert!(parse_timeclock_entry(&mut input).is_err());
+}
+
+#[test]
+fn test_parse_timeclock() {
+    let mut input = r#"; billable hours
+i 2024-03-01 09:00:00 client:acme  task
+o 2024-03-01 12:30:00
+
+i 2024-03-01 22:00:00 client:globex  deployment
+O 2024-03-02 01:15:00
+"#;
+    let journal = parse_timeclock(&mut input, None).unwrap();
+    let transactions = journal.transactions();
+
+    assert_eq!(
+        transactions
+            .iter()
+            .map(|t| (
+                t.primary_date.to_string(),
+                t.postings[0].account.to_string(),
+                t.postings[0].amount.as_ref().unwrap().value,
+                t.status.clone()
+            ))
+            .collect::<Vec<_>>(),
+        vec![
+            (
+                "2024-03-01".to_string(),
+                "client:acme".to_string(),
+                dec!(3.5),
+                Status::Unmarked
+            ),
+            (
+                "2024-03-01".to_string(),
+                "client:globex".to_string(),
+                dec!(2),
+                Status::Cleared
+            ),
+            (
+                "2024-03-02".to_string(),
+                "client:globex".to_string(),
+                dec!(1.25),
+                Status::Cleared
+            ),
+        ]
+    );
+    assert_eq!(
+        transactions[0].postings[0]
+            .amount
+            .as_ref()
+            .unwrap()
+            .currency,
+        "h"
+    );
+    assert_eq!(
+        transactions[1].description.note,
+        Some("deployment".to_string())
+    );
+    assert_eq!(journal.transaction_location(2).map(|l| l.line), Some(5));
+}
+
+#[test]
+fn test_timeclock_transactions_round_trip() {
+    let mut input = "i 2024-03-01 09:00:00 client:acme  call\no 2024-03-01 09:20:00\n";
+    let journal = parse_timeclock(&mut input, None).unwrap();
+    let transactions = journal.transactions();
+
+    let posting = &transactions[0].postings[0];
+    assert_eq!(posting.kind, PostingKind::Virtual);
+    assert_eq!(posting.amount.as_ref().unwrap().value, dec!(1) / dec!(3));
+    assert!(journal.check(&[Check::BalancedNoAutoConversion]).is_empty());
+
+    let text = transactions[0].to_string();
+    let parsed = parse_journal(&mut text.as_str(), None).unwrap();
+    assert_eq!(parsed.transactions(), transactions);
+}
+
+#[rstest]
+#[case::unmatched_clock_in(
+    "i 2024-03-01 09:00:00 a\ni 2024-03-01 10:00:00 b\no 2024-03-01 11:00:00\n",
+    ValidationError::UnmatchedClockIn(Location { path: None, line: 1 })
+)]
+#[case::unmatched_clock_in_at_end(
+    "i 2024-03-01 09:00:00 a\no 2024-03-01 10:00:00\n\ni 2024-03-01 11:00:00 a\n",
+    ValidationError::UnmatchedClockIn(Location { path: None, line: 4 })
+)]
+#[case::unmatched_clock_out(
+    "i 2024-03-01 09:00:00 a\no 2024-03-01 10:00:00\no 2024-03-01 11:00:00\n",
+    ValidationError::UnmatchedClockOut(Location { path: None, line: 3 })
+)]
+#[case::clock_out_before_clock_in(
+    "i 2024-03-01 09:00:00 a\no 2024-03-01 08:00:00\n",
+    ValidationError::ClockOutBeforeClockIn(Location { path: None, line: 2 })
+)]
+fn test_parse_timeclock_errors(#[case] input: &str, #[case] expected: ValidationError) {
+    let mut input = input;
+    match parse_timeclock(&mut input, None) {
+        Err(HLParserError::Validation(error)) => {
+            assert_eq!(error.to_string(), expected.to_string())
+        }
+        r => panic!("Unexpected result {:?}", r),
+    }
+}
+
+#[test]
+fn test_include_timeclock() {
+    let dir = std::env::temp_dir().join(format!("hledger-parse-timeclock-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("work.log"),
+        "i 2024-03-01 09:00:00 acme  task\no 2024-03-01 10:30:00\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("hours.timeclock"),
+        "i 2024-03-02 09:00:00 acme\no 2024-03-02 10:00:00\n",
+    )
+    .unwrap();
+    let mut input = r#"alias acme = clients:acme
+include timeclock:work.log
+include hours.timeclock
+"#;
+    let journal = parse_journal(&mut input, Some(dir.clone())).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    let transactions = journal.transactions();
+    assert_eq!(transactions.len(), 2);
+    assert_eq!(transactions[0].postings[0].account, "clients:acme".into());
+    assert_eq!(
+        transactions[0].postings[0].amount.as_ref().unwrap().value,
+        dec!(1.5)
+    );
+    assert_eq!(
+        journal.transaction_location(1),
+        Some(&Location {
+            path: Some(dir.join("hours.timeclock")),
+            line: 1
+        })
+    );
+}
@@ -0,0 +1,184 @@
+use std::fmt::Display;
+
+use chrono::{Days, NaiveDateTime, NaiveTime};
+use rust_decimal::Decimal;
+
+use crate::{
+    account::types::Account,
+    amount::types::Amount,
+    description::types::Description,
+    journal::types::Location,
+    posting::types::{Posting, PostingKind},
+    status::types::Status,
+    transaction::types::Transaction,
+    HLParserError, ValidationError,
+};
+
+/// The kind of a timeclock entry
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum TimeclockCode {
+    /// `i`, the start of a session
+    ClockIn,
+    /// `o`, the end of a session
+    ClockOut,
+    /// `O`, the end of a session which is marked cleared
+    FinalClockOut,
+}
+
+impl Display for TimeclockCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TimeclockCode::ClockIn => write!(f, "i"),
+            TimeclockCode::ClockOut => write!(f, "o"),
+            TimeclockCode::FinalClockOut => write!(f, "O"),
+        }
+    }
+}
+
+/// A line of a timeclock file
+///
+/// # Example
+///
+/// ```
+/// use chrono::NaiveDate;
+/// use hledger_parse::{TimeclockCode, TimeclockEntry};
+///
+/// let entry = TimeclockEntry {
+///     code: TimeclockCode::ClockIn,
+///     datetime: NaiveDate::from_ymd_opt(2024, 3, 1)
+///         .unwrap()
+///         .and_hms_opt(9, 0, 0)
+///         .unwrap(),
+///     account: Some("client:acme".into()),
+///     description: Some("task".to_string()),
+/// };
+/// assert_eq!("i 2024-03-01 09:00:00 client:acme  task", format!("{}", entry));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TimeclockEntry {
+    /// Whether the entry clocks in or out
+    pub code: TimeclockCode,
+    /// The date and time of the entry
+    pub datetime: NaiveDateTime,
+    /// The account of the session, for clock-ins
+    pub account: Option<Account>,
+    /// The description of the session, for clock-ins
+    pub description: Option<String>,
+}
+
+impl Display for TimeclockEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {}",
+            self.code,
+            self.datetime.format("%Y-%m-%d %H:%M:%S")
+        )?;
+        if let Some(account) = &self.account {
+            write!(f, " {}", account)?;
+        }
+        if let Some(description) = &self.description {
+            write!(f, "  {}", description)?;
+        }
+        Ok(())
+    }
+}
+
+/// Converts timeclock entries to transactions, like hledger does
+///
+/// Each session, from a clock-in to the next clock-out, becomes one transaction per day, with a
+/// single virtual posting of the hours spent to the clock-in account, like hledger generates. The
+/// transactions are located at their clock-in.
+pub fn timeclock_transactions(
+    entries: &[(TimeclockEntry, Location)],
+) -> Result<Vec<(Transaction, Location)>, HLParserError> {
+    let mut transactions = vec![];
+    let mut clock_in: Option<&(TimeclockEntry, Location)> = None;
+
+    for entry in entries {
+        let (timeclock_entry, location) = entry;
+        match (timeclock_entry.code, clock_in) {
+            (TimeclockCode::ClockIn, None) => clock_in = Some(entry),
+            (TimeclockCode::ClockIn, Some((_, open_location))) => {
+                return Err(HLParserError::Validation(
+                    ValidationError::UnmatchedClockIn(open_location.clone()),
+                ))
+            }
+            (_, None) => {
+                return Err(HLParserError::Validation(
+                    ValidationError::UnmatchedClockOut(location.clone()),
+                ))
+            }
+            (code, Some((start, start_location))) => {
+                if timeclock_entry.datetime < start.datetime {
+                    return Err(HLParserError::Validation(
+                        ValidationError::ClockOutBeforeClockIn(location.clone()),
+                    ));
+                }
+                let status = match code {
+                    TimeclockCode::FinalClockOut => Status::Cleared,
+                    _ => Status::Unmarked,
+                };
+                for transaction in session_transactions(start, timeclock_entry.datetime, status) {
+                    transactions.push((transaction, start_location.clone()));
+                }
+                clock_in = None;
+            }
+        }
+    }
+
+    match clock_in {
+        Some((_, location)) => Err(HLParserError::Validation(
+            ValidationError::UnmatchedClockIn(location.clone()),
+        )),
+        None => Ok(transactions),
+    }
+}
+
+/// Returns the transactions of a session, split at midnight
+fn session_transactions(
+    clock_in: &TimeclockEntry,
+    end: NaiveDateTime,
+    status: Status,
+) -> Vec<Transaction> {
+    let mut transactions = vec![];
+    let mut start = clock_in.datetime;
+    while start < end {
+        let next_day = start
+            .date()
+            .checked_add_days(Days::new(1))
+            .expect("the day after a session should be a valid date")
+            .and_time(NaiveTime::MIN);
+        let segment_end = end.min(next_day);
+        let seconds = (segment_end - start).num_seconds();
+        transactions.push(Transaction {
+            primary_date: start.date(),
+            secondary_date: None,
+            status: status.clone(),
+            code: None,
+            description: Description {
+                payee: None,
+                note: clock_in.description.clone(),
+            },
+            postings: vec![Posting {
+                status: Status::Unmarked,
+                account: clock_in.account.clone().unwrap_or_else(|| "".into()),
+                kind: PostingKind::Virtual,
+                amount: Some(Amount {
+                    currency: "h".to_string(),
+                    value: Decimal::from(seconds) / Decimal::from(3600),
+                }),
+                unit_price: None,
+                total_price: None,
+                balance_assertion: None,
+                inclusive_assertion: false,
+            }],
+            tags: vec![],
+        });
+        start = segment_end;
+    }
+    transactions
+}
@@ -0,0 +1,229 @@
+use std::path::PathBuf;
+
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use winnow::{
+    ascii::{digit1, line_ending, space0, space1, till_line_ending},
+    combinator::{alt, eof, opt, preceded, repeat, terminated},
+    token::{one_of, take_till},
+    PResult, Parser,
+};
+
+use crate::{
+    comment::parsers::parse_line_comment,
+    date::parsers::parse_date_with_year,
+    journal::types::{Journal, Location, ParseState, Value},
+    transaction::types::Transaction,
+    utils::LineCounter,
+    HLParserError,
+};
+
+use super::types::TimedotEntry;
+
+/// Parses a timedot quantity into hours
+///
+/// A quantity is either dots, each worth a quarter of an hour and optionally grouped with
+/// spaces, or a number with an optional unit: `s`, `m`, `h` (the default), `d`, `w`, `mo` or `y`.
+///
+/// # Example
+///
+/// ```
+/// use hledger_parse::parse_timedot_quantity;
+/// use rust_decimal_macros::dec;
+///
+/// assert_eq!(dec!(1.50), parse_timedot_quantity(&mut ".... ..").unwrap());
+/// assert_eq!(dec!(0.5), parse_timedot_quantity(&mut "30m").unwrap());
+/// ```
+pub fn parse_timedot_quantity(input: &mut &str) -> PResult<Decimal> {
+    alt((parse_number_quantity, parse_dots)).parse_next(input)
+}
+
+fn parse_dots(input: &mut &str) -> PResult<Decimal> {
+    (
+        '.',
+        repeat(0.., alt(('.'.value(1), one_of([' ', '\t']).value(0)))),
+    )
+        .map(|(_, dots): (char, Vec<i64>)| {
+            Decimal::new(25, 2) * Decimal::from(1 + dots.iter().sum::<i64>())
+        })
+        .parse_next(input)
+}
+
+fn parse_number_quantity(input: &mut &str) -> PResult<Decimal> {
+    let number = (digit1, opt(('.', digit1)))
+        .take()
+        .parse_to::<Decimal>()
+        .parse_next(input)?;
+    let (multiplier, divisor): (i64, i64) = alt((
+        "s".value((1, 3600)),
+        "mo".value((24 * 30, 1)),
+        "m".value((1, 60)),
+        "h".value((1, 1)),
+        "d".value((24, 1)),
+        "w".value((24 * 7, 1)),
+        "y".value((24 * 365, 1)),
+        "".value((1, 1)),
+    ))
+    .parse_next(input)?;
+    Ok(match (multiplier, divisor) {
+        (1, 1) => number,
+        _ => number * Decimal::from(multiplier) / Decimal::from(divisor),
+    })
+}
+
+/// Parses the line starting a day, a date followed by an optional description
+fn parse_day_line<'s>(
+    default_year: Option<i32>,
+) -> impl FnMut(&mut &'s str) -> PResult<(NaiveDate, Option<String>)> {
+    move |input: &mut &'s str| {
+        let (date, _) = parse_date_with_year(default_year).parse_next(input)?;
+        let description = terminated(
+            opt(preceded(space1, till_line_ending)),
+            alt((line_ending, eof)),
+        )
+        .map(|d| d.map(str::trim).filter(|d| !d.is_empty()))
+        .parse_next(input)?;
+        Ok((date, description.map(str::to_string)))
+    }
+}
+
+/// Parses an entry line, an indented or unindented account followed by a quantity
+///
+/// The account and the quantity are separated by two spaces or a tab, or by a single space when
+/// the account is a single word. A comment starting with `;` may follow the quantity.
+fn parse_entry_line<'s>(input: &mut &'s str) -> PResult<(&'s str, Decimal)> {
+    let word = || take_till(1.., [' ', '\t', '\n', '\r']);
+    let (account, hours) = preceded(
+        space0,
+        alt((
+            (
+                (word(), repeat::<_, _, (), _, _>(0.., (' ', word()))).take(),
+                preceded((alt(("  ", "\t")), space0), parse_timedot_quantity),
+            ),
+            (word(), preceded(space1, parse_timedot_quantity)),
+        )),
+    )
+    .parse_next(input)?;
+    terminated(
+        (space0, opt(preceded(';', till_line_ending))),
+        alt((line_ending, eof)),
+    )
+    .parse_next(input)?;
+    Ok((account, hours))
+}
+
+fn parse_ignored_line(input: &mut &str) -> PResult<()> {
+    alt((
+        terminated(parse_line_comment, alt((line_ending, eof))).void(),
+        terminated(space0, alt((line_ending, eof))).void(),
+    ))
+    .parse_next(input)
+}
+
+#[derive(Clone)]
+enum Line<'s> {
+    Day(NaiveDate, Option<String>),
+    Entry(&'s str, Decimal),
+    Ignored,
+}
+
+/// Parses the contents of a timedot file into entries with their locations
+fn parse_timedot_contents(
+    input: &mut &str,
+    path: Option<PathBuf>,
+    default_year: Option<i32>,
+) -> Result<Vec<(TimedotEntry, Location)>, HLParserError> {
+    let mut lines = LineCounter::new(input);
+    let mut day: Option<(NaiveDate, Option<String>)> = None;
+    let mut entries = vec![];
+    while !input.is_empty() {
+        let line = lines.line(input);
+        let text = input.lines().next().unwrap_or_default();
+        let parsed = alt((
+            parse_day_line(default_year).map(|(date, description)| Line::Day(date, description)),
+            parse_ignored_line.value(Line::Ignored),
+            parse_entry_line.map(|(account, hours)| Line::Entry(account, hours)),
+        ))
+        .parse_next(input)
+        .map_err(|_| {
+            HLParserError::Parse(format!(
+                "line {}: expected a date or an account and a quantity: {}",
+                line, text
+            ))
+        })?;
+        match parsed {
+            Line::Day(date, description) => day = Some((date, description)),
+            Line::Entry(account, hours) => {
+                let (date, description) = day.clone().ok_or_else(|| {
+                    HLParserError::Parse(format!("line {}: entry before the first date", line))
+                })?;
+                entries.push((
+                    TimedotEntry {
+                        date,
+                        description,
+                        account: account.into(),
+                        hours,
+                    },
+                    Location {
+                        path: path.clone(),
+                        line,
+                    },
+                ));
+            }
+            Line::Ignored => {}
+        }
+    }
+    Ok(entries)
+}
+
+/// Parses a timedot file into a journal with one transaction per entry
+///
+/// `path` is the file the input was read from, if any, and is used in locations.
+///
+/// # Example
+///
+/// ```
+/// use hledger_parse::parse_timedot;
+///
+/// let mut input = r#"2024-03-01 sprint 12
+/// fos:hledger  .... ..
+/// client:acme  2.5h
+/// "#;
+/// let journal = parse_timedot(&mut input, None).unwrap();
+/// assert_eq!(
+///     "2024-03-01 * sprint 12\n   (fos:hledger)  1.50 h\n",
+///     format!("{}", journal.transactions()[0])
+/// );
+/// ```
+pub fn parse_timedot(input: &mut &str, path: Option<PathBuf>) -> Result<Journal, HLParserError> {
+    let (transactions, locations): (Vec<Transaction>, Vec<Location>) =
+        parse_timedot_contents(input, path, None)?
+            .into_iter()
+            .map(|(entry, location)| (entry.transaction(), location))
+            .unzip();
+    let mut journal = Journal::new(transactions, vec![], vec![], vec![]);
+    journal.set_transaction_locations(locations);
+    Ok(journal)
+}
+
+/// Reads a timedot file included by a journal
+///
+/// The journal parser state, e.g. account aliases and the default year, is applied to the
+/// transactions.
+pub fn read_timedot_from_path(
+    path: PathBuf,
+    state: &ParseState,
+    locations: &mut Vec<Location>,
+) -> Result<Vec<Value>, HLParserError> {
+    let contents = std::fs::read_to_string(&path).map_err(|e| HLParserError::IO(e.to_string()))?;
+    let mut input = &contents[..];
+    Ok(
+        parse_timedot_contents(&mut input, Some(path), state.default_year)?
+            .into_iter()
+            .map(|(entry, location)| {
+                locations.push(location);
+                Value::Transaction(state.apply_to_transaction(entry.transaction()))
+            })
+            .collect(),
+    )
+}
@@ -0,0 +1,163 @@
+use rstest::rstest;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+
+use crate::{
+    check::types::Check,
+    journal::{parsers::parse_journal, types::Location},
+    posting::types::PostingKind,
+    status::types::Status,
+    HLParserError,
+};
+
+use super::parsers::{parse_timedot, parse_timedot_quantity};
+
+#[rstest]
+#[case::dot(".", dec!(0.25))]
+#[case::grouped_dots(".... ..", dec!(1.5))]
+#[case::hours("2.5h", dec!(2.5))]
+#[case::bare_number("3", dec!(3))]
+#[case::minutes("30m", dec!(0.5))]
+#[case::seconds("90s", dec!(0.025))]
+#[case::days("1d", dec!(24))]
+#[case::weeks("1w", dec!(168))]
+#[case::months("1mo", dec!(720))]
+#[case::years("1y", dec!(8760))]
+fn test_parse_timedot_quantity(#[case] input: &str, #[case] expected: Decimal) {
+    let mut input = input;
+    assert_eq!(parse_timedot_quantity(&mut input).unwrap(), expected);
+    assert_eq!(input, "");
+}
+
+#[test]
+fn test_parse_timedot() {
+    let mut input = r#"# effort log
+2024-03-01 sprint 12
+fos:hledger  .... ..
+  client acme:support   2.5h  ; on call
+* org heading
+
+2024/03/02
+client acme:support  45m
+"#;
+    let journal = parse_timedot(&mut input, None).unwrap();
+    let transactions = journal.transactions();
+
+    assert_eq!(
+        transactions
+            .iter()
+            .map(|t| (
+                t.primary_date.to_string(),
+                t.postings[0].account.to_string(),
+                t.postings[0].amount.as_ref().unwrap().value,
+                t.description.note.clone()
+            ))
+            .collect::<Vec<_>>(),
+        vec![
+            (
+                "2024-03-01".to_string(),
+                "fos:hledger".to_string(),
+                dec!(1.5),
+                Some("sprint 12".to_string())
+            ),
+            (
+                "2024-03-01".to_string(),
+                "client acme:support".to_string(),
+                dec!(2.5),
+                Some("sprint 12".to_string())
+            ),
+            (
+                "2024-03-02".to_string(),
+                "client acme:support".to_string(),
+                dec!(0.75),
+                None
+            ),
+        ]
+    );
+    assert!(transactions.iter().all(|t| t.status == Status::Cleared));
+    assert_eq!(journal.transaction_location(2).map(|l| l.line), Some(8));
+}
+
+#[test]
+fn test_timedot_transactions_round_trip() {
+    let mut input = "2024-03-01\nfos:hledger  10s\n";
+    let journal = parse_timedot(&mut input, None).unwrap();
+    let transactions = journal.transactions();
+
+    let posting = &transactions[0].postings[0];
+    assert_eq!(posting.kind, PostingKind::Virtual);
+    assert_eq!(
+        posting.amount.as_ref().unwrap().value,
+        dec!(10) / dec!(3600)
+    );
+    assert!(journal.check(&[Check::BalancedNoAutoConversion]).is_empty());
+
+    let text = transactions[0].to_string();
+    let parsed = parse_journal(&mut text.as_str(), None).unwrap();
+    assert_eq!(parsed.transactions(), transactions);
+}
+
+#[rstest]
+#[case::minutes("client:acme 30m", dec!(0.5))]
+#[case::dots("client:acme .... ..", dec!(1.5))]
+#[case::comment("  client:acme 2h ; review", dec!(2))]
+fn test_parse_timedot_single_space(#[case] entry: &str, #[case] expected: Decimal) {
+    let input = format!("2024-03-01\n{}\n", entry);
+    let journal = parse_timedot(&mut input.as_str(), None).unwrap();
+    let posting = &journal.transactions()[0].postings[0];
+
+    assert_eq!(posting.account.to_string(), "client:acme");
+    assert_eq!(posting.amount.as_ref().unwrap().value, expected);
+}
+
+#[rstest]
+#[case::entry_before_date(
+    "fos:hledger  ..\n",
+    "Parse error: line 1: entry before the first date"
+)]
+#[case::missing_quantity(
+    "2024-03-01\nfos:hledger\n",
+    "Parse error: line 2: expected a date or an account and a quantity: fos:hledger"
+)]
+#[case::invalid_quantity(
+    "2024-03-01\nfos:hledger  lots\n",
+    "Parse error: line 2: expected a date or an account and a quantity: fos:hledger  lots"
+)]
+#[case::multi_word_account_single_space("2024-03-01\nclient acme 30m\n", "line 2")]
+fn test_parse_timedot_errors(#[case] input: &str, #[case] expected: &str) {
+    let mut input = input;
+    match parse_timedot(&mut input, None) {
+        Err(e @ HLParserError::Parse(_)) => assert!(e.to_string().contains(expected), "{}", e),
+        r => panic!("Unexpected result {:?}", r),
+    }
+}
+
+#[test]
+fn test_include_timedot() {
+    let dir = std::env::temp_dir().join(format!("hledger-parse-timedot-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("effort.timedot"), "03-01\nhledger  ....\n").unwrap();
+    let mut input = r#"Y 2024
+apply account fos
+include effort.timedot
+end apply account
+
+2024-03-01 lunch
+    expenses:food  10 EUR
+    assets:cash
+"#;
+    let journal = parse_journal(&mut input, Some(dir.clone())).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    let transactions = journal.transactions();
+    assert_eq!(transactions.len(), 2);
+    assert_eq!(transactions[0].primary_date.to_string(), "2024-03-01");
+    assert_eq!(transactions[0].postings[0].account, "fos:hledger".into());
+    assert_eq!(
+        journal.transaction_location(0),
+        Some(&Location {
+            path: Some(dir.join("effort.timedot")),
+            line: 2
+        })
+    );
+}
@@ -0,0 +1,76 @@
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+
+use crate::{
+    account::types::Account,
+    amount::types::Amount,
+    description::types::Description,
+    posting::types::{Posting, PostingKind},
+    status::types::Status,
+    transaction::types::Transaction,
+};
+
+/// An entry of a timedot file, the time spent on an account on a day
+///
+/// # Example
+///
+/// ```
+/// use chrono::NaiveDate;
+/// use hledger_parse::TimedotEntry;
+/// use rust_decimal_macros::dec;
+///
+/// let entry = TimedotEntry {
+///     date: NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(),
+///     description: Some("sprint 12".to_string()),
+///     account: "fos:hledger".into(),
+///     hours: dec!(1.50),
+/// };
+/// assert_eq!(
+///     "2024-03-01 * sprint 12\n   (fos:hledger)  1.50 h\n",
+///     format!("{}", entry.transaction())
+/// );
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TimedotEntry {
+    /// The date of the day the entry belongs to
+    pub date: NaiveDate,
+    /// The description following the date of the day, if any
+    pub description: Option<String>,
+    /// The account the time was spent on
+    pub account: Account,
+    /// The time spent, in hours
+    pub hours: Decimal,
+}
+
+impl TimedotEntry {
+    /// Converts the entry to a transaction, like hledger does
+    ///
+    /// The transaction is cleared and has a single virtual posting of the hours to the account.
+    pub fn transaction(&self) -> Transaction {
+        Transaction {
+            primary_date: self.date,
+            secondary_date: None,
+            status: Status::Cleared,
+            code: None,
+            description: Description {
+                payee: None,
+                note: self.description.clone(),
+            },
+            postings: vec![Posting {
+                status: Status::Unmarked,
+                account: self.account.clone(),
+                kind: PostingKind::Virtual,
+                amount: Some(Amount {
+                    currency: "h".to_string(),
+                    value: self.hours,
+                }),
+                unit_price: None,
+                total_price: None,
+                balance_assertion: None,
+                inclusive_assertion: false,
+            }],
+            tags: vec![],
+        }
+    }
+}
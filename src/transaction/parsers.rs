@@ -9,9 +9,10 @@ use winnow::{
 use crate::{
     code::parsers::parse_code,
     comment::parsers::parse_transaction_comment,
-    date::parsers::parse_date,
+    date::parsers::parse_date_with_year,
     description::parsers::parse_description,
-    posting::parsers::parse_posting,
+    journal::types::ParseState,
+    posting::parsers::parse_posting_with_state,
     status::parsers::parse_status,
     tag::{parsers::parse_tag, types::Tag},
     utils::find_space_before_char,
@@ -19,7 +20,7 @@ use crate::{
 
 use super::types::Transaction;
 
-pub(super) fn parse_comments_tags<'s>(input: &mut &'s str) -> PResult<(&'s str, Vec<Tag>)> {
+pub fn parse_comments_tags<'s>(input: &mut &'s str) -> PResult<(&'s str, Vec<Tag>)> {
     let mut line = till_line_ending.parse_next(input)?;
     let comment = match find_space_before_char(line, ':') {
         Some(pos) => take(pos + 1).parse_next(&mut line)?,
@@ -36,46 +37,62 @@ pub(super) fn parse_comments_tags<'s>(input: &mut &'s str) -> PResult<(&'s str,
     Ok((comment.trim(), tags))
 }
 
+/// Parses a transaction with the default journal parser state
+#[allow(dead_code)]
 pub fn parse_transaction(input: &mut &str) -> PResult<Transaction> {
-    let (primary_date, secondary_date) = terminated(parse_date, space0).parse_next(input)?;
-    let status = parse_status
-        .context(StrContext::Label("transaction status"))
-        .parse_next(input)?;
-    let code = opt(parse_code.context(StrContext::Label("transaction code"))).parse_next(input)?;
+    parse_transaction_with_state(&ParseState::default()).parse_next(input)
+}
 
-    let (description, comment_and_tags) = terminated(
-        (
-            parse_description.context(StrContext::Label("transaction description")),
-            opt(preceded(
-                space0,
-                parse_transaction_comment
-                    .and_then(parse_comments_tags)
-                    .context(StrContext::Label("transaction comment and tags")),
-            )),
-        ),
-        line_ending,
-    )
-    .parse_next(input)?;
+/// Parses a transaction, using the journal parser state for e.g. dates without a year
+pub fn parse_transaction_with_state<'s>(
+    state: &ParseState,
+) -> impl FnMut(&mut &'s str) -> PResult<Transaction> + '_ {
+    move |input: &mut &'s str| {
+        let (primary_date, secondary_date) =
+            terminated(parse_date_with_year(state.default_year), space0).parse_next(input)?;
+        let status = parse_status
+            .context(StrContext::Label("transaction status"))
+            .parse_next(input)?;
+        let code =
+            opt(parse_code.context(StrContext::Label("transaction code"))).parse_next(input)?;
 
-    let postings =
-        repeat(0.., terminated(parse_posting, alt((line_ending, eof)))).parse_next(input)?;
+        let (description, comment_and_tags) = terminated(
+            (
+                parse_description.context(StrContext::Label("transaction description")),
+                opt(preceded(
+                    space0,
+                    parse_transaction_comment
+                        .and_then(parse_comments_tags)
+                        .context(StrContext::Label("transaction comment and tags")),
+                )),
+            ),
+            line_ending,
+        )
+        .parse_next(input)?;
 
-    let transaction = Transaction {
-        primary_date,
-        secondary_date,
-        code: code.map(str::to_string),
-        status,
-        description,
-        tags: match comment_and_tags {
-            Some((_, tags)) => tags,
-            None => vec![],
-        },
-        postings,
-    };
+        let postings = repeat(
+            0..,
+            terminated(parse_posting_with_state(state), alt((line_ending, eof))),
+        )
+        .parse_next(input)?;
+
+        let transaction = Transaction {
+            primary_date,
+            secondary_date,
+            code: code.map(str::to_string),
+            status,
+            description,
+            tags: match comment_and_tags {
+                Some((_, tags)) => tags,
+                None => vec![],
+            },
+            postings,
+        };
 
-    transaction.validate().map_err(|e| {
-        ErrMode::from_external_error(input, winnow::error::ErrorKind::Verify, e).cut()
-    })?;
+        transaction.validate().map_err(|e| {
+            ErrMode::from_external_error(input, winnow::error::ErrorKind::Verify, e).cut()
+        })?;
 
-    Ok(transaction)
+        Ok(transaction)
+    }
 }
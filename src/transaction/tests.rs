@@ -2,8 +2,11 @@ use chrono::NaiveDate;
 use rust_decimal_macros::dec;
 
 use crate::{
-    amount::types::Amount, description::types::Description, posting::types::Posting,
-    status::types::Status, tag::types::Tag,
+    amount::types::Amount,
+    description::types::Description,
+    posting::types::{Posting, PostingKind},
+    status::types::Status,
+    tag::types::Tag,
 };
 
 use super::{
@@ -53,6 +56,7 @@ fn test_simple_transaction() {
             postings: vec![
                 Posting {
                     account: "assets:bank:checking".into(),
+                    kind: PostingKind::Regular,
                     status: Status::Unmarked,
                     amount: Some(Amount {
                         currency: "$".into(),
@@ -61,9 +65,11 @@ fn test_simple_transaction() {
                     unit_price: None,
                     total_price: None,
                     balance_assertion: None,
+                    inclusive_assertion: false,
                 },
                 Posting {
                     account: "income:salary".into(),
+                    kind: PostingKind::Regular,
                     status: Status::Unmarked,
                     amount: Some(Amount {
                         currency: "$".into(),
@@ -72,6 +78,7 @@ fn test_simple_transaction() {
                     unit_price: None,
                     total_price: None,
                     balance_assertion: None,
+                    inclusive_assertion: false,
                 },
             ],
         }
@@ -100,6 +107,7 @@ fn test_empty_description_cleared_transaction() {
             postings: vec![
                 Posting {
                     account: "assets:bank:checking".into(),
+                    kind: PostingKind::Regular,
                     status: Status::Unmarked,
                     amount: Some(Amount {
                         currency: "$".into(),
@@ -108,9 +116,11 @@ fn test_empty_description_cleared_transaction() {
                     unit_price: None,
                     total_price: None,
                     balance_assertion: None,
+                    inclusive_assertion: false,
                 },
                 Posting {
                     account: "income:salary".into(),
+                    kind: PostingKind::Regular,
                     status: Status::Unmarked,
                     amount: Some(Amount {
                         currency: "$".into(),
@@ -119,6 +129,7 @@ fn test_empty_description_cleared_transaction() {
                     unit_price: None,
                     total_price: None,
                     balance_assertion: None,
+                    inclusive_assertion: false,
                 },
             ],
         }
@@ -147,6 +158,7 @@ fn test_empty_description_unmarked_transaction() {
             postings: vec![
                 Posting {
                     account: "assets:bank:checking".into(),
+                    kind: PostingKind::Regular,
                     status: Status::Unmarked,
                     amount: Some(Amount {
                         currency: "$".into(),
@@ -155,9 +167,11 @@ fn test_empty_description_unmarked_transaction() {
                     unit_price: None,
                     total_price: None,
                     balance_assertion: None,
+                    inclusive_assertion: false,
                 },
                 Posting {
                     account: "income:salary".into(),
+                    kind: PostingKind::Regular,
                     status: Status::Unmarked,
                     amount: Some(Amount {
                         currency: "$".into(),
@@ -166,6 +180,7 @@ fn test_empty_description_unmarked_transaction() {
                     unit_price: None,
                     total_price: None,
                     balance_assertion: None,
+                    inclusive_assertion: false,
                 },
             ],
         }
@@ -197,6 +212,7 @@ fn test_transaction_ending_after_postings() {
             postings: vec![
                 Posting {
                     account: "assets:bank:checking".into(),
+                    kind: PostingKind::Regular,
                     status: Status::Unmarked,
                     amount: Some(Amount {
                         currency: "$".into(),
@@ -205,14 +221,17 @@ fn test_transaction_ending_after_postings() {
                     unit_price: None,
                     total_price: None,
                     balance_assertion: None,
+                    inclusive_assertion: false,
                 },
                 Posting {
                     account: "income:salary".into(),
+                    kind: PostingKind::Regular,
                     status: Status::Unmarked,
                     amount: None,
                     unit_price: None,
                     total_price: None,
                     balance_assertion: None,
+                    inclusive_assertion: false,
                 },
             ],
         }
@@ -244,6 +263,7 @@ fn test_simple_transaction_with_empty_amount_posting() {
             postings: vec![
                 Posting {
                     account: "assets:bank:checking".into(),
+                    kind: PostingKind::Regular,
                     status: Status::Unmarked,
                     amount: Some(Amount {
                         currency: "$".into(),
@@ -252,14 +272,17 @@ fn test_simple_transaction_with_empty_amount_posting() {
                     unit_price: None,
                     total_price: None,
                     balance_assertion: None,
+                    inclusive_assertion: false,
                 },
                 Posting {
                     account: "income:salary".into(),
+                    kind: PostingKind::Regular,
                     status: Status::Unmarked,
                     amount: None,
                     unit_price: None,
                     total_price: None,
                     balance_assertion: None,
+                    inclusive_assertion: false,
                 },
             ],
         }
@@ -288,6 +311,7 @@ fn test_transaction_with_code() {
             postings: vec![
                 Posting {
                     account: "assets:bank:checking".into(),
+                    kind: PostingKind::Regular,
                     status: Status::Unmarked,
                     amount: Some(Amount {
                         currency: "$".into(),
@@ -296,9 +320,11 @@ fn test_transaction_with_code() {
                     unit_price: None,
                     total_price: None,
                     balance_assertion: None,
+                    inclusive_assertion: false,
                 },
                 Posting {
                     account: "income:salary".into(),
+                    kind: PostingKind::Regular,
                     status: Status::Unmarked,
                     amount: Some(Amount {
                         currency: "$".into(),
@@ -307,6 +333,7 @@ fn test_transaction_with_code() {
                     unit_price: None,
                     total_price: None,
                     balance_assertion: None,
+                    inclusive_assertion: false,
                 },
             ],
         }
@@ -335,6 +362,7 @@ fn test_transaction_with_status() {
             postings: vec![
                 Posting {
                     account: "assets:bank:checking".into(),
+                    kind: PostingKind::Regular,
                     status: Status::Unmarked,
                     amount: Some(Amount {
                         currency: "$".into(),
@@ -343,9 +371,11 @@ fn test_transaction_with_status() {
                     unit_price: None,
                     total_price: None,
                     balance_assertion: None,
+                    inclusive_assertion: false,
                 },
                 Posting {
                     account: "income:salary".into(),
+                    kind: PostingKind::Regular,
                     status: Status::Unmarked,
                     amount: Some(Amount {
                         currency: "$".into(),
@@ -354,6 +384,7 @@ fn test_transaction_with_status() {
                     unit_price: None,
                     total_price: None,
                     balance_assertion: None,
+                    inclusive_assertion: false,
                 },
             ],
         }
@@ -382,6 +413,7 @@ fn test_transaction_no_description() {
             postings: vec![
                 Posting {
                     account: "assets:bank:checking".into(),
+                    kind: PostingKind::Regular,
                     status: Status::Unmarked,
                     amount: Some(Amount {
                         currency: "$".into(),
@@ -390,9 +422,11 @@ fn test_transaction_no_description() {
                     unit_price: None,
                     total_price: None,
                     balance_assertion: None,
+                    inclusive_assertion: false,
                 },
                 Posting {
                     account: "income:salary".into(),
+                    kind: PostingKind::Regular,
                     status: Status::Unmarked,
                     amount: Some(Amount {
                         currency: "$".into(),
@@ -401,6 +435,7 @@ fn test_transaction_no_description() {
                     unit_price: None,
                     total_price: None,
                     balance_assertion: None,
+                    inclusive_assertion: false,
                 },
             ],
         }
@@ -442,6 +477,7 @@ fn test_transaction_with_tags() {
             postings: vec![
                 Posting {
                     account: "assets:bank:checking".into(),
+                    kind: PostingKind::Regular,
                     status: Status::Unmarked,
                     amount: Some(Amount {
                         currency: "$".into(),
@@ -450,9 +486,11 @@ fn test_transaction_with_tags() {
                     unit_price: None,
                     total_price: None,
                     balance_assertion: None,
+                    inclusive_assertion: false,
                 },
                 Posting {
                     account: "income:salary".into(),
+                    kind: PostingKind::Regular,
                     status: Status::Unmarked,
                     amount: Some(Amount {
                         currency: "$".into(),
@@ -461,6 +499,7 @@ fn test_transaction_with_tags() {
                     unit_price: None,
                     total_price: None,
                     balance_assertion: None,
+                    inclusive_assertion: false,
                 },
             ],
         }
@@ -482,6 +521,7 @@ fn test_transaction_validate_none_amount_postings() {
         postings: vec![
             Posting {
                 account: "assets:bank:checking".into(),
+                kind: PostingKind::Regular,
                 amount: Some(Amount {
                     currency: "$".into(),
                     value: dec!(1),
@@ -490,14 +530,17 @@ fn test_transaction_validate_none_amount_postings() {
                 unit_price: None,
                 total_price: None,
                 balance_assertion: None,
+                inclusive_assertion: false,
             },
             Posting {
                 account: "income:salary".into(),
+                kind: PostingKind::Regular,
                 amount: None,
                 status: Status::Unmarked,
                 unit_price: None,
                 total_price: None,
                 balance_assertion: None,
+                inclusive_assertion: false,
             },
         ],
         tags: vec![],
@@ -520,6 +563,7 @@ fn test_transaction_validate_not_zero_sum_postings() {
         postings: vec![
             Posting {
                 account: "assets:bank:checking".into(),
+                kind: PostingKind::Regular,
                 amount: Some(Amount {
                     currency: "$".into(),
                     value: dec!(1),
@@ -528,9 +572,11 @@ fn test_transaction_validate_not_zero_sum_postings() {
                 unit_price: None,
                 total_price: None,
                 balance_assertion: None,
+                inclusive_assertion: false,
             },
             Posting {
                 account: "income:salary".into(),
+                kind: PostingKind::Regular,
                 amount: Some(Amount {
                     currency: "$".into(),
                     value: dec!(0),
@@ -539,6 +585,7 @@ fn test_transaction_validate_not_zero_sum_postings() {
                 unit_price: None,
                 total_price: None,
                 balance_assertion: None,
+                inclusive_assertion: false,
             },
         ],
         tags: vec![],
@@ -5,8 +5,13 @@ use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
 
 use crate::{
-    description::types::Description, journal::types::Value, posting::types::Posting,
-    status::types::Status, tag::types::Tag, HLParserError, ValidationError,
+    amount::types::MixedAmount,
+    description::types::Description,
+    journal::types::Value,
+    posting::types::{Posting, PostingKind},
+    status::types::Status,
+    tag::types::Tag,
+    HLParserError, ValidationError,
 };
 
 /// Transaction information
@@ -16,7 +21,7 @@ use crate::{
 /// ```
 /// use rust_decimal_macros::dec;
 /// use chrono::NaiveDate;
-/// use hledger_parse::{Amount, Description, Posting, Status, Tag, Transaction};
+/// use hledger_parse::{Amount, Description, Posting, PostingKind, Status, Tag, Transaction};
 ///
 /// let transaction = Transaction {
 ///     primary_date: NaiveDate::from_ymd_opt(2022, 6, 23).unwrap(),
@@ -31,6 +36,7 @@ use crate::{
 ///         Posting {
 ///             status: Status::Unmarked,
 ///             account: "assets:cash".into(),
+///             kind: PostingKind::Regular,
 ///             amount: Some(Amount {
 ///                 currency: "EUR".into(),
 ///                 value: dec!(-5),
@@ -38,10 +44,12 @@ use crate::{
 ///             unit_price: None,
 ///             total_price: None,
 ///             balance_assertion: None,
+///             inclusive_assertion: false,
 ///         },
 ///         Posting {
 ///             status: Status::Unmarked,
 ///             account: "expenses:bars".into(),
+///             kind: PostingKind::Regular,
 ///             amount: Some(Amount {
 ///                 currency: "EUR".into(),
 ///                 value: dec!(5),
@@ -49,6 +57,7 @@ use crate::{
 ///             unit_price: None,
 ///             total_price: None,
 ///             balance_assertion: None,
+///             inclusive_assertion: false,
 ///         },
 ///     ],
 ///     tags: vec![
@@ -67,7 +76,8 @@ use crate::{
 ///    expenses:bars  5 EUR
 /// "#, format!("{}", transaction));
 /// ```
-#[derive(PartialEq, Eq, Debug, Clone)]
+#[derive(PartialEq, Eq, Hash, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Transaction {
     /// The primary date of the transaction
     pub primary_date: NaiveDate,
@@ -129,44 +139,79 @@ impl Display for Transaction {
 }
 
 impl Transaction {
+    /// Returns the payee, which is the description if there is no separate payee
+    pub fn payee(&self) -> Option<&str> {
+        self.description
+            .payee
+            .as_deref()
+            .or(self.description.note.as_deref())
+    }
+
+    /// Returns the amount of each posting, in posting order
+    ///
+    /// A posting with a missing amount gets the amount that balances the other postings of its
+    /// kind, and a virtual posting with a missing amount gets no amount.
+    pub fn posting_amounts(&self) -> Vec<MixedAmount> {
+        let balancing = |kind: PostingKind| {
+            let mut balancing = MixedAmount::default();
+            for p in self.postings.iter().filter(|p| p.kind == kind) {
+                if let Some(cost) = p.cost() {
+                    balancing.add_amount(&cost);
+                }
+            }
+            balancing.negate()
+        };
+
+        self.postings
+            .iter()
+            .map(|p| match (&p.amount, p.kind) {
+                (Some(amount), _) => amount.clone().into(),
+                (None, PostingKind::Virtual) => MixedAmount::default(),
+                (None, kind) => balancing(kind),
+            })
+            .collect()
+    }
+
     pub fn validate(&self) -> Result<(), ValidationError> {
         self.validate_postings()?;
         Ok(())
     }
 
     fn validate_postings(&self) -> Result<(), ValidationError> {
-        let none_amounts = self.postings.iter().filter(|p| p.amount.is_none()).count();
+        for kind in PostingKind::BALANCED {
+            let postings: Vec<&Posting> = self.postings.iter().filter(|p| p.kind == kind).collect();
+            let none_amounts = postings.iter().filter(|p| p.amount.is_none()).count();
 
-        if none_amounts > 1_usize {
-            return Err(ValidationError::TransactionWithMissingAmountPostings(
-                self.clone(),
-            ));
-        }
+            if none_amounts > 1_usize {
+                return Err(ValidationError::TransactionWithMissingAmountPostings(
+                    self.clone(),
+                ));
+            }
 
-        if none_amounts == 1_usize {
-            return Ok(());
-        }
+            if none_amounts == 1_usize {
+                continue;
+            }
 
-        let postings_sum = self
-            .postings
-            .iter()
-            .flat_map(|p| match &p.total_price {
-                Some(v) => match &p
-                    .amount
-                    .as_ref()
-                    .expect("Amount should be present")
-                    .is_negative()
-                {
-                    false => Some(v.clone()),
-                    true => Some(v.negate()),
-                },
-                None => p.amount.clone(),
-            })
-            .map(|a| a.value) // TODO: different currencies, conversion rates
-            .sum::<Decimal>();
+            let postings_sum = postings
+                .iter()
+                .flat_map(|p| match &p.total_price {
+                    Some(v) => match &p
+                        .amount
+                        .as_ref()
+                        .expect("Amount should be present")
+                        .is_negative()
+                    {
+                        false => Some(v.clone()),
+                        true => Some(v.negate()),
+                    },
+                    None => p.amount.clone(),
+                })
+                .map(|a| a.value) // TODO: different currencies, conversion rates
+                .sum::<Decimal>();
 
-        if postings_sum != dec!(0) {
-            return Err(ValidationError::NonZeroSumTransactionPostings(self.clone()));
+            if postings_sum != dec!(0) {
+                return Err(ValidationError::NonZeroSumTransactionPostings(self.clone()));
+            }
         }
 
         Ok(())
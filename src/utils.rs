@@ -1,3 +1,5 @@
+use std::path::{Component, Path, PathBuf};
+
 use winnow::{
     ascii::{dec_int, dec_uint, space0},
     combinator::{alt, delimited, preceded, terminated},
@@ -50,6 +52,150 @@ pub fn deci32_leading_zeros(input: &mut &str) -> PResult<i32> {
     .parse_next(input)
 }
 
+/// Returns true if a path contains the `*` or `?` wildcards of glob patterns
+pub fn is_glob_pattern(path: &str) -> bool {
+    path.contains(['*', '?'])
+}
+
+/// Returns true if a file name matches a glob pattern component
+///
+/// `*` matches any sequence of characters and `?` matches a single character.
+pub fn glob_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    // matches[j] is true if the pattern read so far matches the first j characters of the name
+    let mut matches = vec![false; name.len() + 1];
+    matches[0] = true;
+    for p in pattern {
+        let previous = matches.clone();
+        match p {
+            '*' => {
+                for j in 1..=name.len() {
+                    matches[j] = matches[j] || matches[j - 1];
+                }
+            }
+            _ => {
+                matches[0] = false;
+                for j in 1..=name.len() {
+                    matches[j] = previous[j - 1] && (p == '?' || p == name[j - 1]);
+                }
+            }
+        }
+    }
+    matches[name.len()]
+}
+
+/// Returns the files matching a glob pattern, sorted by path
+///
+/// Besides the wildcards of [`glob_match`], a `**` component matches any number of directories.
+/// Hidden directories are not searched by `**`.
+pub fn expand_glob(pattern: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut candidates = vec![PathBuf::new()];
+    for component in pattern.components() {
+        let component_str = component.as_os_str().to_string_lossy();
+        candidates = match component {
+            Component::Normal(_) if component_str == "**" => {
+                let mut directories = vec![];
+                for candidate in candidates {
+                    subdirectories(&candidate, &mut directories)?;
+                }
+                directories
+            }
+            Component::Normal(_) if is_glob_pattern(&component_str) => {
+                let mut matching = vec![];
+                for candidate in candidates.iter().filter(|c| c.is_dir()) {
+                    for entry in std::fs::read_dir(candidate)? {
+                        let entry = entry?;
+                        if glob_match(&component_str, &entry.file_name().to_string_lossy()) {
+                            matching.push(entry.path());
+                        }
+                    }
+                }
+                matching
+            }
+            _ => candidates.into_iter().map(|c| c.join(component)).collect(),
+        };
+    }
+
+    let mut files: Vec<PathBuf> = candidates.into_iter().filter(|c| c.is_file()).collect();
+    files.sort();
+    files.dedup();
+    Ok(files)
+}
+
+/// Adds a directory and all its non-hidden subdirectories, recursively
+fn subdirectories(directory: &Path, directories: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    if !directory.is_dir() {
+        return Ok(());
+    }
+    directories.push(directory.to_path_buf());
+    for entry in std::fs::read_dir(directory)? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() && !entry.file_name().to_string_lossy().starts_with('.') {
+            subdirectories(&entry.path(), directories)?;
+        }
+    }
+    Ok(())
+}
+
+/// Replaces a leading `~` with the home directory of the user
+pub fn expand_home(path: &str) -> Option<PathBuf> {
+    match path.strip_prefix('~') {
+        Some(rest) if rest.is_empty() || rest.starts_with(['/', '\\']) => {
+            let home = std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE"))?;
+            Some(PathBuf::from(home).join(rest.trim_start_matches(['/', '\\'])))
+        }
+        _ => Some(PathBuf::from(path)),
+    }
+}
+
+/// Tracks the line number of the input of a parser as it is consumed
+///
+/// Only the text consumed since the previous call is scanned for newlines, so following a whole
+/// file stays linear in its size.
+pub struct LineCounter<'s> {
+    line: usize,
+    remaining: &'s str,
+}
+
+impl<'s> LineCounter<'s> {
+    pub fn new(contents: &'s str) -> LineCounter<'s> {
+        LineCounter {
+            line: 1,
+            remaining: contents,
+        }
+    }
+
+    /// Returns the line number at the start of `input`, which must be a suffix of the input
+    /// given to the previous call
+    pub fn line(&mut self, input: &str) -> usize {
+        let consumed = self.remaining.len() - input.len();
+        self.line += self.remaining[..consumed].matches('\n').count();
+        self.remaining = &self.remaining[consumed..];
+        self.line
+    }
+}
+
+/// Serializes a regex as its pattern, compiling it case-insensitively on deserialization like
+/// every regex parsed from a journal or rules file
+#[cfg(feature = "serde")]
+pub mod serde_regex {
+    use regex::{Regex, RegexBuilder};
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(regex: &Regex, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(regex.as_str())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Regex, D::Error> {
+        let pattern = String::deserialize(deserializer)?;
+        RegexBuilder::new(&pattern)
+            .case_insensitive(true)
+            .build()
+            .map_err(D::Error::custom)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use rstest::rstest;
@@ -108,4 +254,67 @@ mod tests {
         assert_eq!(deci32_leading_zeros(&mut input), expected);
         assert_eq!(input, "");
     }
+
+    #[rstest]
+    #[case::literal("a.journal", "a.journal", true)]
+    #[case::star("*.journal", "2024.journal", true)]
+    #[case::star_empty("*.journal", ".journal", true)]
+    #[case::star_other_extension("*.journal", "2024.prices", false)]
+    #[case::question_mark("202?.journal", "2024.journal", true)]
+    #[case::question_mark_too_short("202?.journal", "202.journal", false)]
+    #[case::several_stars("*-*.csv", "bank-2024.csv", true)]
+    fn test_glob_match(#[case] pattern: &str, #[case] name: &str, #[case] expected: bool) {
+        assert_eq!(glob_match(pattern, name), expected);
+    }
+
+    #[test]
+    fn test_expand_glob() {
+        let dir = std::env::temp_dir().join(format!("hledger-parse-glob-{}", std::process::id()));
+        for file in [
+            "2024/b.journal",
+            "2024/a.journal",
+            "2024/notes.txt",
+            "2023/q1/x.prices",
+            "y.prices",
+            ".git/z.prices",
+        ] {
+            std::fs::create_dir_all(dir.join(file).parent().unwrap()).unwrap();
+            std::fs::write(dir.join(file), "").unwrap();
+        }
+
+        let journals = expand_glob(&dir.join("2024/*.journal")).unwrap();
+        let prices = expand_glob(&dir.join("**/*.prices")).unwrap();
+        let missing = expand_glob(&dir.join("2025/*.journal")).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(
+            journals,
+            vec![dir.join("2024/a.journal"), dir.join("2024/b.journal")]
+        );
+        assert_eq!(
+            prices,
+            vec![dir.join("2023/q1/x.prices"), dir.join("y.prices")]
+        );
+        assert!(missing.is_empty());
+    }
+
+    #[test]
+    fn test_expand_home() {
+        let home = PathBuf::from(std::env::var_os("HOME").unwrap());
+        assert_eq!(expand_home("~/a.journal"), Some(home.join("a.journal")));
+        assert_eq!(expand_home("~"), Some(home));
+        assert_eq!(expand_home("a/~b"), Some(PathBuf::from("a/~b")));
+    }
+
+    #[test]
+    fn test_line_counter() {
+        let contents = "a\nb\n\nc";
+        let mut lines = LineCounter::new(contents);
+        assert_eq!(lines.line(contents), 1);
+        assert_eq!(lines.line(&contents[1..]), 1);
+        assert_eq!(lines.line(&contents[2..]), 2);
+        assert_eq!(lines.line(&contents[2..]), 2);
+        assert_eq!(lines.line(&contents[5..]), 4);
+        assert_eq!(lines.line(""), 4);
+    }
 }